@@ -0,0 +1,194 @@
+use std::env::var;
+use std::fs::{File, read_dir, read_to_string};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use super::config::structure::ThemeColor;
+use super::squashfs::SquashFs;
+
+const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+// AppImages of type 2 carry this after the ELF magic, type 1 ones are ISO 9660 images which are not supported
+const APPIMAGE_TYPE2_MAGIC: &[u8; 3] = b"AI\x02";
+const ELF_HEADER_SIZE: usize = 64;
+const ICON_EXTENSIONS: [&str; 2] = ["png", "svg"];
+// sizes in the hicolor icon theme, the ones closest to the icon size first
+const HICOLOR_SIZES: [&str; 8] = ["256x256", "512x512", "scalable", "128x128", "96x96", "64x64", "48x48", "32x32"];
+const MAX_SHEBANG_LEN: u64 = 256;
+
+fn read_header<P: AsRef<Path>>(path: P, len: usize) -> Option<Vec<u8>> {
+    let mut header: Vec<u8> = Vec::with_capacity(len);
+    File::open(path).ok()?.take(len as u64).read_to_end(&mut header).ok()?;
+    Some(header)
+}
+
+pub fn is_appimage(header: &[u8]) -> bool {
+    header.starts_with(ELF_MAGIC) && header.get(8..11) == Some(APPIMAGE_TYPE2_MAGIC)
+}
+
+// the end of the section headers of an ELF file, where anything appended to it starts,
+// like the squashfs image after the runtime of an AppImage
+pub fn elf_end_offset(header: &[u8]) -> Option<u64> {
+    let little_endian = match header.get(5)? { 1 => true, 2 => false, _ => return None };
+    let u16_at = |pos: usize| header.get(pos..pos + 2)?.try_into().ok()
+        .map(|bytes| if little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) } as u64);
+    let u32_at = |pos: usize| header.get(pos..pos + 4)?.try_into().ok()
+        .map(|bytes| if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) } as u64);
+    let u64_at = |pos: usize| header.get(pos..pos + 8)?.try_into().ok()
+        .map(|bytes| if little_endian { u64::from_le_bytes(bytes) } else { u64::from_be_bytes(bytes) });
+    let (section_offset, entry_size, entry_count) = match header.get(4)? {
+        1 => (u32_at(0x20)?, u16_at(0x2E)?, u16_at(0x30)?),
+        2 => (u64_at(0x28)?, u16_at(0x3A)?, u16_at(0x3C)?),
+        _ => return None
+    };
+    section_offset.checked_add(entry_size * entry_count)
+}
+
+// the value of the key in the [Desktop Entry] group of a desktop file, localized keys are not looked at
+pub fn desktop_entry_value(text: &str, key: &str) -> Option<String> {
+    let mut in_entry_group = false;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry_group || line.starts_with('#') {
+            continue;
+        }
+        if let Some((line_key, value)) = line.split_once('=') && line_key.trim() == key {
+            return Some(value.trim().to_string());
+        }
+    }
+    None
+}
+
+// the program of an Exec value, without its quotes and arguments
+fn desktop_exec_program(exec: &str) -> Option<String> {
+    let exec = exec.trim();
+    let program = match exec.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next()?,
+        None => exec.split_whitespace().next()?
+    };
+    (!program.is_empty()).then(|| program.to_string())
+}
+
+// paths where an icon of the name can be, relative to the base directory and the data directories like /usr/share
+fn icon_paths(name: &str, base_dir: &str, data_dirs: &[String]) -> Vec<String> {
+    let mut dirs = vec![base_dir.to_string()];
+    for data_dir in data_dirs {
+        dirs.extend(HICOLOR_SIZES.iter().map(|size| format!("{}/icons/hicolor/{}/apps", data_dir, size)));
+        dirs.push(format!("{}/pixmaps", data_dir));
+    }
+    let has_extension = Path::new(name).extension()
+        .is_some_and(|ext| ICON_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()));
+    dirs.iter().flat_map(|dir| {
+        if has_extension {
+            vec![format!("{}/{}", dir, name)]
+        } else {
+            ICON_EXTENSIONS.iter().map(|ext| format!("{}/{}.{}", dir, name, ext)).collect()
+        }
+    }).collect()
+}
+
+// the icon of a type 2 AppImage, its .DirIcon or else the icon named in its desktop file, with the path of the icon in the image
+pub fn appimage_icon<P: AsRef<Path>>(path: P) -> Option<(Vec<u8>, String)> {
+    let header = read_header(&path, ELF_HEADER_SIZE)?;
+    if !is_appimage(&header) {
+        return None;
+    }
+    let mut image = SquashFs::new(BufReader::new(File::open(&path).ok()?), elf_end_offset(&header)?).ok()?;
+    if let Ok(icon_data) = image.read_file(".DirIcon") {
+        return Some((icon_data, String::from(".DirIcon")));
+    }
+    let desktop_file = image.list_dir("").ok()?.into_iter().find(|name| name.ends_with(".desktop"))?;
+    let desktop_entry = String::from_utf8(image.read_file(&desktop_file).ok()?).ok()?;
+    let icon_name = desktop_entry_value(&desktop_entry, "Icon")?;
+    icon_paths(&icon_name, "", &[String::from("usr/share")]).into_iter()
+        .find_map(|icon_path| image.read_file(&icon_path).ok().map(|icon_data| (icon_data, icon_path)))
+}
+
+// the directories the icon themes are in, following the xdg base directory specification
+fn data_dirs() -> Vec<String> {
+    let data_home = var("XDG_DATA_HOME").ok().filter(|dir| !dir.is_empty())
+        .or_else(|| var("HOME").ok().map(|home| format!("{}/.local/share", home)));
+    let system_dirs = var("XDG_DATA_DIRS").ok().filter(|dirs| !dirs.is_empty())
+        .unwrap_or(String::from("/usr/local/share:/usr/share"));
+    data_home.into_iter().chain(system_dirs.split(':').map(String::from)).collect()
+}
+
+// the icon named by a desktop file next to the app, one with the same name as the app or one that runs it
+pub fn desktop_entry_icon<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
+    let path = path.as_ref();
+    let dir = path.parent()?;
+    let file_name = path.file_name()?;
+    let stem = path.file_stem()?;
+    let icon_name = read_dir(dir).ok()?.filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|entry_path| entry_path.extension().is_some_and(|ext| ext == "desktop"))
+        .filter_map(|entry_path| Some((entry_path.file_stem()? == stem, read_to_string(&entry_path).ok()?)))
+        .filter(|(same_name, desktop_entry)| *same_name || desktop_entry_value(desktop_entry, "Exec")
+            .and_then(|exec| desktop_exec_program(&exec))
+            .is_some_and(|program| Path::new(&program).file_name() == Some(file_name)))
+        // a desktop file of the same name wins over one that only runs the app
+        .max_by_key(|(same_name, _)| *same_name)
+        .and_then(|(_, desktop_entry)| desktop_entry_value(&desktop_entry, "Icon"))?;
+    if Path::new(&icon_name).is_absolute() {
+        return Some(PathBuf::from(icon_name)).filter(|icon_path| icon_path.is_file());
+    }
+    icon_paths(&icon_name, &dir.to_string_lossy(), &data_dirs()).into_iter()
+        .map(PathBuf::from)
+        .find(|icon_path| icon_path.is_file())
+}
+
+// the interpreter of a shebang line, without its path and version, e.g. "python" for "#!/usr/bin/env python3.12"
+pub fn shebang_interpreter(line: &str) -> Option<String> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let mut program = Path::new(words.next()?).file_name()?.to_string_lossy().to_string();
+    if program == "env" {
+        // the options of env, like -S, come before the program
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?.to_string();
+    }
+    let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+    (!name.is_empty()).then(|| name.to_lowercase())
+}
+
+// the interpreter a script runs with, by its shebang or else by its extension
+pub fn script_interpreter<P: AsRef<Path>>(path: P) -> Option<String> {
+    let path = path.as_ref();
+    let mut first_line = String::new();
+    BufReader::new(File::open(path).ok()?.take(MAX_SHEBANG_LEN)).read_line(&mut first_line).ok()?;
+    if let Some(interpreter) = shebang_interpreter(&first_line) {
+        return Some(interpreter);
+    }
+    let interpreter = match path.extension()?.to_string_lossy().to_lowercase().as_str() {
+        "py" | "pyw" => "python",
+        "sh" => "sh",
+        "bash" => "bash",
+        "js" | "mjs" => "node",
+        "pl" => "perl",
+        "rb" => "ruby",
+        "php" => "php",
+        "lua" => "lua",
+        "ps1" => "pwsh",
+        _ => return None
+    };
+    Some(interpreter.to_string())
+}
+
+// the text and color of the badge of scripts of the interpreter, the ones not known get their first letters in gray
+pub fn interpreter_badge(interpreter: &str) -> (String, ThemeColor) {
+    let (label, (r, g, b)) = match interpreter {
+        "python" | "pypy" => ("PY", (0x37, 0x76, 0xAB)),
+        "sh" | "bash" | "zsh" | "dash" | "ksh" | "ash" | "fish" => ("SH", (0x4E, 0xAA, 0x25)),
+        "node" | "nodejs" | "deno" | "bun" => ("JS", (0xF7, 0xDF, 0x1E)),
+        "perl" => ("PL", (0x39, 0x45, 0x7E)),
+        "ruby" => ("RB", (0xCC, 0x34, 0x2D)),
+        "php" => ("PHP", (0x77, 0x7B, 0xB4)),
+        "lua" => ("LUA", (0x00, 0x00, 0x80)),
+        "pwsh" | "powershell" => ("PS", (0x01, 0x24, 0x56)),
+        _ => {
+            let label: String = interpreter.chars().filter(char::is_ascii_alphanumeric).take(2).collect();
+            let label = if label.is_empty() { String::from("#") } else { label.to_ascii_uppercase() };
+            return (label, ThemeColor::RGB { r: 0x60, g: 0x7D, b: 0x8B });
+        }
+    };
+    (label.to_string(), ThemeColor::RGB { r, g, b })
+}
@@ -1,11 +1,22 @@
 use std::sync::Mutex;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf, absolute};
 use std::process::Command;
+use std::fs::{read as fs_read, write as fs_write};
 use std::env::current_dir;
 use serde::{Serialize, Deserialize};
 use tauri::{command, State};
-use super::config::{Config, structure::{AppMetadata, ToolboxVersion, Theme}};
+use super::config::{Config, FAVORITES_CATEGORY, active_config_path, diff::{ConfigDiff, diff_configs}, history::{EditHistory, EditHistoryState}, merge::{MergeReport, merge_configs}, sync::ConfigSync, structure::{AppKind, AppMetadata, AppSnapshot, IconSettings, CategoryTreeNode, IntegrityPolicy, LaunchGroup, LaunchHooks, LaunchPolicy, LaunchStep, SmartCategoryMetadata, SmartCategoryQuery, ToolboxVersion, Theme, VersionSource}};
+use super::integrity::{IntegrityManifest, IntegrityStatus};
+use super::launcher::{self, INTEGRITY_MANIFEST_PATH, LaunchStatus, LaunchStepResult};
+use super::process::ProcessTracker;
+use super::logs::{self, APP_LOG_DIR, AppLogInfo, DEFAULT_LOG_TAIL_BYTES, list_app_logs, read_log_tail};
+use super::search::{self, SearchMatch};
+use super::theme::*;
+use super::signature::*;
 use super::util::*;
+use super::version_info::{lang_id_of, read_version_info};
+use super::versions::{AppVersionChange, apply_version_scans, scan_app_files};
 
 // corresponding to the AppMetadata interface in types.ts
 #[derive(Serialize, Deserialize)]
@@ -16,7 +27,27 @@ pub struct AppMetadataWithName {
     pub launch_args: String,
     pub working_dir: PathBuf,
     pub desc: String,
-    pub icon_url: String
+    pub icon_url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub hooks: LaunchHooks,
+    #[serde(default)]
+    pub capture_output: bool,
+    #[serde(default)]
+    pub launch_policy: LaunchPolicy,
+    #[serde(default)]
+    pub kind: AppKind,
+    #[serde(default)]
+    pub generated_icon: bool,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub version_source: VersionSource,
+    #[serde(default)]
+    pub publisher: String,
+    #[serde(default)]
+    pub snapshot: Option<AppSnapshot>
 }
 
 impl From<&AppMetadata> for AppMetadataWithName {
@@ -27,7 +58,17 @@ impl From<&AppMetadata> for AppMetadataWithName {
             launch_args: metadata.launch_args.clone(),
             working_dir: metadata.working_dir.clone(),
             desc: metadata.desc.clone(),
-            icon_url: metadata.icon_url.clone()
+            icon_url: metadata.icon_url.clone(),
+            tags: metadata.tags.clone(),
+            hooks: metadata.hooks.clone(),
+            capture_output: metadata.capture_output,
+            launch_policy: metadata.launch_policy.clone(),
+            kind: metadata.kind.clone(),
+            generated_icon: metadata.generated_icon,
+            version: metadata.version.clone(),
+            version_source: metadata.version_source.clone(),
+            publisher: metadata.publisher.clone(),
+            snapshot: metadata.snapshot.clone()
         }
     }
 }
@@ -40,7 +81,9 @@ pub struct ConfigBasicInfo {
     pub header_text: String,
     pub author: Option<String>,
     pub toolbox_version: Option<ToolboxVersion>,
-    pub theme: Theme
+    pub theme: Theme,
+    #[serde(default)]
+    pub dark_theme: Option<Theme>
 }
 
 impl From<&Config> for ConfigBasicInfo {
@@ -50,25 +93,98 @@ impl From<&Config> for ConfigBasicInfo {
             header_text: config.header_text.clone(),
             author: config.author.clone(),
             toolbox_version: config.toolbox_version.clone(),
-            theme: config.theme.clone()
+            theme: config.theme.clone(),
+            dark_theme: config.dark_theme.clone()
         }
     }
 }
 
+// corresponding to the AppSearchResult interface in types.ts
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSearchResult {
+    pub app: AppMetadataWithName,
+    pub matches: Vec<SearchMatch>
+}
+
+// generate an icon for the app if it has none, or generate it again if it was generated as the name may have changed
+fn with_fallback_icon(config: &Config, app_name: &str, metadata: AppMetadata) -> AppMetadata {
+    if !metadata.icon_url.is_empty() && !metadata.generated_icon {
+        return metadata;
+    }
+    match fallback_icon_url(app_name, config.get_theme(false), &config.icon_settings) {
+        Ok(icon_url) => AppMetadata { icon_url, generated_icon: true, ..metadata },
+        Err(_) => metadata
+    }
+}
+
+// remember the config before a successful edit, so that the edit can be undone
+fn record_edit<T, E>(history_state: &State<Mutex<EditHistory>>, snapshot: Config, result: Result<T, E>) -> bool {
+    if result.is_ok() {
+        history_state.lock().unwrap().record(snapshot);
+    }
+    result.is_ok()
+}
+
 #[command]
-pub fn load_config(config_state: State<Mutex<Config>>) -> bool {
+pub fn load_config(config_state: State<Mutex<Config>>, verifier_state: State<Mutex<ConfigVerifier>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>) -> bool {
     let mut config = config_state.lock().unwrap();
-    if Path::new("config.json").is_file() {
-        Config::from_file("config.json").and_then(|c| {
+    let mut sync = sync_state.lock().unwrap();
+    history_state.lock().unwrap().clear();
+    verifier_state.lock().unwrap().invalidate();
+    let config_path = active_config_path();
+    if Path::new(config_path).is_file() {
+        sync.load(config_path).map(|c| {
             *config = c;
-            Ok(())
         }).is_ok()
     } else {
         *config = Config::new();
-        config.to_file("config.json").is_ok()
+        sync.force_save(&config, config_path).is_ok()
+    }
+}
+
+// resolve a conflict between the edited config and the one modified externally
+#[command]
+pub fn resolve_config_conflict(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, keep_external: bool) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let mut sync = sync_state.lock().unwrap();
+    if keep_external {
+        sync.load(active_config_path()).map(|c| {
+            history_state.lock().unwrap().record(std::mem::replace(&mut *config, c));
+        }).is_ok()
+    } else {
+        sync.force_save(&config, active_config_path()).is_ok()
     }
 }
 
+#[command]
+pub fn has_config_conflict(sync_state: State<Mutex<ConfigSync>>) -> bool {
+    sync_state.lock().unwrap().has_conflict()
+}
+
+#[command]
+pub fn get_config_signature_status(config_state: State<Mutex<Config>>, verifier_state: State<Mutex<ConfigVerifier>>) -> SignatureStatus {
+    let config = config_state.lock().unwrap();
+    verifier_state.lock().unwrap().status(active_config_path(), &config)
+}
+
+#[command]
+pub fn sign_config(config_state: State<Mutex<Config>>, key_path: String, include_app_hashes: bool) -> bool {
+    let config = config_state.lock().unwrap();
+    // the new signature is verified on the next status request, as the signature file changed
+    signing_key_from_file(key_path).and_then(|signing_key| {
+        ConfigSignature::sign(active_config_path(), &signing_key, include_app_hashes.then_some(&*config))
+    }).and_then(|signature| { signature.to_file(signature_path_of(active_config_path())) }).is_ok()
+}
+
+// returns the public key to be added to the trusted keys
+#[command]
+pub fn generate_signing_key_file(key_path: String) -> Option<String> {
+    let signing_key = generate_signing_key();
+    signing_key_to_file(&signing_key, key_path).ok()?;
+    Some(encode_public_key(&signing_key))
+}
+
 #[command]
 pub fn get_config_basic_info(config_state: State<Mutex<Config>>) -> ConfigBasicInfo {
     let config = config_state.lock().unwrap();
@@ -76,71 +192,96 @@ pub fn get_config_basic_info(config_state: State<Mutex<Config>>) -> ConfigBasicI
 }
 
 #[command]
-pub fn set_config_basic_info(config_state: State<Mutex<Config>>, basic_info: ConfigBasicInfo) -> bool {
+pub fn set_config_basic_info(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, basic_info: ConfigBasicInfo) -> bool {
     let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
     config.lang = basic_info.lang;
     config.header_text = basic_info.header_text;
     config.author = basic_info.author;
     config.toolbox_version = basic_info.toolbox_version;
     config.theme = basic_info.theme;
-    config.to_file("config.json").is_ok()
+    config.dark_theme = basic_info.dark_theme;
+    record_edit(&history_state, snapshot, sync_state.lock().unwrap().save(&config, active_config_path()))
 }
 
+// the changes since the older config, written to the file as json or as markdown release notes
 #[command]
-pub fn launch_app(config_state: State<Mutex<Config>>, app_name: String) -> bool {
-    let config = config_state.lock().unwrap();
-    if let Some(metadata) = config.get_app(&app_name) {
-        if !metadata.app_path.is_file() || !metadata.working_dir.is_dir() {
-            return false;
-        }
-        let absolute_app_path = absolute(&metadata.app_path);
-        let absolute_working_dir = absolute(&metadata.working_dir);
-        if absolute_app_path.is_err() || absolute_working_dir.is_err() {
-            return false;
-        }
-        #[cfg(windows)]
-        {
-            use std::os::windows::process::CommandExt;
-            const DETACHED_PROCESS: u32 = 0x00000008;
-            const CREATE_NEW_CONSOLE: u32 = 0x00000010;
-            let creation_flag: u32;
-            let is_powershell_script: bool;
-            if let Some(ext) = metadata.app_path.extension() &&
-                (ext.to_ascii_lowercase() == "bat" || ext.to_ascii_lowercase() == "cmd" || ext.to_ascii_lowercase() == "ps1") {
-                // for batch files and powershell scripts, we should create a new console for it
-                creation_flag = CREATE_NEW_CONSOLE;
-                is_powershell_script = ext.to_ascii_lowercase() == "ps1";
-            } else {
-                // for other executables, we want to prevent the console window from appearing if it is a GUI app
-                creation_flag = DETACHED_PROCESS;
-                is_powershell_script = false;
-            }
-            let mut command: Command;
-            if is_powershell_script {
-                command = Command::new("powershell");
-                command.arg("-File");
-            } else {
-                command = Command::new("cmd");
-                command.arg("/C");
-            }
-            command.arg(absolute_app_path.unwrap())
-                .current_dir(absolute_working_dir.unwrap())
-                .creation_flags(creation_flag);
-            if !metadata.launch_args.is_empty() {
-                command.raw_arg(&metadata.launch_args);
-            }
-            command.spawn().is_ok()
-        }
+pub fn export_config_changelog(config_state: State<Mutex<Config>>, old_config_path: String, path: String) -> Option<ConfigDiff> {
+    let old_config = Config::from_file(old_config_path).ok()?;
+    let diff = diff_configs(&old_config, &config_state.lock().unwrap());
+    let content = if Path::new(&path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+        serde_json::to_string_pretty(&diff).ok()?
     } else {
-        false
+        diff.to_markdown()
+    };
+    fs_write(path, content).ok()?;
+    Some(diff)
+}
+
+// merge the config of another maintainer into this one, three-way if the config both started from is given
+#[command]
+pub fn merge_config_file(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, theirs_path: String, base_path: Option<String>) -> Option<MergeReport> {
+    let theirs = Config::from_file(theirs_path).ok()?;
+    let base = match base_path {
+        Some(base_path) => Some(Config::from_file(base_path).ok()?),
+        None => None
+    };
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    let (merged, report) = merge_configs(base.as_ref(), &config, &theirs);
+    if merged == snapshot {
+        return Some(report);
     }
+    *config = merged;
+    record_edit(&history_state, snapshot, sync_state.lock().unwrap().save(&config, active_config_path())).then_some(report)
+}
+
+// the formats are picked by the file extensions, a converted config next to the launcher
+// is opened on the next start if there is no config.json, see CONFIG_FILE_NAMES
+#[command]
+pub fn convert_config_file(source_path: String, target_path: String) -> bool {
+    Config::from_file(source_path).and_then(|config| config.to_file(target_path)).is_ok()
+}
+
+// the pre-launch hook may take a while, so launching runs off the main thread
+#[command(async)]
+pub fn launch_app(config_state: State<Mutex<Config>>, tracker_state: State<ProcessTracker>, app_name: String, confirmed: Option<bool>, allow_another: Option<bool>) -> LaunchStatus {
+    launcher::launch_app(&config_state, &tracker_state, &app_name, confirmed.unwrap_or(false), allow_another.unwrap_or(false))
+}
+
+// launching waits for the delays and exits of the steps, so it runs off the main thread
+#[command(async)]
+pub fn launch_group(config_state: State<Mutex<Config>>, tracker_state: State<ProcessTracker>, group: String, confirmed: Option<bool>) -> Option<Vec<LaunchStepResult>> {
+    launcher::launch_group(&config_state, &tracker_state, &group, confirmed.unwrap_or(false))
+}
+
+// apps with processes started by the launcher that are still running
+#[command]
+pub fn get_running_app_list(tracker_state: State<ProcessTracker>) -> Vec<String> {
+    tracker_state.get_running_app_list()
+}
+
+#[command]
+pub fn get_app_log_list(app_name: String) -> Vec<AppLogInfo> {
+    list_app_logs(APP_LOG_DIR, &app_name)
+}
+
+#[command]
+pub fn read_app_log(app_name: String, file_name: String, max_bytes: Option<u64>) -> Option<String> {
+    read_log_tail(APP_LOG_DIR, &app_name, &file_name, max_bytes.unwrap_or(DEFAULT_LOG_TAIL_BYTES)).ok()
+}
+
+// clear the logs of the app, or of all apps if no app is given
+#[command]
+pub fn clear_app_logs(app_name: Option<String>) -> bool {
+    logs::clear_app_logs(APP_LOG_DIR, app_name.as_deref()).is_ok()
 }
 
 #[command]
 pub fn open_app_file_location(config_state: State<Mutex<Config>>, app_name: String) -> bool {
     let config = config_state.lock().unwrap();
     if let Some(metadata) = config.get_app(&app_name) {
-        if !metadata.app_path.is_file() {
+        if metadata.kind == AppKind::Url || !metadata.app_path.exists() {
             return false;
         }
         let absolute_app_path = absolute(&metadata.app_path);
@@ -164,7 +305,24 @@ pub fn open_app_file_location(config_state: State<Mutex<Config>>, app_name: Stri
 #[command]
 pub fn get_category_list(config_state: State<Mutex<Config>>) -> Vec<String> {
     let config = config_state.lock().unwrap();
-    config.get_category_list().into_iter().cloned().collect()
+    let mut category_list: Vec<String> = config.get_category_list().into_iter().cloned().collect();
+    // the virtual favorites category comes first once any app is pinned
+    if !config.get_favorite_app_name_list().is_empty() {
+        category_list.insert(0, FAVORITES_CATEGORY.to_string());
+    }
+    category_list
+}
+
+#[command]
+pub fn get_sub_category_list(config_state: State<Mutex<Config>>, category: String) -> Option<Vec<String>> {
+    let config = config_state.lock().unwrap();
+    Some(config.get_sub_category_list(&category)?.into_iter().cloned().collect())
+}
+
+#[command]
+pub fn get_category_tree(config_state: State<Mutex<Config>>) -> Vec<CategoryTreeNode> {
+    let config = config_state.lock().unwrap();
+    config.get_category_tree()
 }
 
 #[command]
@@ -182,6 +340,9 @@ pub fn get_all_app_list(config_state: State<Mutex<Config>>) -> Vec<AppMetadataWi
 #[command]
 pub fn get_app_list_by_category(config_state: State<Mutex<Config>>, category: String) -> Option<Vec<AppMetadataWithName>> {
     let config = config_state.lock().unwrap();
+    if category == FAVORITES_CATEGORY {
+        return Some(app_list_from_names(&config, config.get_favorite_app_name_list()));
+    }
     if let Some(category_metadata) = config.get_category(&category) {
         let mut app_list: Vec<AppMetadataWithName> = Vec::new();
         for app_name in &category_metadata.apps {
@@ -202,6 +363,10 @@ pub fn get_app_list_by_category(config_state: State<Mutex<Config>>, category: St
 #[command]
 pub fn get_available_app_list_by_category(config_state: State<Mutex<Config>>, category: String) -> Option<Vec<AppMetadataWithName>> {
     let config = config_state.lock().unwrap();
+    if category == FAVORITES_CATEGORY {
+        let app_names = config.get_all_app_name_list().into_iter().filter(|app_name| !config.is_pinned(app_name)).collect();
+        return Some(app_list_from_names(&config, app_names));
+    }
     if let Some(category_metadata) = config.get_category(&category) {
         let mut app_list: Vec<AppMetadataWithName> = Vec::new();
         for app_name in config.get_all_app_name_list().iter().filter(|app_name| !category_metadata.apps.contains(app_name)).cloned() {
@@ -218,80 +383,478 @@ pub fn get_available_app_list_by_category(config_state: State<Mutex<Config>>, ca
 }
 
 #[command]
-pub fn add_category(config_state: State<Mutex<Config>>, category: String) -> bool {
+pub fn get_smart_category_list(config_state: State<Mutex<Config>>) -> Vec<SmartCategoryMetadata> {
+    let config = config_state.lock().unwrap();
+    config.get_smart_category_list().into_iter().cloned().collect()
+}
+
+#[command]
+pub fn get_app_list_by_smart_category(config_state: State<Mutex<Config>>, category: String) -> Option<Vec<AppMetadataWithName>> {
+    let config = config_state.lock().unwrap();
+    let app_names = config.get_smart_category_app_name_list(&category)?;
+    Some(app_list_from_names(&config, app_names))
+}
+
+#[command]
+pub fn get_all_tags(config_state: State<Mutex<Config>>) -> Vec<String> {
+    let config = config_state.lock().unwrap();
+    config.get_all_tags()
+}
+
+#[command]
+pub fn get_app_list_by_tag(config_state: State<Mutex<Config>>, tag: String) -> Vec<AppMetadataWithName> {
+    let config = config_state.lock().unwrap();
+    let mut app_names = config.get_app_name_list_by_tag(&tag);
+    app_names.sort();
+    app_list_from_names(&config, app_names)
+}
+
+#[command]
+pub fn search_apps(config_state: State<Mutex<Config>>, query: String) -> Vec<AppSearchResult> {
+    let config = config_state.lock().unwrap();
+    search::search_apps(&config, &query).into_iter().filter_map(|result| {
+        let mut app = AppMetadataWithName::from(config.get_app(&result.app_name)?);
+        app.name = result.app_name;
+        Some(AppSearchResult { app, matches: result.matches })
+    }).collect()
+}
+
+fn app_list_from_names(config: &Config, app_names: Vec<&String>) -> Vec<AppMetadataWithName> {
+    app_names.into_iter().filter_map(|app_name| {
+        let mut metadata_with_name = AppMetadataWithName::from(config.get_app(app_name)?);
+        metadata_with_name.name = app_name.clone();
+        Some(metadata_with_name)
+    }).collect()
+}
+
+#[command]
+pub fn add_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, category: String) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.add_category(&category).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn update_categories(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, mut new_categories: Vec<String>) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    // the favorites category always stays on top
+    new_categories.retain(|category| category != FAVORITES_CATEGORY);
+    record_edit(&history_state, snapshot, config.update_categories(new_categories).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn add_sub_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, category: String, parent: String) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.add_sub_category(&category, &parent).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn update_sub_categories(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, new_categories: Vec<String>, parent: String) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.update_sub_categories(new_categories, &parent).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn move_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, category: String, new_parent: Option<String>, index: Option<usize>) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.move_category(&category, new_parent.as_deref(), index).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn remove_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, category: String) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.remove_category(&category).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn rename_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, category: String, new_category: String) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.rename_category(&category, &new_category).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn add_smart_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, category: String, query: SmartCategoryQuery) -> bool {
     let mut config = config_state.lock().unwrap();
-    config.add_category(&category).and_then(|_| { config.to_file("config.json") }).is_ok()
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.add_smart_category(&category, query).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
 }
 
 #[command]
-pub fn update_categories(config_state: State<Mutex<Config>>, new_categories: Vec<String>) -> bool {
+pub fn update_smart_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, category: String, query: SmartCategoryQuery) -> bool {
     let mut config = config_state.lock().unwrap();
-    config.update_categories(new_categories).and_then(|_| { config.to_file("config.json") }).is_ok()
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.update_smart_category(&category, query).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
 }
 
 #[command]
-pub fn rename_category(config_state: State<Mutex<Config>>, category: String, new_category: String) -> bool {
+pub fn remove_smart_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, category: String) -> bool {
     let mut config = config_state.lock().unwrap();
-    config.rename_category(&category, &new_category).and_then(|_| { config.to_file("config.json") }).is_ok()
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.remove_smart_category(&category).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
 }
 
 #[command]
-pub fn add_app_to_category(config_state: State<Mutex<Config>>, app: String, category: String) -> bool {
+pub fn add_app_to_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, app: String, category: String) -> bool {
     let mut config = config_state.lock().unwrap();
-    config.add_app_to_category(&app, &category).and_then(|_| { config.to_file("config.json") }).is_ok()
+    let snapshot = config.clone();
+    let result = if category == FAVORITES_CATEGORY { config.pin_app(&app, None) } else { config.add_app_to_category(&app, &category) };
+    record_edit(&history_state, snapshot, result.and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
 }
 
 #[command]
-pub fn add_app_list_to_category(config_state: State<Mutex<Config>>, apps: Vec<String>, category: String) -> bool {
+pub fn add_app_list_to_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, apps: Vec<String>, category: String) -> bool {
     let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
     for app in &apps {
-        if config.add_app_to_category(app, &category).is_err() {
+        let result = if category == FAVORITES_CATEGORY { config.pin_app(app, None) } else { config.add_app_to_category(app, &category) };
+        if result.is_err() {
+            // roll back the apps added so far
+            *config = snapshot;
             return false;
         }
     }
-    config.to_file("config.json").is_ok()
+    record_edit(&history_state, snapshot, sync_state.lock().unwrap().save(&config, active_config_path()))
+}
+
+#[command]
+pub fn update_apps_in_category(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, apps: Vec<String>, category: String) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    let result = if category == FAVORITES_CATEGORY { config.update_favorites(apps) } else { config.update_apps_in_category(apps, &category) };
+    record_edit(&history_state, snapshot, result.and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn get_favorite_app_list(config_state: State<Mutex<Config>>) -> Vec<AppMetadataWithName> {
+    let config = config_state.lock().unwrap();
+    app_list_from_names(&config, config.get_favorite_app_name_list())
+}
+
+#[command]
+pub fn pin_app(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, app: String, index: Option<usize>) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.pin_app(&app, index).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn unpin_app(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, app: String) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.unpin_app(&app).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn update_favorites(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, apps: Vec<String>) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.update_favorites(apps).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn get_launch_group_list(config_state: State<Mutex<Config>>) -> Vec<LaunchGroup> {
+    let config = config_state.lock().unwrap();
+    config.get_launch_group_list().into_iter().cloned().collect()
+}
+
+#[command]
+pub fn add_launch_group(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, group: String, steps: Vec<LaunchStep>) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.add_launch_group(&group, steps).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn update_launch_group(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, group: String, steps: Vec<LaunchStep>) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.update_launch_group(&group, steps).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
 }
 
 #[command]
-pub fn update_apps_in_category(config_state: State<Mutex<Config>>, apps: Vec<String>, category: String) -> bool {
+pub fn rename_launch_group(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, group: String, new_group: String) -> bool {
     let mut config = config_state.lock().unwrap();
-    config.update_apps_in_category(apps, &category).and_then(|_| { config.to_file("config.json") }).is_ok()
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.rename_launch_group(&group, &new_group).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
 }
 
 #[command]
-pub fn add_app(config_state: State<Mutex<Config>>, app_metadata_with_name: AppMetadataWithName) -> bool {
+pub fn remove_launch_group(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, group: String) -> bool {
     let mut config = config_state.lock().unwrap();
-    let app_metadata = AppMetadata::from(&app_metadata_with_name);
-    config.add_app(&app_metadata_with_name.name, app_metadata).and_then(|_| { config.to_file("config.json") }).is_ok()
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.remove_launch_group(&group).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
 }
 
 #[command]
-pub fn update_app(config_state: State<Mutex<Config>>, app_name: String, app_metadata_with_name: AppMetadataWithName) -> bool {
+pub fn add_app(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, app_metadata_with_name: AppMetadataWithName) -> bool {
     let mut config = config_state.lock().unwrap();
-    let app_metadata = AppMetadata::from(&app_metadata_with_name);
+    let snapshot = config.clone();
+    let app_metadata = with_fallback_icon(&config, &app_metadata_with_name.name, AppMetadata::from(&app_metadata_with_name));
+    record_edit(&history_state, snapshot, config.add_app(&app_metadata_with_name.name, app_metadata).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn update_app(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, tracker_state: State<ProcessTracker>, app_name: String, app_metadata_with_name: AppMetadataWithName) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    let app_metadata = with_fallback_icon(&config, &app_metadata_with_name.name, AppMetadata::from(&app_metadata_with_name));
     // rename the app if necessary
     if app_name != app_metadata_with_name.name && config.rename_app(&app_name, &app_metadata_with_name.name).is_err() {
         dbg!("Failed to rename app: {} to {}", app_name, app_metadata_with_name.name);
         false
     } else {
-        config.update_app(&app_metadata_with_name.name, app_metadata).and_then(|_| { config.to_file("config.json") }).is_ok()
+        if app_name != app_metadata_with_name.name {
+            tracker_state.rename_app(&app_name, &app_metadata_with_name.name);
+        }
+        record_edit(&history_state, snapshot, config.update_app(&app_metadata_with_name.name, app_metadata).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
     }
 }
 
+// generate the icons of the apps without one, and again those generated before, e.g. after changing the theme
 #[command]
-pub fn remove_app(config_state: State<Mutex<Config>>, app_name: String) -> bool {
+pub fn regenerate_fallback_icons(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>) -> bool {
     let mut config = config_state.lock().unwrap();
-    config.remove_app(&app_name).and_then(|_| { config.to_file("config.json") }).is_ok()
+    let snapshot = config.clone();
+    let app_names: Vec<String> = config.get_all_app_name_list().into_iter().cloned().collect();
+    for app_name in app_names {
+        let metadata = with_fallback_icon(&config, &app_name, config.get_app(&app_name).unwrap().clone());
+        config.update_app(&app_name, metadata).unwrap();
+    }
+    record_edit(&history_state, snapshot, sync_state.lock().unwrap().save(&config, active_config_path()))
+}
+
+// snapshot the app files again and report the apps updated since the last scan, running them with --version if allowed,
+// hashing and probing may take a while, so it runs off the main thread on a copy of the config
+#[command(async)]
+pub fn scan_app_versions(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, probe: bool) -> Option<Vec<AppVersionChange>> {
+    let scanned_config = config_state.lock().unwrap().clone();
+    let (changes, scans) = scan_app_files(&scanned_config, probe);
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    apply_version_scans(&mut config, scans);
+    if *config == snapshot {
+        return Some(changes);
+    }
+    record_edit(&history_state, snapshot, sync_state.lock().unwrap().save(&config, active_config_path())).then_some(changes)
 }
 
 #[command]
-pub fn load_icon_from_file(path: String) -> Option<String> {
-    encode_image_url_from_file(path).ok()
+pub fn remove_app(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, app_name: String) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    record_edit(&history_state, snapshot, config.remove_app(&app_name).and_then(|_| { sync_state.lock().unwrap().save(&config, active_config_path()) }))
+}
+
+#[command]
+pub fn generate_integrity_manifest(config_state: State<Mutex<Config>>, include_dirs: bool) -> bool {
+    let config = config_state.lock().unwrap();
+    IntegrityManifest::from_config(&config, include_dirs).and_then(|manifest| {
+        manifest.to_file(INTEGRITY_MANIFEST_PATH)
+    }).is_ok()
+}
+
+#[command]
+pub fn verify_integrity(config_state: State<Mutex<Config>>) -> Option<BTreeMap<String, IntegrityStatus>> {
+    let config = config_state.lock().unwrap();
+    let manifest = IntegrityManifest::from_file(INTEGRITY_MANIFEST_PATH).ok()?;
+    Some(manifest.verify(&config))
+}
+
+#[command]
+pub fn check_app_integrity(config_state: State<Mutex<Config>>, app_name: String) -> Option<IntegrityStatus> {
+    let config = config_state.lock().unwrap();
+    // folders and urls are not verified
+    let metadata = config.get_app(&app_name).filter(|metadata| metadata.kind.is_file())?;
+    match IntegrityManifest::from_file(INTEGRITY_MANIFEST_PATH) {
+        Ok(manifest) => Some(manifest.verify_app(&app_name, metadata)),
+        Err(_) => Some(IntegrityStatus::Unlisted)
+    }
+}
+
+#[command]
+pub fn get_integrity_policy(config_state: State<Mutex<Config>>) -> IntegrityPolicy {
+    let config = config_state.lock().unwrap();
+    config.integrity_policy.clone()
+}
+
+#[command]
+pub fn set_integrity_policy(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, policy: IntegrityPolicy) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    config.integrity_policy = policy;
+    record_edit(&history_state, snapshot, sync_state.lock().unwrap().save(&config, active_config_path()))
 }
 
 #[command]
-pub fn load_icon_from_app(path: String) -> Option<String> {
-    encode_image_url_from_app_icon(path)
+pub fn get_default_hooks(config_state: State<Mutex<Config>>) -> LaunchHooks {
+    let config = config_state.lock().unwrap();
+    config.default_hooks.clone()
+}
+
+#[command]
+pub fn set_default_hooks(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, hooks: LaunchHooks) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    config.default_hooks = hooks;
+    record_edit(&history_state, snapshot, sync_state.lock().unwrap().save(&config, active_config_path()))
+}
+
+#[command]
+pub fn get_icon_settings(config_state: State<Mutex<Config>>) -> IconSettings {
+    let config = config_state.lock().unwrap();
+    config.icon_settings.clone()
+}
+
+#[command]
+pub fn set_icon_settings(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>, icon_settings: IconSettings) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let snapshot = config.clone();
+    // max_size is already clamped when the arguments are deserialized
+    config.icon_settings = icon_settings;
+    record_edit(&history_state, snapshot, sync_state.lock().unwrap().save(&config, active_config_path()))
+}
+
+#[command]
+pub fn undo(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let mut history = history_state.lock().unwrap();
+    if !history.undo(&mut config) {
+        return false;
+    }
+    if sync_state.lock().unwrap().save(&config, active_config_path()).is_err() {
+        history.redo(&mut config);
+        return false;
+    }
+    true
+}
+
+#[command]
+pub fn redo(config_state: State<Mutex<Config>>, history_state: State<Mutex<EditHistory>>, sync_state: State<Mutex<ConfigSync>>) -> bool {
+    let mut config = config_state.lock().unwrap();
+    let mut history = history_state.lock().unwrap();
+    if !history.redo(&mut config) {
+        return false;
+    }
+    if sync_state.lock().unwrap().save(&config, active_config_path()).is_err() {
+        history.undo(&mut config);
+        return false;
+    }
+    true
+}
+
+#[command]
+pub fn get_edit_history_state(history_state: State<Mutex<EditHistory>>) -> EditHistoryState {
+    history_state.lock().unwrap().state()
+}
+
+#[command]
+pub fn load_icon_from_file(config_state: State<Mutex<Config>>, path: String) -> Option<String> {
+    let icon_settings = config_state.lock().unwrap().icon_settings.clone();
+    encode_icon_url_from_file(path, &icon_settings).ok()
+}
+
+#[command]
+pub fn load_icon_from_app(config_state: State<Mutex<Config>>, path: String) -> Option<String> {
+    let icon_settings = config_state.lock().unwrap().icon_settings.clone();
+    encode_image_url_from_app_icon(path, &icon_settings)
+}
+
+// name, description, version and publisher of an executable from its version resource, in the language of the toolbox if it has them
+#[command]
+pub fn load_metadata_from_app(config_state: State<Mutex<Config>>, path: String) -> Option<AppMetadataWithName> {
+    let lang_id = lang_id_of(&config_state.lock().unwrap().lang);
+    let app_path = PathBuf::from(&path);
+    if !app_path.is_file() {
+        return None;
+    }
+    // executables without a version resource are still named after the file
+    let version_info = read_version_info(&path).ok();
+    let string = |key: &str| version_info.as_ref().and_then(|info| info.string(key, lang_id)).map(String::from);
+    let file_desc = string("FileDescription");
+    let name = string("ProductName").or(file_desc.clone())
+        .or_else(|| app_path.file_stem().map(|stem| stem.to_string_lossy().to_string()))?;
+    let version = string("ProductVersion").or_else(|| version_info.as_ref().and_then(|info| info.product_version.clone())).unwrap_or_default();
+    let metadata = AppMetadata {
+        desc: file_desc.filter(|desc| *desc != name).unwrap_or_default(),
+        working_dir: app_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        app_path,
+        version_source: if version.is_empty() { VersionSource::Manual } else { VersionSource::VersionInfo },
+        version,
+        publisher: string("CompanyName").unwrap_or_default(),
+        ..Default::default()
+    };
+    let mut metadata_with_name = AppMetadataWithName::from(&metadata);
+    metadata_with_name.name = name;
+    Some(metadata_with_name)
+}
+
+#[command]
+pub fn get_default_icon(kind: AppKind) -> String {
+    default_icon_url(&kind)
+}
+
+#[command]
+pub fn get_theme_presets() -> Vec<ThemePreset> {
+    let mut presets = builtin_presets();
+    presets.extend(load_custom_presets(THEME_PRESET_DIR));
+    presets
+}
+
+#[command]
+pub fn save_theme_preset(name: String, theme: Theme) -> bool {
+    save_custom_preset(THEME_PRESET_DIR, &ThemePreset::new(&name, theme)).is_ok()
+}
+
+#[command]
+pub fn remove_theme_preset(name: String) -> bool {
+    remove_custom_preset(THEME_PRESET_DIR, &name).is_ok()
+}
+
+#[command]
+pub fn import_theme_preset(path: String) -> Option<ThemePreset> {
+    import_preset(THEME_PRESET_DIR, path).ok()
+}
+
+#[command]
+pub fn export_theme_preset(name: String, theme: Theme, path: String) -> bool {
+    ThemePreset::new(&name, theme).to_file(path).is_ok()
+}
+
+#[command]
+pub fn check_theme_contrast(theme: Theme) -> Option<ThemeContrast> {
+    check_contrast(&theme, &HEADER_TEXT_COLOR)
+}
+
+// copy the background image next to the config and return the absolute path of the copy,
+// an existing theme image of the same name is kept and the copy gets a numbered name
+#[command]
+pub fn import_theme_image(path: String) -> Option<String> {
+    let source = absolute(&path).ok()?;
+    let image_data = fs_read(&source).ok()?;
+    sniff_image_type(&image_data)?;
+    let config_dir = absolute(".").ok()?;
+    if source.parent() == Some(config_dir.as_path()) {
+        return Some(source.to_string_lossy().to_string());
+    }
+    let stem = source.file_stem()?.to_string_lossy().to_string();
+    let extension = source.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let target = (1..).map(|i| match i {
+        1 => config_dir.join(format!("theme_{}{}", stem, extension)),
+        _ => config_dir.join(format!("theme_{} ({}){}", stem, i, extension))
+    }).find(|target| !target.exists())?;
+    fs_write(&target, image_data).ok()?;
+    Some(target.to_string_lossy().to_string())
+}
+
+#[command]
+pub fn load_theme_image(path: String) -> Option<String> {
+    encode_image_url_from_file(path).ok()
 }
 
 #[command]
@@ -1,12 +1,56 @@
+pub mod diff;
 pub mod error;
+pub mod history;
+pub mod merge;
 pub mod structure;
+pub mod sync;
 
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::OnceLock;
 use std::fs::{read_to_string as fs_read_to_string, write as fs_write};
 use serde::{Deserialize, Serialize};
-use structure::{AppMetadata, CategoryMetadata, Theme, ThemeColor, ToolboxVersion};
-use error::{ConfigError, ConfigErrorType};
+use structure::{AppMetadata, CategoryMetadata, CategoryTreeNode, ColorStop, DEFAULT_GRADIENT_ANGLE, IconSettings, IntegrityPolicy, LaunchGroup, LaunchHooks, LaunchStep, SmartCategoryMetadata, SmartCategoryQuery, Theme, ThemeColor, ToolboxVersion};
+use error::{ConfigError, ConfigErrorType, FormatError};
+
+// internal name of the virtual category listing the pinned apps, the frontend shows a localized one;
+// reserved for manual categories, categories loaded with this name are renamed
+pub const FAVORITES_CATEGORY: &str = "__favorites__";
+
+// the config files looked for next to the launcher, in order of preference
+pub const CONFIG_FILE_NAMES: [&str; 4] = ["config.json", "config.toml", "config.yaml", "config.yml"];
+
+static ACTIVE_CONFIG_PATH: OnceLock<&'static str> = OnceLock::new();
+
+// the first config file found in the directory, config.json for a new toolbox
+pub fn find_config_file<P: AsRef<Path>>(dir: P) -> &'static str {
+    CONFIG_FILE_NAMES.into_iter().find(|name| dir.as_ref().join(name).is_file()).unwrap_or(CONFIG_FILE_NAMES[0])
+}
+
+// the config file the launcher loads, saves, watches and signs, resolved once at startup
+pub fn active_config_path() -> &'static str {
+    ACTIVE_CONFIG_PATH.get_or_init(|| find_config_file("."))
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum ConfigFormat {
+    #[default]
+    Json,
+    Toml,
+    Yaml
+}
+
+impl ConfigFormat {
+    // by the extension of the file, JSON unless it is a TOML or YAML file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let extension = path.as_ref().extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("toml") => ConfigFormat::Toml,
+            Some("yaml" | "yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Json
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct Config {
@@ -15,8 +59,24 @@ pub struct Config {
     pub author: Option<String>,
     pub toolbox_version: Option<ToolboxVersion>,
     pub theme: Theme,
+    // used instead of `theme` when the OS prefers a dark color scheme
+    #[serde(default)]
+    pub dark_theme: Option<Theme>,
+    #[serde(default)]
+    pub integrity_policy: IntegrityPolicy,
+    // hooks of the apps that do not have their own
+    #[serde(default)]
+    pub default_hooks: LaunchHooks,
+    #[serde(default)]
+    pub icon_settings: IconSettings,
     app_library: HashMap<String, AppMetadata>,
     categories: Vec<CategoryMetadata>,
+    #[serde(default)]
+    smart_categories: Vec<SmartCategoryMetadata>,
+    #[serde(default)]
+    favorites: Vec<String>,
+    #[serde(default)]
+    launch_groups: Vec<LaunchGroup>
 }
 
 impl Config {
@@ -27,11 +87,21 @@ impl Config {
             author: None,
             toolbox_version: None,
             theme: Theme::LinearGradient {
-                from: ThemeColor::RGB { r: 0x28, g: 0x54, b: 0xB5 },
-                to: ThemeColor::RGB { r: 0x14, g: 0xC0, b: 0xD3 },
+                stops: vec![
+                    ColorStop { color: ThemeColor::RGB { r: 0x28, g: 0x54, b: 0xB5 }, position: 0 },
+                    ColorStop { color: ThemeColor::RGB { r: 0x14, g: 0xC0, b: 0xD3 }, position: 100 }
+                ],
+                angle: DEFAULT_GRADIENT_ANGLE
             },
+            dark_theme: None,
+            integrity_policy: IntegrityPolicy::Off,
+            default_hooks: LaunchHooks::default(),
+            icon_settings: IconSettings::default(),
             app_library: HashMap::new(),
-            categories: Vec::new()
+            categories: Vec::new(),
+            smart_categories: Vec::new(),
+            favorites: Vec::new(),
+            launch_groups: Vec::new()
         }
     }
 
@@ -43,10 +113,9 @@ impl Config {
             return Err(ConfigError { err_type: ConfigErrorType::FileNotExist, config_path: Some(path_str) });
         }
         match fs_read_to_string(path) {
-            Ok(content) => match serde_json::from_str(&content) {
-                Ok(config) => Ok(config),
-                Err(e) => Err(ConfigError { err_type: ConfigErrorType::ParseError(e), config_path: Some(path_str) })
-            },
+            Ok(content) => Self::parse(&content, ConfigFormat::from_path(path)).map_err(|e| {
+                ConfigError { err_type: ConfigErrorType::ParseError(e), config_path: Some(path_str) }
+            }),
             Err(e) => Err(ConfigError { err_type: ConfigErrorType::ReadError(e), config_path: Some(path_str) })
         }
     }
@@ -54,8 +123,11 @@ impl Config {
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
         let path = path.as_ref();
         let path_str = path.to_string_lossy().to_string();
-        let content = serde_json::to_string_pretty(self).expect("Failed to serialize config");
-        
+        let content = match self.serialize(ConfigFormat::from_path(path)) {
+            Ok(content) => content,
+            Err(e) => return Err(ConfigError { err_type: ConfigErrorType::SerializeError(e), config_path: Some(path_str) })
+        };
+
         if let Err(e) = fs_write(path, content) {
             Err(ConfigError { err_type: ConfigErrorType::WriteError(e), config_path: Some(path_str) })
         } else {
@@ -63,6 +135,46 @@ impl Config {
         }
     }
 
+    pub fn parse(content: &str, format: ConfigFormat) -> Result<Self, FormatError> {
+        let config: Self = match format {
+            ConfigFormat::Json => serde_json::from_str(content).map_err(FormatError::Json),
+            ConfigFormat::Toml => toml::from_str(content).map_err(FormatError::TomlParse),
+            ConfigFormat::Yaml => serde_yaml_ng::from_str(content).map_err(FormatError::Yaml)
+        }?;
+        Ok(config.rename_reserved_categories())
+    }
+
+    // give the categories named like the favorites a free name, so that they are not mistaken for the favorites
+    fn rename_reserved_categories(mut self) -> Self {
+        let free_name = |config: &Self| (2..).map(|i| format!("{} {}", FAVORITES_CATEGORY, i))
+            .find(|name| !config.category_exists(name)).unwrap();
+        while find_category(&self.categories, FAVORITES_CATEGORY).is_some() {
+            let new_name = free_name(&self);
+            find_category_mut(&mut self.categories, FAVORITES_CATEGORY).unwrap().name = new_name;
+        }
+        while self.smart_categories.iter().any(|c| c.name == FAVORITES_CATEGORY) {
+            let new_name = free_name(&self);
+            self.smart_categories.iter_mut().find(|c| c.name == FAVORITES_CATEGORY).unwrap().name = new_name;
+        }
+        self
+    }
+
+    pub fn serialize(&self, format: ConfigFormat) -> Result<String, FormatError> {
+        match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(self).map_err(FormatError::Json),
+            ConfigFormat::Toml => toml::to_string_pretty(self).map_err(FormatError::TomlWrite),
+            ConfigFormat::Yaml => serde_yaml_ng::to_string(self).map_err(FormatError::Yaml)
+        }
+    }
+
+    // the theme to show for the OS color scheme, falling back to the light one
+    pub fn get_theme(&self, dark: bool) -> &Theme {
+        match &self.dark_theme {
+            Some(dark_theme) if dark => dark_theme,
+            _ => &self.theme
+        }
+    }
+
     pub fn add_app(&mut self, app_name: &str, metadata: AppMetadata) -> Result<(), ConfigError> {
         if self.app_library.contains_key(app_name) {
             return Err(ConfigError { err_type: ConfigErrorType::AppExist(app_name.to_string()), config_path: None });
@@ -77,10 +189,18 @@ impl Config {
         }
         let app_metadata = self.app_library.remove(app_name).expect("App should exist");
         self.app_library.insert(new_app_name.to_string(), app_metadata);
-        for category in &mut self.categories {
+        for_each_category_mut(&mut self.categories, &mut |category| {
             if let Some(pos) = category.apps.iter().position(|category_app_name| category_app_name == app_name) {
                 category.apps[pos] = new_app_name.to_string();
             }
+        });
+        if let Some(pos) = self.favorites.iter().position(|favorite| favorite == app_name) {
+            self.favorites[pos] = new_app_name.to_string();
+        }
+        for step in self.launch_groups.iter_mut().flat_map(|group| group.steps.iter_mut()) {
+            if step.app == app_name {
+                step.app = new_app_name.to_string();
+            }
         }
         Ok(())
     }
@@ -97,8 +217,12 @@ impl Config {
         if let None = self.app_library.remove(app_name) {
             return Err(ConfigError { err_type: ConfigErrorType::AppNotExist(app_name.to_string()), config_path: None });
         }
-        for category in &mut self.categories {
+        for_each_category_mut(&mut self.categories, &mut |category| {
             category.apps.retain(|app| app != app_name);
+        });
+        self.favorites.retain(|app| app != app_name);
+        for group in &mut self.launch_groups {
+            group.steps.retain(|step| step.app != app_name);
         }
         Ok(())
     }
@@ -111,20 +235,54 @@ impl Config {
         self.app_library.keys().collect()
     }
 
+    pub fn get_all_tags(&self) -> Vec<String> {
+        let mut app_names = self.get_all_app_name_list();
+        app_names.sort();
+        let mut tags: Vec<String> = Vec::new();
+        for app_name in app_names {
+            for tag in &self.app_library[app_name].tags {
+                if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                    tags.push(tag.clone());
+                }
+            }
+        }
+        tags.sort_by_key(|tag| tag.to_lowercase());
+        tags
+    }
+
+    pub fn get_app_name_list_by_tag(&self, tag: &str) -> Vec<&String> {
+        self.app_library.iter().filter(|(_, metadata)| metadata.has_tag(tag)).map(|(app_name, _)| app_name).collect()
+    }
+
+    // category names are unique across the whole category tree and the smart categories
+    fn category_exists(&self, category_name: &str) -> bool {
+        category_name == FAVORITES_CATEGORY ||
+            self.categories.iter().any(|c| c.contains(category_name)) || self.smart_categories.iter().any(|c| c.name == category_name)
+    }
+
     pub fn add_category(&mut self, category_name: &str) -> Result<(), ConfigError> {
-        if self.categories.iter().any(|c| c.name == category_name) {
+        if self.category_exists(category_name) {
             return Err(ConfigError { err_type: ConfigErrorType::CategoryExist(category_name.to_string()), config_path: None });
         }
-        self.categories.push(CategoryMetadata {
-            name: category_name.to_string(),
-            apps: Vec::new()
-        });
+        self.categories.push(CategoryMetadata::new(category_name));
         Ok(())
     }
 
+    pub fn add_sub_category(&mut self, category_name: &str, parent_name: &str) -> Result<(), ConfigError> {
+        if self.category_exists(category_name) {
+            return Err(ConfigError { err_type: ConfigErrorType::CategoryExist(category_name.to_string()), config_path: None });
+        }
+        if let Some(parent) = find_category_mut(&mut self.categories, parent_name) {
+            parent.children.push(CategoryMetadata::new(category_name));
+            Ok(())
+        } else {
+            Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(parent_name.to_string()), config_path: None })
+        }
+    }
+
+    // removes the category together with its subcategories
     pub fn remove_category(&mut self, category_name: &str) -> Result<(), ConfigError> {
-        if let Some(pos) = self.categories.iter().position(|c| c.name == category_name) {
-            self.categories.remove(pos);
+        if take_category(&mut self.categories, category_name).is_some() {
             Ok(())
         } else {
             Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(category_name.to_string()), config_path: None })
@@ -132,17 +290,50 @@ impl Config {
     }
 
     pub fn get_category(&self, category_name: &str) -> Option<&CategoryMetadata> {
-        self.categories.iter().find(|c| c.name == category_name)
+        find_category(&self.categories, category_name)
     }
 
     pub fn get_category_list(&self) -> Vec<&String> {
         self.categories.iter().map(|c| &c.name).collect()
     }
 
+    pub fn get_sub_category_list(&self, parent_name: &str) -> Option<Vec<&String>> {
+        let parent = self.get_category(parent_name)?;
+        Some(parent.children.iter().map(|c| &c.name).collect())
+    }
+
+    pub fn get_category_tree(&self) -> Vec<CategoryTreeNode> {
+        self.categories.iter().map(CategoryTreeNode::from).collect()
+    }
+
+    // move the category under a new parent (or to the top level if None), at the given position among its new siblings
+    pub fn move_category(&mut self, category_name: &str, new_parent_name: Option<&str>, index: Option<usize>) -> Result<(), ConfigError> {
+        let Some(category) = self.get_category(category_name) else {
+            return Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(category_name.to_string()), config_path: None });
+        };
+        if let Some(new_parent_name) = new_parent_name {
+            if category.contains(new_parent_name) {
+                return Err(ConfigError { err_type: ConfigErrorType::CategoryMoveIntoItself(category_name.to_string(), new_parent_name.to_string()), config_path: None });
+            }
+            if self.get_category(new_parent_name).is_none() {
+                return Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(new_parent_name.to_string()), config_path: None });
+            }
+        }
+        let category = take_category(&mut self.categories, category_name).expect("Category should exist");
+        let siblings = match new_parent_name {
+            Some(new_parent_name) => &mut find_category_mut(&mut self.categories, new_parent_name).expect("Category should exist").children,
+            None => &mut self.categories
+        };
+        let index = index.unwrap_or(siblings.len()).min(siblings.len());
+        siblings.insert(index, category);
+        Ok(())
+    }
+
     pub fn update_categories(&mut self, new_categories: Vec<String>) -> Result<(), ConfigError> {
         let mut updated_list: Vec<CategoryMetadata> = Vec::new();
+        // only the top-level categories are reordered here, subcategories are not found
         for ct in &new_categories {
-            if let Some(category_metadata) = self.get_category(ct) {
+            if let Some(category_metadata) = self.categories.iter().find(|c| &c.name == ct) {
                 updated_list.push(category_metadata.clone());
             } else {
                 return Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(ct.to_string()), config_path: None });
@@ -152,14 +343,31 @@ impl Config {
         Ok(())
     }
 
+    // reorder the subcategories of a category, like update_categories does for the top level
+    pub fn update_sub_categories(&mut self, new_categories: Vec<String>, parent_name: &str) -> Result<(), ConfigError> {
+        let Some(parent) = find_category_mut(&mut self.categories, parent_name) else {
+            return Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(parent_name.to_string()), config_path: None });
+        };
+        let mut updated_list: Vec<CategoryMetadata> = Vec::new();
+        for ct in &new_categories {
+            if let Some(category_metadata) = parent.children.iter().find(|c| &c.name == ct) {
+                updated_list.push(category_metadata.clone());
+            } else {
+                return Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(ct.to_string()), config_path: None });
+            }
+        }
+        parent.children = updated_list;
+        Ok(())
+    }
+
     pub fn rename_category(&mut self, category_name: &str, new_category_name: &str) -> Result<(), ConfigError> {
         if category_name == new_category_name {
             return Ok(()); // No change needed
         }
-        if self.categories.iter().any(|c| c.name == new_category_name) {
+        if self.category_exists(new_category_name) {
             return Err(ConfigError { err_type: ConfigErrorType::CategoryExist(new_category_name.to_string()), config_path: None });
         }
-        if let Some(category) = self.categories.iter_mut().find(|c| c.name == category_name) {
+        if let Some(category) = find_category_mut(&mut self.categories, category_name) {
             category.name = new_category_name.to_string();
             Ok(())
         } else {
@@ -168,7 +376,7 @@ impl Config {
     }
 
     pub fn add_app_to_category(&mut self, app_name: &str, category_name: &str) -> Result<(), ConfigError> {
-        if let Some(category) = self.categories.iter_mut().find(|c| c.name == category_name) {
+        if let Some(category) = find_category_mut(&mut self.categories, category_name) {
             if !self.app_library.contains_key(app_name) {
                 return Err(ConfigError { err_type: ConfigErrorType::AppNotExist(app_name.to_string()), config_path: None });
             }
@@ -183,7 +391,7 @@ impl Config {
     }
 
     pub fn remove_app_from_category(&mut self, app_name: &str, category_name: &str) -> Result<(), ConfigError> {
-        if let Some(category) = self.categories.iter_mut().find(|c| c.name == category_name) {
+        if let Some(category) = find_category_mut(&mut self.categories, category_name) {
             if !self.app_library.contains_key(app_name) {
                 return Err(ConfigError { err_type: ConfigErrorType::AppNotExist(app_name.to_string()), config_path: None });
             }
@@ -198,7 +406,7 @@ impl Config {
     }
 
     pub fn update_apps_in_category(&mut self, new_apps: Vec<String>, category_name: &str) -> Result<(), ConfigError> {
-        if let Some(category) = self.categories.iter_mut().find(|c| c.name == category_name) {
+        if let Some(category) = find_category_mut(&mut self.categories, category_name) {
             for app in &new_apps {
                 if !self.app_library.contains_key(app) {
                     return Err(ConfigError { err_type: ConfigErrorType::AppNotExist(app.to_string()), config_path: None });
@@ -211,4 +419,191 @@ impl Config {
         }
     }
 
+    pub fn add_smart_category(&mut self, category_name: &str, query: SmartCategoryQuery) -> Result<(), ConfigError> {
+        if self.category_exists(category_name) {
+            return Err(ConfigError { err_type: ConfigErrorType::CategoryExist(category_name.to_string()), config_path: None });
+        }
+        self.smart_categories.push(SmartCategoryMetadata {
+            name: category_name.to_string(),
+            query
+        });
+        Ok(())
+    }
+
+    pub fn update_smart_category(&mut self, category_name: &str, query: SmartCategoryQuery) -> Result<(), ConfigError> {
+        if let Some(category) = self.smart_categories.iter_mut().find(|c| c.name == category_name) {
+            category.query = query;
+            Ok(())
+        } else {
+            Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(category_name.to_string()), config_path: None })
+        }
+    }
+
+    pub fn remove_smart_category(&mut self, category_name: &str) -> Result<(), ConfigError> {
+        if let Some(pos) = self.smart_categories.iter().position(|c| c.name == category_name) {
+            self.smart_categories.remove(pos);
+            Ok(())
+        } else {
+            Err(ConfigError { err_type: ConfigErrorType::CategoryNotExist(category_name.to_string()), config_path: None })
+        }
+    }
+
+    pub fn get_smart_category(&self, category_name: &str) -> Option<&SmartCategoryMetadata> {
+        self.smart_categories.iter().find(|c| c.name == category_name)
+    }
+
+    pub fn get_smart_category_list(&self) -> Vec<&SmartCategoryMetadata> {
+        self.smart_categories.iter().collect()
+    }
+
+    // the apps of a smart category are computed from its query, sorted by name
+    pub fn get_smart_category_app_name_list(&self, category_name: &str) -> Option<Vec<&String>> {
+        let category = self.get_smart_category(category_name)?;
+        let mut app_names: Vec<&String> = self.app_library.iter()
+            .filter(|(app_name, metadata)| category.query.matches(app_name, metadata))
+            .map(|(app_name, _)| app_name)
+            .collect();
+        app_names.sort();
+        Some(app_names)
+    }
+
+    // pin the app to the favorites, at the given position or at the end
+    pub fn pin_app(&mut self, app_name: &str, index: Option<usize>) -> Result<(), ConfigError> {
+        if !self.app_library.contains_key(app_name) {
+            return Err(ConfigError { err_type: ConfigErrorType::AppNotExist(app_name.to_string()), config_path: None });
+        }
+        if self.favorites.iter().any(|favorite| favorite == app_name) {
+            return Err(ConfigError { err_type: ConfigErrorType::AppExistInCategory(app_name.to_string(), FAVORITES_CATEGORY.to_string()), config_path: None });
+        }
+        let index = index.unwrap_or(self.favorites.len()).min(self.favorites.len());
+        self.favorites.insert(index, app_name.to_string());
+        Ok(())
+    }
+
+    pub fn unpin_app(&mut self, app_name: &str) -> Result<(), ConfigError> {
+        if let Some(pos) = self.favorites.iter().position(|favorite| favorite == app_name) {
+            self.favorites.remove(pos);
+            Ok(())
+        } else {
+            Err(ConfigError { err_type: ConfigErrorType::AppNotExistInCategory(app_name.to_string(), FAVORITES_CATEGORY.to_string()), config_path: None })
+        }
+    }
+
+    pub fn update_favorites(&mut self, new_favorites: Vec<String>) -> Result<(), ConfigError> {
+        for (i, app) in new_favorites.iter().enumerate() {
+            if !self.app_library.contains_key(app) {
+                return Err(ConfigError { err_type: ConfigErrorType::AppNotExist(app.to_string()), config_path: None });
+            }
+            if new_favorites[..i].contains(app) {
+                return Err(ConfigError { err_type: ConfigErrorType::AppExistInCategory(app.to_string(), FAVORITES_CATEGORY.to_string()), config_path: None });
+            }
+        }
+        self.favorites = new_favorites;
+        Ok(())
+    }
+
+    pub fn is_pinned(&self, app_name: &str) -> bool {
+        self.favorites.iter().any(|favorite| favorite == app_name)
+    }
+
+    pub fn get_favorite_app_name_list(&self) -> Vec<&String> {
+        self.favorites.iter().collect()
+    }
+
+    fn check_launch_steps(&self, steps: &[LaunchStep]) -> Result<(), ConfigError> {
+        if let Some(step) = steps.iter().find(|step| !self.app_library.contains_key(&step.app)) {
+            return Err(ConfigError { err_type: ConfigErrorType::AppNotExist(step.app.clone()), config_path: None });
+        }
+        Ok(())
+    }
+
+    pub fn add_launch_group(&mut self, group_name: &str, steps: Vec<LaunchStep>) -> Result<(), ConfigError> {
+        if self.launch_groups.iter().any(|g| g.name == group_name) {
+            return Err(ConfigError { err_type: ConfigErrorType::LaunchGroupExist(group_name.to_string()), config_path: None });
+        }
+        self.check_launch_steps(&steps)?;
+        self.launch_groups.push(LaunchGroup { name: group_name.to_string(), steps });
+        Ok(())
+    }
+
+    pub fn update_launch_group(&mut self, group_name: &str, steps: Vec<LaunchStep>) -> Result<(), ConfigError> {
+        self.check_launch_steps(&steps)?;
+        if let Some(group) = self.launch_groups.iter_mut().find(|g| g.name == group_name) {
+            group.steps = steps;
+            Ok(())
+        } else {
+            Err(ConfigError { err_type: ConfigErrorType::LaunchGroupNotExist(group_name.to_string()), config_path: None })
+        }
+    }
+
+    pub fn rename_launch_group(&mut self, group_name: &str, new_group_name: &str) -> Result<(), ConfigError> {
+        if group_name == new_group_name {
+            return Ok(()); // No change needed
+        }
+        if self.launch_groups.iter().any(|g| g.name == new_group_name) {
+            return Err(ConfigError { err_type: ConfigErrorType::LaunchGroupExist(new_group_name.to_string()), config_path: None });
+        }
+        if let Some(group) = self.launch_groups.iter_mut().find(|g| g.name == group_name) {
+            group.name = new_group_name.to_string();
+            Ok(())
+        } else {
+            Err(ConfigError { err_type: ConfigErrorType::LaunchGroupNotExist(group_name.to_string()), config_path: None })
+        }
+    }
+
+    pub fn remove_launch_group(&mut self, group_name: &str) -> Result<(), ConfigError> {
+        if let Some(pos) = self.launch_groups.iter().position(|g| g.name == group_name) {
+            self.launch_groups.remove(pos);
+            Ok(())
+        } else {
+            Err(ConfigError { err_type: ConfigErrorType::LaunchGroupNotExist(group_name.to_string()), config_path: None })
+        }
+    }
+
+    pub fn get_launch_group(&self, group_name: &str) -> Option<&LaunchGroup> {
+        self.launch_groups.iter().find(|g| g.name == group_name)
+    }
+
+    pub fn get_launch_group_list(&self) -> Vec<&LaunchGroup> {
+        self.launch_groups.iter().collect()
+    }
+}
+
+fn find_category<'a>(categories: &'a [CategoryMetadata], category_name: &str) -> Option<&'a CategoryMetadata> {
+    for category in categories {
+        if category.name == category_name {
+            return Some(category);
+        }
+        if let Some(found) = find_category(&category.children, category_name) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn find_category_mut<'a>(categories: &'a mut [CategoryMetadata], category_name: &str) -> Option<&'a mut CategoryMetadata> {
+    for category in categories {
+        if category.name == category_name {
+            return Some(category);
+        }
+        if let Some(found) = find_category_mut(&mut category.children, category_name) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+// detach the category from the tree
+fn take_category(categories: &mut Vec<CategoryMetadata>, category_name: &str) -> Option<CategoryMetadata> {
+    if let Some(pos) = categories.iter().position(|c| c.name == category_name) {
+        return Some(categories.remove(pos));
+    }
+    categories.iter_mut().find_map(|category| take_category(&mut category.children, category_name))
+}
+
+fn for_each_category_mut(categories: &mut [CategoryMetadata], f: &mut impl FnMut(&mut CategoryMetadata)) {
+    for category in categories {
+        f(category);
+        for_each_category_mut(&mut category.children, f);
+    }
 }
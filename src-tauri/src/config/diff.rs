@@ -0,0 +1,350 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use super::Config;
+use super::structure::{AppMetadata, CategoryMetadata, ToolboxVersion};
+
+// the snapshot changes on every version scan, it is not worth a changelog entry
+const IGNORED_APP_FIELDS: [&str; 1] = ["snapshot"];
+// longer values, e.g. icon data urls, are cut in the markdown
+const MAX_MARKDOWN_VALUE_LEN: usize = 60;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    // null if the field is not set
+    pub old_value: Value,
+    pub new_value: Value
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRename {
+    pub old_name: String,
+    pub new_name: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppChange {
+    // the new name of renamed apps
+    pub name: String,
+    pub fields: Vec<FieldChange>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum CategoryChangeType {
+    Added,
+    Removed,
+    Changed
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryChange {
+    pub name: String,
+    pub change_type: CategoryChangeType,
+    // None for top-level categories, and for the side the category is not on
+    pub old_parent: Option<String>,
+    pub new_parent: Option<String>,
+    pub added_apps: Vec<String>,
+    pub removed_apps: Vec<String>,
+    pub apps_reordered: bool,
+    pub children_reordered: bool
+}
+
+// corresponding to the ConfigDiff interface in types.ts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiff {
+    pub old_version: Option<ToolboxVersion>,
+    pub new_version: Option<ToolboxVersion>,
+    // header, author, version, language and themes
+    pub settings: Vec<FieldChange>,
+    pub added_apps: Vec<String>,
+    pub removed_apps: Vec<String>,
+    pub renamed_apps: Vec<AppRename>,
+    pub changed_apps: Vec<AppChange>,
+    pub categories_reordered: bool,
+    pub categories: Vec<CategoryChange>
+}
+
+// a category with its place in the tree
+#[derive(Debug, Eq, PartialEq)]
+pub struct FlatCategory<'a> {
+    pub parent: Option<&'a str>,
+    pub apps: &'a [String],
+    pub children: Vec<&'a str>
+}
+
+pub fn flatten_categories<'a>(categories: &'a [CategoryMetadata], parent: Option<&'a str>, flat: &mut Vec<(&'a str, FlatCategory<'a>)>) {
+    for category in categories {
+        let children = category.children.iter().map(|child| child.name.as_str()).collect();
+        flat.push((&category.name, FlatCategory { parent, apps: &category.apps, children }));
+        flatten_categories(&category.children, Some(&category.name), flat);
+    }
+}
+
+// whether the items on both sides are in a different order, leaving out the added and removed ones
+pub fn is_reordered<T: AsRef<str>, U: AsRef<str>>(old: &[T], new: &[U]) -> bool {
+    let old_set: HashSet<&str> = old.iter().map(AsRef::as_ref).collect();
+    let new_set: HashSet<&str> = new.iter().map(AsRef::as_ref).collect();
+    let old_common = old.iter().map(AsRef::as_ref).filter(|item| new_set.contains(item));
+    let new_common = new.iter().map(AsRef::as_ref).filter(|item| old_set.contains(item));
+    !old_common.eq(new_common)
+}
+
+fn field_changes(old: Value, new: Value, ignored: &[&str]) -> Vec<FieldChange> {
+    let (Value::Object(old), Value::Object(new)) = (old, new) else {
+        return Vec::new();
+    };
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).filter(|field| !ignored.contains(&field.as_str())).collect();
+    fields.sort();
+    fields.dedup();
+    fields.into_iter().filter_map(|field| {
+        let old_value = old.get(field).cloned().unwrap_or(Value::Null);
+        let new_value = new.get(field).cloned().unwrap_or(Value::Null);
+        (old_value != new_value).then(|| FieldChange { field: field.clone(), old_value, new_value })
+    }).collect()
+}
+
+fn settings_value(config: &Config) -> Value {
+    json!({
+        "lang": config.lang,
+        "header_text": config.header_text,
+        "author": config.author,
+        "toolbox_version": config.toolbox_version,
+        "theme": config.theme,
+        "dark_theme": config.dark_theme
+    })
+}
+
+fn normalized_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").to_lowercase()
+}
+
+fn normalized_file_name(metadata: &AppMetadata) -> Option<String> {
+    metadata.kind.is_file().then(|| metadata.app_path.file_name().map(|name| name.to_string_lossy().to_lowercase())).flatten()
+}
+
+// pair the removed apps with the added ones pointing to the same path, or failing that to the only file of the same name
+fn detect_renames(old: &Config, new: &Config, removed: &mut Vec<String>, added: &mut Vec<String>) -> Vec<AppRename> {
+    let mut renames: Vec<AppRename> = Vec::new();
+    let mut take_pairs = |key: &dyn Fn(&AppMetadata) -> Option<String>, removed: &mut Vec<String>, added: &mut Vec<String>| {
+        let mut count: HashMap<String, (usize, usize)> = HashMap::new();
+        for name in removed.iter() {
+            if let Some(key) = key(&old.app_library[name]) {
+                count.entry(key).or_default().0 += 1;
+            }
+        }
+        for name in added.iter() {
+            if let Some(key) = key(&new.app_library[name]) {
+                count.entry(key).or_default().1 += 1;
+            }
+        }
+        // ambiguous keys are left alone
+        let unique = |key: &Option<String>| key.as_ref().is_some_and(|key| count.get(key) == Some(&(1, 1)));
+        removed.retain(|old_name| {
+            let old_key = key(&old.app_library[old_name]);
+            if !unique(&old_key) {
+                return true;
+            }
+            let pos = added.iter().position(|new_name| key(&new.app_library[new_name]) == old_key).unwrap();
+            renames.push(AppRename { old_name: old_name.clone(), new_name: added.remove(pos) });
+            false
+        });
+    };
+    take_pairs(&|metadata| Some(normalized_path(&metadata.app_path)), removed, added);
+    take_pairs(&normalized_file_name, removed, added);
+    renames.sort_by(|a, b| a.new_name.cmp(&b.new_name));
+    renames
+}
+
+fn diff_categories(old: &Config, new: &Config, renames: &[AppRename]) -> Vec<CategoryChange> {
+    let renamed: HashMap<&str, &str> = renames.iter().map(|rename| (rename.old_name.as_str(), rename.new_name.as_str())).collect();
+    let mut old_flat: Vec<(&str, FlatCategory)> = Vec::new();
+    flatten_categories(&old.categories, None, &mut old_flat);
+    let mut new_flat: Vec<(&str, FlatCategory)> = Vec::new();
+    flatten_categories(&new.categories, None, &mut new_flat);
+    let old_map: HashMap<&str, &FlatCategory> = old_flat.iter().map(|(name, category)| (*name, category)).collect();
+    let new_names: HashSet<&str> = new_flat.iter().map(|(name, _)| *name).collect();
+
+    let mut changes: Vec<CategoryChange> = Vec::new();
+    for (name, category) in &new_flat {
+        let new_parent = category.parent.map(String::from);
+        let Some(old_category) = old_map.get(name) else {
+            changes.push(CategoryChange {
+                name: name.to_string(),
+                change_type: CategoryChangeType::Added,
+                old_parent: None,
+                new_parent,
+                added_apps: category.apps.to_vec(),
+                removed_apps: Vec::new(),
+                apps_reordered: false,
+                children_reordered: false
+            });
+            continue;
+        };
+        // apps renamed since are still the same apps
+        let old_apps: Vec<&str> = old_category.apps.iter().map(|app| renamed.get(app.as_str()).copied().unwrap_or(app)).collect();
+        let change = CategoryChange {
+            name: name.to_string(),
+            change_type: CategoryChangeType::Changed,
+            old_parent: old_category.parent.map(String::from),
+            new_parent,
+            added_apps: category.apps.iter().filter(|app| !old_apps.contains(&app.as_str())).cloned().collect(),
+            removed_apps: old_apps.iter().filter(|app| !category.apps.iter().any(|new_app| new_app == *app)).map(|app| app.to_string()).collect(),
+            apps_reordered: is_reordered(&old_apps, category.apps),
+            children_reordered: is_reordered(&old_category.children, &category.children)
+        };
+        if change.old_parent != change.new_parent || !change.added_apps.is_empty() || !change.removed_apps.is_empty() || change.apps_reordered || change.children_reordered {
+            changes.push(change);
+        }
+    }
+    for (name, category) in old_flat.iter().filter(|(name, _)| !new_names.contains(name)) {
+        changes.push(CategoryChange {
+            name: name.to_string(),
+            change_type: CategoryChangeType::Removed,
+            old_parent: category.parent.map(String::from),
+            new_parent: None,
+            added_apps: Vec::new(),
+            removed_apps: category.apps.to_vec(),
+            apps_reordered: false,
+            children_reordered: false
+        });
+    }
+    changes
+}
+
+// the changes made from the old config to the new one
+pub fn diff_configs(old: &Config, new: &Config) -> ConfigDiff {
+    let mut removed_apps: Vec<String> = old.app_library.keys().filter(|name| !new.app_library.contains_key(*name)).cloned().collect();
+    let mut added_apps: Vec<String> = new.app_library.keys().filter(|name| !old.app_library.contains_key(*name)).cloned().collect();
+    removed_apps.sort();
+    added_apps.sort();
+    let renamed_apps = detect_renames(old, new, &mut removed_apps, &mut added_apps);
+
+    let mut changed_apps: Vec<AppChange> = new.app_library.iter().filter_map(|(name, metadata)| {
+        let old_name = renamed_apps.iter().find(|rename| rename.new_name == *name).map_or(name, |rename| &rename.old_name);
+        let old_metadata = old.app_library.get(old_name)?;
+        let fields = field_changes(json!(old_metadata), json!(metadata), &IGNORED_APP_FIELDS);
+        (!fields.is_empty()).then(|| AppChange { name: name.clone(), fields })
+    }).collect();
+    changed_apps.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let old_top: Vec<&str> = old.categories.iter().map(|category| category.name.as_str()).collect();
+    let new_top: Vec<&str> = new.categories.iter().map(|category| category.name.as_str()).collect();
+    ConfigDiff {
+        old_version: old.toolbox_version.clone(),
+        new_version: new.toolbox_version.clone(),
+        settings: field_changes(settings_value(old), settings_value(new), &[]),
+        categories_reordered: is_reordered(&old_top, &new_top),
+        categories: diff_categories(old, new, &renamed_apps),
+        added_apps,
+        removed_apps,
+        renamed_apps,
+        changed_apps
+    }
+}
+
+fn markdown_value(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::from("(none)"),
+        Value::String(text) => text.replace(['\r', '\n'], " "),
+        _ => value.to_string()
+    };
+    if text.chars().count() > MAX_MARKDOWN_VALUE_LEN {
+        let cut: String = text.chars().take(MAX_MARKDOWN_VALUE_LEN).collect();
+        return markdown_code(&format!("{}...", cut));
+    }
+    markdown_code(&text)
+}
+
+// a code span fenced by more backticks than the text contains in a row
+fn markdown_code(text: &str) -> String {
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    if text.starts_with('`') || text.ends_with('`') {
+        return format!("{} {} {}", fence, text, fence);
+    }
+    format!("{}{}{}", fence, text, fence)
+}
+
+fn markdown_field_change(change: &FieldChange) -> String {
+    format!("`{}`: {} -> {}", change.field, markdown_value(&change.old_value), markdown_value(&change.new_value))
+}
+
+fn parent_text(parent: &Option<String>) -> String {
+    parent.as_ref().map_or(String::from("the top level"), |parent| format!("**{}**", parent))
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty() && self.added_apps.is_empty() && self.removed_apps.is_empty() && self.renamed_apps.is_empty() &&
+            self.changed_apps.is_empty() && !self.categories_reordered && self.categories.is_empty()
+    }
+
+    // release notes listing the changes section by section
+    pub fn to_markdown(&self) -> String {
+        let title = match (&self.old_version, &self.new_version) {
+            (Some(old), Some(new)) => format!("# Changes from {} to {}", old, new),
+            (None, Some(new)) => format!("# Changes in {}", new),
+            _ => String::from("# Changes")
+        };
+        let mut lines: Vec<String> = vec![title];
+        if self.is_empty() {
+            lines.push(String::new());
+            lines.push(String::from("No changes."));
+        }
+        let mut section = |heading: &str, items: Vec<String>| {
+            if !items.is_empty() {
+                lines.push(String::new());
+                lines.push(format!("## {}", heading));
+                lines.push(String::new());
+                lines.extend(items.into_iter().map(|item| format!("- {}", item)));
+            }
+        };
+        section("Settings", self.settings.iter().map(markdown_field_change).collect());
+        section("Added apps", self.added_apps.iter().map(|name| format!("**{}**", name)).collect());
+        section("Removed apps", self.removed_apps.iter().map(|name| format!("**{}**", name)).collect());
+        section("Renamed apps", self.renamed_apps.iter().map(|rename| format!("**{}** -> **{}**", rename.old_name, rename.new_name)).collect());
+        section("Changed apps", self.changed_apps.iter().map(|change| {
+            let fields: Vec<String> = change.fields.iter().map(|field| format!("  - {}", markdown_field_change(field))).collect();
+            format!("**{}**\n{}", change.name, fields.join("\n"))
+        }).collect());
+
+        let mut category_items: Vec<String> = Vec::new();
+        if self.categories_reordered {
+            category_items.push(String::from("Top-level categories reordered"));
+        }
+        category_items.extend(self.categories.iter().map(|change| {
+            let mut parts: Vec<String> = Vec::new();
+            match change.change_type {
+                CategoryChangeType::Added => parts.push(format!("added under {}", parent_text(&change.new_parent))),
+                CategoryChangeType::Removed => parts.push(format!("removed from {}", parent_text(&change.old_parent))),
+                CategoryChangeType::Changed if change.old_parent != change.new_parent => {
+                    parts.push(format!("moved from {} to {}", parent_text(&change.old_parent), parent_text(&change.new_parent)));
+                }
+                CategoryChangeType::Changed => {}
+            }
+            if change.change_type != CategoryChangeType::Removed && !change.added_apps.is_empty() {
+                parts.push(format!("apps added: {}", change.added_apps.join(", ")));
+            }
+            if change.change_type != CategoryChangeType::Removed && !change.removed_apps.is_empty() {
+                parts.push(format!("apps removed: {}", change.removed_apps.join(", ")));
+            }
+            if change.apps_reordered {
+                parts.push(String::from("apps reordered"));
+            }
+            if change.children_reordered {
+                parts.push(String::from("subcategories reordered"));
+            }
+            format!("**{}**: {}", change.name, parts.join("; "))
+        }));
+        section("Categories", category_items);
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
@@ -1,6 +1,29 @@
 use std::fmt::Display;
 use std::io::Error as IoError;
 use serde_json::Error as SerdeError;
+use serde_yaml_ng::Error as YamlError;
+use toml::de::Error as TomlParseError;
+use toml::ser::Error as TomlWriteError;
+
+// the error of the format the config is parsed from or written to
+#[derive(Debug)]
+pub enum FormatError {
+    Json(SerdeError),
+    TomlParse(TomlParseError),
+    TomlWrite(TomlWriteError),
+    Yaml(YamlError)
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Json(e) => write!(f, "JSON: {}", e),
+            FormatError::TomlParse(e) => write!(f, "TOML: {}", e),
+            FormatError::TomlWrite(e) => write!(f, "TOML: {}", e),
+            FormatError::Yaml(e) => write!(f, "YAML: {}", e)
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct ConfigError {
@@ -13,33 +36,45 @@ impl Display for ConfigError {
         let config_path_str = self.config_path.clone().unwrap_or(String::from("<unknown>"));
         match &self.err_type {
             ConfigErrorType::ParseError(e) => write!(f, "Failed to parse config file '{}': {}", config_path_str, e),
+            ConfigErrorType::SerializeError(e) => write!(f, "Failed to serialize config file '{}': {}", config_path_str, e),
             ConfigErrorType::ReadError(e) => write!(f, "Failed to read config file '{}': {}", config_path_str, e),
             ConfigErrorType::WriteError(e) => write!(f, "Failed to write config file '{}': {}", config_path_str, e),
             ConfigErrorType::FileNotExist => write!(f, "Config file '{}' does not exist", config_path_str),
+            ConfigErrorType::ModifiedExternally => write!(f, "Config file '{}' has been modified externally", config_path_str),
             ConfigErrorType::AppExist(app_name) => write!(f, "App '{}' already exists", app_name),
             ConfigErrorType::AppNotExist(app_name) => write!(f, "App '{}' does not exists", app_name),
             ConfigErrorType::CategoryExist(category_name) => write!(f, "Category '{}' already exists", category_name),
             ConfigErrorType::CategoryNotExist(category_name) => write!(f, "Category '{}' does not exist", category_name),
+            ConfigErrorType::CategoryMoveIntoItself(category_name, parent_name) => {
+                write!(f, "Cannot move category '{}' into itself or its subcategory '{}'", category_name, parent_name)
+            },
             ConfigErrorType::AppExistInCategory(app_name, category_name) => {
                 write!(f, "App '{}' already exists in category '{}'", app_name, category_name)
             },
             ConfigErrorType::AppNotExistInCategory(app_name, category_name) => {
                 write!(f, "App '{}' does not exist in category '{}'", app_name, category_name)
-            }
+            },
+            ConfigErrorType::LaunchGroupExist(group_name) => write!(f, "Launch group '{}' already exists", group_name),
+            ConfigErrorType::LaunchGroupNotExist(group_name) => write!(f, "Launch group '{}' does not exist", group_name)
         }
     }
 }
 
 #[derive(Debug)]
 pub enum ConfigErrorType {
-    ParseError(SerdeError),
+    ParseError(FormatError),
+    SerializeError(FormatError),
     ReadError(IoError),
     WriteError(IoError),
     FileNotExist,
+    ModifiedExternally,
     AppExist(String),
     AppNotExist(String),
     CategoryExist(String),
     CategoryNotExist(String),
+    CategoryMoveIntoItself(String, String),
     AppExistInCategory(String, String),
-    AppNotExistInCategory(String, String)
+    AppNotExistInCategory(String, String),
+    LaunchGroupExist(String),
+    LaunchGroupNotExist(String)
 }
\ No newline at end of file
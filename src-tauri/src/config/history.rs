@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use super::Config;
+
+pub const DEFAULT_HISTORY_DEPTH: usize = 50;
+
+// snapshot-based edit history, kept in memory until the app closes
+#[derive(Clone, Debug)]
+pub struct EditHistory {
+    undo_stack: VecDeque<Config>,
+    redo_stack: Vec<Config>,
+    max_depth: usize
+}
+
+// corresponding to the EditHistoryState interface in types.ts
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EditHistoryState {
+    pub can_undo: bool,
+    pub can_redo: bool
+}
+
+impl EditHistory {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            max_depth
+        }
+    }
+
+    // record the config as it was before an edit
+    pub fn record(&mut self, snapshot: Config) {
+        if self.max_depth == 0 {
+            return;
+        }
+        if self.undo_stack.len() == self.max_depth {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(snapshot);
+        self.redo_stack.clear();
+    }
+
+    pub fn undo(&mut self, config: &mut Config) -> bool {
+        if let Some(snapshot) = self.undo_stack.pop_back() {
+            self.redo_stack.push(std::mem::replace(config, snapshot));
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn redo(&mut self, config: &mut Config) -> bool {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            self.undo_stack.push_back(std::mem::replace(config, snapshot));
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    pub fn state(&self) -> EditHistoryState {
+        EditHistoryState {
+            can_undo: !self.undo_stack.is_empty(),
+            can_redo: !self.redo_stack.is_empty()
+        }
+    }
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_DEPTH)
+    }
+}
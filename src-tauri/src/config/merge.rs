@@ -0,0 +1,509 @@
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use super::Config;
+use super::diff::{FlatCategory, flatten_categories, is_reordered};
+use super::structure::{AppMetadata, CategoryMetadata, LaunchGroup, SmartCategoryMetadata};
+
+// merged item by item instead of as a whole setting
+const COLLECTION_FIELDS: [&str; 5] = ["app_library", "categories", "smart_categories", "favorites", "launch_groups"];
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum MergeTarget {
+    Setting,
+    App,
+    Category,
+    // the order of the top-level categories, with an empty name
+    CategoryOrder,
+    SmartCategory,
+    LaunchGroup,
+    Favorites
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum MergeAction {
+    // only ours changed it, or both changed it in conflicting ways
+    KeptOurs,
+    TookTheirs,
+    // the changes of both sides were combined
+    Combined,
+    // one side removed it and the other did not change it
+    Removed
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum ConflictType {
+    BothModified,
+    // the modified side is kept
+    ModifiedAndRemoved,
+    BothReordered,
+    BothMoved,
+    // a launch group step of an app missing from the merged config, the step is dropped
+    MissingApp,
+    // a manual and a smart category of the same name, the manual one is kept
+    NameTaken
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeDecision {
+    pub target: MergeTarget,
+    pub name: String,
+    pub action: MergeAction
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldConflict {
+    pub field: String,
+    // null if there is no base or the field is not set
+    pub base_value: Value,
+    pub ours_value: Value,
+    pub theirs_value: Value
+}
+
+// conflicts are resolved with the value of ours, they are listed for the user to check
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    pub target: MergeTarget,
+    pub name: String,
+    pub conflict_type: ConflictType,
+    // the conflicting fields of apps and settings
+    pub fields: Vec<FieldConflict>
+}
+
+// corresponding to the MergeReport interface in types.ts
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    pub decisions: Vec<MergeDecision>,
+    pub conflicts: Vec<MergeConflict>
+}
+
+impl MergeReport {
+    fn decide(&mut self, target: MergeTarget, name: &str, action: MergeAction) {
+        self.decisions.push(MergeDecision { target, name: name.to_string(), action });
+    }
+
+    fn conflict(&mut self, target: MergeTarget, name: &str, conflict_type: ConflictType, fields: Vec<FieldConflict>) {
+        self.conflicts.push(MergeConflict { target, name: name.to_string(), conflict_type, fields });
+    }
+}
+
+// which side of a value to take
+enum Pick {
+    Same,
+    Ours,
+    Theirs,
+    Conflict
+}
+
+fn pick<T: PartialEq>(base: Option<&T>, ours: &T, theirs: &T) -> Pick {
+    if ours == theirs {
+        Pick::Same
+    } else if base == Some(ours) {
+        Pick::Theirs
+    } else if base == Some(theirs) {
+        Pick::Ours
+    } else {
+        Pick::Conflict
+    }
+}
+
+// the action of taking the side, missing on the side means removed
+fn side_action<T>(value: Option<T>, side: MergeAction) -> MergeAction {
+    if value.is_some() { side } else { MergeAction::Removed }
+}
+
+// merge two objects field by field, the conflicting fields keep the value of ours
+fn merge_fields(base: Option<&Value>, ours: &Value, theirs: &Value) -> (Value, MergeAction, Vec<FieldConflict>) {
+    let empty = Map::new();
+    let object = |value: Option<&Value>| value.and_then(Value::as_object).unwrap_or(&empty).clone();
+    let (base, ours, theirs) = (base.map(|base| object(Some(base))), object(Some(ours)), object(Some(theirs)));
+    let mut fields: Vec<&String> = ours.keys().chain(theirs.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    let mut merged = ours.clone();
+    let (mut took_ours, mut took_theirs) = (false, false);
+    let mut conflicts: Vec<FieldConflict> = Vec::new();
+    for field in fields {
+        let base_value = base.as_ref().map(|base| base.get(field).unwrap_or(&Value::Null));
+        let ours_value = ours.get(field).unwrap_or(&Value::Null);
+        let theirs_value = theirs.get(field).unwrap_or(&Value::Null);
+        match pick(base_value, ours_value, theirs_value) {
+            Pick::Same => {}
+            Pick::Ours => took_ours = true,
+            Pick::Theirs => {
+                merged.insert(field.clone(), theirs_value.clone());
+                took_theirs = true;
+            }
+            Pick::Conflict => conflicts.push(FieldConflict {
+                field: field.clone(),
+                base_value: base_value.cloned().unwrap_or(Value::Null),
+                ours_value: ours_value.clone(),
+                theirs_value: theirs_value.clone()
+            })
+        }
+    }
+    let action = match (took_ours || !conflicts.is_empty(), took_theirs) {
+        (true, true) => MergeAction::Combined,
+        (false, true) => MergeAction::TookTheirs,
+        _ => MergeAction::KeptOurs
+    };
+    (Value::Object(merged), action, conflicts)
+}
+
+// keep the items both sides kept and the items either side added, in the order of the side that reordered them
+// returns whether both sides reordered the items differently, in which case the order of ours is kept
+fn merge_order<T: AsRef<str> + Clone + PartialEq>(base: Option<&[T]>, ours: &[T], theirs: &[T]) -> (Vec<T>, bool) {
+    let in_base = |item: &T| base.is_some_and(|base| base.contains(item));
+    let keep = |item: &T| (ours.contains(item) && theirs.contains(item)) || !in_base(item);
+    let ours_reordered = base.is_some_and(|base| is_reordered(base, ours));
+    let theirs_reordered = base.is_some_and(|base| is_reordered(base, theirs));
+    let (primary, secondary) = if theirs_reordered && !ours_reordered { (theirs, ours) } else { (ours, theirs) };
+
+    let mut merged: Vec<T> = primary.iter().filter(|item| keep(item)).cloned().collect();
+    for (index, item) in secondary.iter().enumerate() {
+        if !keep(item) || merged.contains(item) {
+            continue;
+        }
+        // right after the item it follows on its own side
+        let pos = secondary[..index].iter().rev()
+            .find_map(|previous| merged.iter().position(|merged_item| merged_item == previous))
+            .map_or(0, |pos| pos + 1);
+        merged.insert(pos, item.clone());
+    }
+    (merged, ours_reordered && theirs_reordered && is_reordered(ours, theirs))
+}
+
+fn merge_list(report: &mut MergeReport, target: MergeTarget, name: &str, base: Option<&[String]>, ours: &[String], theirs: &[String]) -> Vec<String> {
+    let (merged, conflict) = merge_order(base, ours, theirs);
+    if conflict {
+        report.conflict(target.clone(), name, ConflictType::BothReordered, Vec::new());
+    }
+    if merged != ours {
+        report.decide(target, name, if merged == theirs { MergeAction::TookTheirs } else { MergeAction::Combined });
+    }
+    merged
+}
+
+fn merge_settings(report: &mut MergeReport, base: Option<&Config>, ours: &Config, theirs: &Config) -> Config {
+    let to_object = |config: &Config| match serde_json::to_value(config) {
+        Ok(Value::Object(object)) => object,
+        _ => Map::new()
+    };
+    let base = base.map(to_object);
+    let theirs = to_object(theirs);
+    let mut merged = to_object(ours);
+    let fields: Vec<String> = merged.keys().filter(|field| !COLLECTION_FIELDS.contains(&field.as_str())).cloned().collect();
+    for field in fields {
+        let base_value = base.as_ref().map(|base| base.get(&field).unwrap_or(&Value::Null));
+        let ours_value = merged[&field].clone();
+        let theirs_value = theirs.get(&field).unwrap_or(&Value::Null);
+        match pick(base_value, &ours_value, theirs_value) {
+            Pick::Same => {}
+            Pick::Ours => report.decide(MergeTarget::Setting, &field, MergeAction::KeptOurs),
+            Pick::Theirs => {
+                merged.insert(field.clone(), theirs_value.clone());
+                report.decide(MergeTarget::Setting, &field, MergeAction::TookTheirs);
+            }
+            Pick::Conflict => {
+                report.decide(MergeTarget::Setting, &field, MergeAction::KeptOurs);
+                report.conflict(MergeTarget::Setting, &field, ConflictType::BothModified, vec![FieldConflict {
+                    field: field.clone(),
+                    base_value: base_value.cloned().unwrap_or(Value::Null),
+                    ours_value,
+                    theirs_value: theirs_value.clone()
+                }]);
+            }
+        }
+    }
+    serde_json::from_value(Value::Object(merged)).unwrap_or_else(|_| ours.clone())
+}
+
+fn merge_apps(report: &mut MergeReport, base: Option<&Config>, ours: &Config, theirs: &Config) -> HashMap<String, AppMetadata> {
+    let mut names: Vec<&String> = ours.app_library.keys().chain(theirs.app_library.keys()).collect();
+    names.sort();
+    names.dedup();
+    let mut merged: HashMap<String, AppMetadata> = HashMap::new();
+    for name in names {
+        let base_app = base.and_then(|base| base.app_library.get(name));
+        let ours_app = ours.app_library.get(name);
+        let theirs_app = theirs.app_library.get(name);
+        let app = match pick(Some(&base_app), &ours_app, &theirs_app) {
+            Pick::Same => ours_app.cloned(),
+            Pick::Ours => {
+                report.decide(MergeTarget::App, name, side_action(ours_app, MergeAction::KeptOurs));
+                ours_app.cloned()
+            }
+            Pick::Theirs => {
+                report.decide(MergeTarget::App, name, side_action(theirs_app, MergeAction::TookTheirs));
+                theirs_app.cloned()
+            }
+            Pick::Conflict => match (ours_app, theirs_app) {
+                (Some(ours_app), Some(theirs_app)) => {
+                    let to_value = |app: &AppMetadata| serde_json::to_value(app).unwrap_or(Value::Null);
+                    let (value, action, conflicts) = merge_fields(base_app.map(to_value).as_ref(), &to_value(ours_app), &to_value(theirs_app));
+                    report.decide(MergeTarget::App, name, action);
+                    if !conflicts.is_empty() {
+                        report.conflict(MergeTarget::App, name, ConflictType::BothModified, conflicts);
+                    }
+                    Some(serde_json::from_value(value).unwrap_or_else(|_| ours_app.clone()))
+                }
+                (ours_app, theirs_app) => {
+                    report.decide(MergeTarget::App, name, if ours_app.is_some() { MergeAction::KeptOurs } else { MergeAction::TookTheirs });
+                    report.conflict(MergeTarget::App, name, ConflictType::ModifiedAndRemoved, Vec::new());
+                    ours_app.or(theirs_app).cloned()
+                }
+            }
+        };
+        if let Some(app) = app {
+            merged.insert(name.clone(), app);
+        }
+    }
+    merged
+}
+
+// smart categories and launch groups, merged by name as a whole
+fn merge_named<T: Clone + PartialEq>(report: &mut MergeReport, target: MergeTarget, base: Option<&[T]>, ours: &[T], theirs: &[T], name_of: fn(&T) -> &String) -> Vec<T> {
+    let names = |items: &[T]| -> Vec<String> { items.iter().map(|item| name_of(item).clone()).collect() };
+    let find = |items: &[T], name: &str| items.iter().find(|item| name_of(item) == name).cloned();
+    let ours_names = names(ours);
+    let theirs_names = names(theirs);
+    let base_names = base.map(names);
+    // removed names are dropped by the order, the modified ones are brought back
+    let (mut order, conflict) = merge_order(base_names.as_deref(), &ours_names, &theirs_names);
+    if conflict {
+        report.conflict(target.clone(), "", ConflictType::BothReordered, Vec::new());
+    }
+    for name in ours_names.iter().chain(theirs_names.iter()) {
+        if !order.contains(name) {
+            order.push(name.clone());
+        }
+    }
+    order.into_iter().filter_map(|name| {
+        let base_item = base.and_then(|base| find(base, &name));
+        let ours_item = find(ours, &name);
+        let theirs_item = find(theirs, &name);
+        match pick(Some(&base_item), &ours_item, &theirs_item) {
+            Pick::Same => ours_item,
+            Pick::Ours => {
+                report.decide(target.clone(), &name, side_action(ours_item.as_ref(), MergeAction::KeptOurs));
+                ours_item
+            }
+            Pick::Theirs => {
+                report.decide(target.clone(), &name, side_action(theirs_item.as_ref(), MergeAction::TookTheirs));
+                theirs_item
+            }
+            Pick::Conflict => {
+                let both = ours_item.is_some() && theirs_item.is_some();
+                report.decide(target.clone(), &name, if ours_item.is_some() { MergeAction::KeptOurs } else { MergeAction::TookTheirs });
+                report.conflict(target.clone(), &name, if both { ConflictType::BothModified } else { ConflictType::ModifiedAndRemoved }, Vec::new());
+                ours_item.or(theirs_item)
+            }
+        }
+    }).collect()
+}
+
+// a merged category before it is put back into the tree
+struct MergedCategory {
+    parent: Option<String>,
+    apps: Vec<String>,
+    children: Vec<String>
+}
+
+impl From<&FlatCategory<'_>> for MergedCategory {
+    fn from(category: &FlatCategory) -> Self {
+        Self {
+            parent: category.parent.map(String::from),
+            apps: category.apps.to_vec(),
+            children: category.children.iter().map(|child| child.to_string()).collect()
+        }
+    }
+}
+
+fn flatten_config(config: &Config) -> Vec<(&str, FlatCategory<'_>)> {
+    let mut flat: Vec<(&str, FlatCategory)> = Vec::new();
+    flatten_categories(&config.categories, None, &mut flat);
+    flat
+}
+
+fn find_category<'b, 'a>(flat: &'b Option<Vec<(&'a str, FlatCategory<'a>)>>, name: &str) -> Option<&'b FlatCategory<'a>> {
+    flat.as_ref().and_then(|flat| flat.iter().find(|(flat_name, _)| *flat_name == name).map(|(_, category)| category))
+}
+
+fn build_category_tree(name: &str, merged: &HashMap<String, MergedCategory>, children_of: &HashMap<Option<String>, Vec<String>>, apps: &HashMap<String, AppMetadata>) -> CategoryMetadata {
+    let category = &merged[name];
+    let mut category_apps: Vec<String> = Vec::new();
+    for app in &category.apps {
+        if apps.contains_key(app) && !category_apps.contains(app) {
+            category_apps.push(app.clone());
+        }
+    }
+    CategoryMetadata {
+        name: name.to_string(),
+        apps: category_apps,
+        children: children_of.get(&Some(name.to_string())).map_or(Vec::new(), |children| {
+            children.iter().map(|child| build_category_tree(child, merged, children_of, apps)).collect()
+        })
+    }
+}
+
+fn merge_categories(report: &mut MergeReport, base: Option<&Config>, ours: &Config, theirs: &Config, apps: &HashMap<String, AppMetadata>) -> Vec<CategoryMetadata> {
+    let base_flat = base.map(flatten_config);
+    let ours_flat = flatten_config(ours);
+    let theirs_flat = flatten_config(theirs);
+
+    let mut names: Vec<&str> = ours_flat.iter().map(|(name, _)| *name).collect();
+    names.extend(theirs_flat.iter().map(|(name, _)| *name).filter(|name| !ours_flat.iter().any(|(ours_name, _)| ours_name == name)));
+    let (ours_flat, theirs_flat) = (Some(ours_flat), Some(theirs_flat));
+    let mut merged: HashMap<String, MergedCategory> = HashMap::new();
+    for name in names {
+        let base_category = find_category(&base_flat, name);
+        let ours_category = find_category(&ours_flat, name);
+        let theirs_category = find_category(&theirs_flat, name);
+        let category = match pick(Some(&base_category), &ours_category, &theirs_category) {
+            Pick::Same => ours_category.map(MergedCategory::from),
+            Pick::Ours => {
+                report.decide(MergeTarget::Category, name, side_action(ours_category, MergeAction::KeptOurs));
+                ours_category.map(MergedCategory::from)
+            }
+            Pick::Theirs => {
+                report.decide(MergeTarget::Category, name, side_action(theirs_category, MergeAction::TookTheirs));
+                theirs_category.map(MergedCategory::from)
+            }
+            Pick::Conflict => match (ours_category, theirs_category) {
+                (Some(ours_category), Some(theirs_category)) => {
+                    report.decide(MergeTarget::Category, name, MergeAction::Combined);
+                    let parent = match pick(base_category.map(|base| &base.parent), &ours_category.parent, &theirs_category.parent) {
+                        Pick::Theirs => theirs_category.parent,
+                        Pick::Conflict => {
+                            report.conflict(MergeTarget::Category, name, ConflictType::BothMoved, Vec::new());
+                            ours_category.parent
+                        }
+                        _ => ours_category.parent
+                    };
+                    let (apps, apps_conflict) = merge_order(base_category.map(|base| base.apps), ours_category.apps, theirs_category.apps);
+                    let (children, children_conflict) = merge_order(base_category.map(|base| base.children.as_slice()), &ours_category.children, &theirs_category.children);
+                    if apps_conflict || children_conflict {
+                        report.conflict(MergeTarget::Category, name, ConflictType::BothReordered, Vec::new());
+                    }
+                    Some(MergedCategory {
+                        parent: parent.map(String::from),
+                        apps,
+                        children: children.iter().map(|child| child.to_string()).collect()
+                    })
+                }
+                (ours_category, theirs_category) => {
+                    report.decide(MergeTarget::Category, name, if ours_category.is_some() { MergeAction::KeptOurs } else { MergeAction::TookTheirs });
+                    report.conflict(MergeTarget::Category, name, ConflictType::ModifiedAndRemoved, Vec::new());
+                    ours_category.or(theirs_category).map(MergedCategory::from)
+                }
+            }
+        };
+        if let Some(category) = category {
+            merged.insert(name.to_string(), category);
+        }
+    }
+
+    // categories whose parent is gone, or that ended up inside themselves, are moved to the top level
+    let names: Vec<String> = names_in_order(&merged, &ours_flat, &theirs_flat);
+    for name in &names {
+        let mut visited: HashSet<&str> = HashSet::from([name.as_str()]);
+        let mut parent = merged[name].parent.as_deref();
+        let mut detach = false;
+        while let Some(parent_name) = parent {
+            if !merged.contains_key(parent_name) || !visited.insert(parent_name) {
+                detach = true;
+                break;
+            }
+            parent = merged[parent_name].parent.as_deref();
+        }
+        if detach {
+            merged.get_mut(name).unwrap().parent = None;
+        }
+    }
+
+    let top = |flat: &Option<Vec<(&str, FlatCategory)>>| -> Vec<String> {
+        flat.iter().flatten().filter(|(_, category)| category.parent.is_none()).map(|(name, _)| name.to_string()).collect()
+    };
+    let base_top = base_flat.is_some().then(|| top(&base_flat));
+    let top_order = merge_list(report, MergeTarget::CategoryOrder, "", base_top.as_deref(), &top(&ours_flat), &top(&theirs_flat));
+
+    // the children of each parent in the merged order, followed by the ones moved in from elsewhere
+    let mut children_of: HashMap<Option<String>, Vec<String>> = HashMap::new();
+    for parent in std::iter::once(None).chain(names.iter().map(|name| Some(name.clone()))) {
+        let order = match &parent {
+            Some(parent) => &merged[parent].children,
+            None => &top_order
+        };
+        let mut children: Vec<String> = order.iter().filter(|child| merged.get(*child).is_some_and(|child| child.parent == parent)).cloned().collect();
+        children.extend(names.iter().filter(|name| merged[*name].parent == parent && !children.contains(name)).cloned().collect::<Vec<String>>());
+        children_of.insert(parent, children);
+    }
+    children_of[&None].iter().map(|name| build_category_tree(name, &merged, &children_of, apps)).collect()
+}
+
+fn names_in_order(merged: &HashMap<String, MergedCategory>, ours: &Option<Vec<(&str, FlatCategory)>>, theirs: &Option<Vec<(&str, FlatCategory)>>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (name, _) in ours.iter().flatten().chain(theirs.iter().flatten()) {
+        if merged.contains_key(*name) && !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+// drop the steps of the apps missing from the merged config, e.g. removed on one side and added to a group on the other
+fn drop_missing_steps(report: &mut MergeReport, sides: [Option<&Config>; 3], groups: &mut [LaunchGroup], apps: &HashMap<String, AppMetadata>) {
+    let step_value = |side: Option<&Config>, group_name: &str, app: &str| side
+        .and_then(|config| config.launch_groups.iter().find(|group| group.name == group_name))
+        .and_then(|group| group.steps.iter().find(|step| step.app == app))
+        .and_then(|step| serde_json::to_value(step).ok())
+        .unwrap_or(Value::Null);
+    for group in groups {
+        let mut fields: Vec<FieldConflict> = Vec::new();
+        group.steps.retain(|step| {
+            if apps.contains_key(&step.app) {
+                return true;
+            }
+            if !fields.iter().any(|field| field.field == step.app) {
+                let [base, ours, theirs] = sides.map(|side| step_value(side, &group.name, &step.app));
+                fields.push(FieldConflict { field: step.app.clone(), base_value: base, ours_value: ours, theirs_value: theirs });
+            }
+            false
+        });
+        if !fields.is_empty() {
+            report.conflict(MergeTarget::LaunchGroup, &group.name, ConflictType::MissingApp, fields);
+        }
+    }
+}
+
+// merge the configs both sides changed from the base, or all the items of both sides without a base
+// conflicts keep the value of ours
+pub fn merge_configs(base: Option<&Config>, ours: &Config, theirs: &Config) -> (Config, MergeReport) {
+    let mut report = MergeReport::default();
+    let mut config = merge_settings(&mut report, base, ours, theirs);
+    config.app_library = merge_apps(&mut report, base, ours, theirs);
+    config.categories = merge_categories(&mut report, base, ours, theirs, &config.app_library);
+    config.smart_categories = merge_named(&mut report, MergeTarget::SmartCategory, base.map(|base| base.smart_categories.as_slice()),
+        &ours.smart_categories, &theirs.smart_categories, |category: &SmartCategoryMetadata| &category.name);
+    // category names are unique across the manual and the smart categories
+    let smart_categories = std::mem::take(&mut config.smart_categories);
+    for category in smart_categories {
+        if config.get_category(&category.name).is_some() {
+            report.decide(MergeTarget::SmartCategory, &category.name, MergeAction::Removed);
+            report.conflict(MergeTarget::SmartCategory, &category.name, ConflictType::NameTaken, Vec::new());
+        } else {
+            config.smart_categories.push(category);
+        }
+    }
+    config.launch_groups = merge_named(&mut report, MergeTarget::LaunchGroup, base.map(|base| base.launch_groups.as_slice()),
+        &ours.launch_groups, &theirs.launch_groups, |group: &LaunchGroup| &group.name);
+    drop_missing_steps(&mut report, [base, Some(ours), Some(theirs)], &mut config.launch_groups, &config.app_library);
+    let favorites = merge_list(&mut report, MergeTarget::Favorites, "", base.map(|base| base.favorites.as_slice()), &ours.favorites, &theirs.favorites);
+    config.favorites = favorites.into_iter().filter(|app| config.app_library.contains_key(app)).collect();
+    (config, report)
+}
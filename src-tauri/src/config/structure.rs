@@ -1,6 +1,7 @@
 use std::fmt::Display;
 use std::path::PathBuf;
-use serde::{Deserialize, Serialize};
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
 use super::super::commands::AppMetadataWithName;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -12,28 +13,319 @@ impl Display for ToolboxVersion {
     }
 }
 
+// gradients drawn "to top right" before the angle could be configured
+pub const DEFAULT_GRADIENT_ANGLE: u16 = 45;
+
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
-#[serde(tag = "type")]
+#[serde(tag = "type", try_from = "ThemeDef")]
 pub enum Theme {
     Solid { color: ThemeColor },
-    LinearGradient { from: ThemeColor, to: ThemeColor },
-    RadialGradient { from: ThemeColor, to: ThemeColor }
+    // angle in degrees, as in CSS linear-gradient()
+    LinearGradient { stops: Vec<ColorStop>, angle: u16 },
+    RadialGradient { stops: Vec<ColorStop> },
+    // path of the image file stored next to the config, relative to it
+    Image { path: PathBuf, fit: ImageFit }
+}
+
+// the on-disk form of Theme, accepting the two-color gradients of older configs
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum ThemeDef {
+    Solid { color: ThemeColor },
+    LinearGradient {
+        #[serde(default)]
+        stops: Vec<ColorStop>,
+        angle: Option<u16>,
+        from: Option<ThemeColor>,
+        to: Option<ThemeColor>
+    },
+    RadialGradient {
+        #[serde(default)]
+        stops: Vec<ColorStop>,
+        from: Option<ThemeColor>,
+        to: Option<ThemeColor>
+    },
+    Image {
+        path: PathBuf,
+        #[serde(default)]
+        fit: ImageFit
+    }
+}
+
+impl TryFrom<ThemeDef> for Theme {
+    type Error = String;
+
+    fn try_from(theme: ThemeDef) -> Result<Self, Self::Error> {
+        Ok(match theme {
+            ThemeDef::Solid { color } => Theme::Solid { color },
+            ThemeDef::LinearGradient { stops, angle, from, to } => Theme::LinearGradient {
+                stops: gradient_stops(stops, from, to)?,
+                angle: angle.unwrap_or(DEFAULT_GRADIENT_ANGLE) % 360
+            },
+            ThemeDef::RadialGradient { stops, from, to } => Theme::RadialGradient { stops: gradient_stops(stops, from, to)? },
+            ThemeDef::Image { path, fit } => Theme::Image { path, fit }
+        })
+    }
+}
+
+fn gradient_stops(stops: Vec<ColorStop>, from: Option<ThemeColor>, to: Option<ThemeColor>) -> Result<Vec<ColorStop>, String> {
+    let stops = match (stops.is_empty(), from, to) {
+        (false, _, _) => stops,
+        (true, Some(from), Some(to)) => vec![ColorStop { color: from, position: 0 }, ColorStop { color: to, position: 100 }],
+        _ => return Err(String::from("gradient requires either `stops` or `from` and `to`"))
+    };
+    if stops.len() < 2 {
+        return Err(String::from("gradient requires at least two color stops"));
+    }
+    if stops.iter().any(|stop| stop.position > 100) {
+        return Err(String::from("color stop position must be a percentage between 0 and 100"));
+    }
+    Ok(stops)
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ColorStop {
+    pub color: ThemeColor,
+    // percentage along the gradient line
+    pub position: u8
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub enum ImageFit {
+    #[default]
+    Cover,
+    Contain,
+    Tile
+}
+
+// alpha is a percentage, like saturation and lightness
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 #[serde(tag = "type")]
 pub enum ThemeColor {
     RGB { r: u8, g: u8, b: u8 },
     HSL { h: u16, s: u8, l: u8 },
+    RGBA { r: u8, g: u8, b: u8, #[serde(deserialize_with = "alpha_percentage")] a: u8 },
+    HSLA { h: u16, s: u8, l: u8, #[serde(deserialize_with = "alpha_percentage")] a: u8 }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+fn alpha_percentage<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let a = u8::deserialize(deserializer)?;
+    if a > 100 {
+        return Err(D::Error::custom("alpha must be a percentage between 0 and 100"));
+    }
+    Ok(a)
+}
+
+impl ThemeColor {
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            ThemeColor::RGB { r, g, b } | ThemeColor::RGBA { r, g, b, .. } => (r, g, b),
+            ThemeColor::HSL { h, s, l } | ThemeColor::HSLA { h, s, l, .. } => hsl_to_rgb(h, s, l)
+        }
+    }
+
+    pub fn to_hsl(&self) -> (u16, u8, u8) {
+        match *self {
+            ThemeColor::RGB { r, g, b } | ThemeColor::RGBA { r, g, b, .. } => rgb_to_hsl(r, g, b),
+            ThemeColor::HSL { h, s, l } | ThemeColor::HSLA { h, s, l, .. } => (h, s, l)
+        }
+    }
+
+    // alpha in percent, 100 for opaque colors
+    pub fn alpha(&self) -> u8 {
+        match *self {
+            ThemeColor::RGBA { a, .. } | ThemeColor::HSLA { a, .. } => a.min(100),
+            _ => 100
+        }
+    }
+
+    // the opaque color seen when drawn over the backdrop
+    pub fn blend_over(&self, backdrop: (u8, u8, u8)) -> (u8, u8, u8) {
+        let (r, g, b) = self.to_rgb();
+        let alpha = self.alpha() as f64 / 100.0;
+        let blend = |c: u8, bc: u8| (c as f64 * alpha + bc as f64 * (1.0 - alpha)).round() as u8;
+        (blend(r, backdrop.0), blend(g, backdrop.1), blend(b, backdrop.2))
+    }
+
+    // WCAG relative luminance, translucent colors are drawn over the white window background
+    pub fn relative_luminance(&self) -> f64 {
+        let (r, g, b) = self.blend_over((255, 255, 255));
+        let linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        };
+        0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+    }
+
+    // WCAG contrast ratio, from 1 (no contrast) to 21 (black on white)
+    pub fn contrast_ratio(&self, other: &ThemeColor) -> f64 {
+        let (l1, l2) = (self.relative_luminance(), other.relative_luminance());
+        (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+    }
+}
+
+fn hsl_to_rgb(h: u16, s: u8, l: u8) -> (u8, u8, u8) {
+    let h = (h % 360) as f64;
+    let (s, l) = (s.min(100) as f64 / 100.0, l.min(100) as f64 / 100.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = match h as u16 / 60 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x)
+    };
+    let to_u8 = |v: f64| ((v + m) * 255.0).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (u16, u8, u8) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0, 0, (l * 100.0).round() as u8);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * (((g - b) / d).rem_euclid(6.0))
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    ((h.round() as u16) % 360, (s * 100.0).round() as u8, (l * 100.0).round() as u8)
+}
+
+// how launch_app treats apps whose files do not match the integrity manifest
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub enum IntegrityPolicy {
+    #[default]
+    Off,
+    Confirm,
+    Enforce
+}
+
+// what launch_app does when the app it launched before is still running
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub enum LaunchPolicy {
+    #[default]
+    AllowMultiple,
+    // bring the running instance to the front instead
+    SingleInstance,
+    // let the user decide whether to launch another instance
+    Ask
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
 pub struct AppMetadata {
     pub app_path: PathBuf,
     pub launch_args: String,
     pub working_dir: PathBuf,
     pub desc: String,
-    pub icon_url: String
+    pub icon_url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub hooks: LaunchHooks,
+    // redirect the console output of the app to log files instead of discarding it
+    #[serde(default)]
+    pub capture_output: bool,
+    #[serde(default)]
+    pub launch_policy: LaunchPolicy,
+    #[serde(default)]
+    pub kind: AppKind,
+    // the icon was generated from the name, so it is generated again when the name changes
+    #[serde(default)]
+    pub generated_icon: bool,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub version_source: VersionSource,
+    #[serde(default)]
+    pub publisher: String,
+    #[serde(default)]
+    pub snapshot: Option<AppSnapshot>
+}
+
+impl AppMetadata {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|app_tag| app_tag.eq_ignore_ascii_case(tag))
+    }
+
+    // whether the entry can be launched: the files exist, or the url is one we open
+    pub fn is_target_valid(&self) -> bool {
+        match self.kind {
+            AppKind::Executable => self.app_path.is_file() && self.working_dir.is_dir(),
+            AppKind::Document => self.app_path.is_file(),
+            AppKind::Folder => self.app_path.is_dir(),
+            AppKind::Url => is_supported_url(&self.app_path.to_string_lossy())
+        }
+    }
+}
+
+// urls of other schemes may start arbitrary programs registered for them
+pub const SUPPORTED_URL_SCHEMES: [&str; 4] = ["http", "https", "ftp", "mailto"];
+
+pub fn is_supported_url(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return false;
+    };
+    if !SUPPORTED_URL_SCHEMES.iter().any(|supported| supported.eq_ignore_ascii_case(scheme)) ||
+        url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return false;
+    }
+    if scheme.eq_ignore_ascii_case("mailto") {
+        !rest.is_empty()
+    } else {
+        // a host is required
+        rest.strip_prefix("//").is_some_and(|rest| !rest.is_empty() && !rest.starts_with('/'))
+    }
+}
+
+// what an entry points to, which decides how it is checked and launched
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub enum AppKind {
+    #[default]
+    Executable,
+    // opened by the default program of its file type
+    Document,
+    Folder,
+    // app_path holds the url, opened by the default browser
+    Url
+}
+
+impl AppKind {
+    // whether app_path is a file that can be hashed
+    pub fn is_file(&self) -> bool {
+        matches!(self, AppKind::Executable | AppKind::Document)
+    }
+}
+
+// where the version of an app comes from, which decides whether a scan reads it again
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub enum VersionSource {
+    // typed in, kept as it is
+    #[default]
+    Manual,
+    // the version resource of a windows executable
+    VersionInfo,
+    // the output of running the app with --version
+    Probe
+}
+
+// the app file when the versions were last scanned, to tell which apps were updated since
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct AppSnapshot {
+    pub size: u64,
+    pub sha256: String,
+    // seconds since the unix epoch, the file is only hashed again when its size or modification time changes
+    pub modified: Option<u64>,
+    pub version: String
 }
 
 impl From<&AppMetadataWithName> for AppMetadata {
@@ -43,7 +335,17 @@ impl From<&AppMetadataWithName> for AppMetadata {
             launch_args: metadata.launch_args.clone(),
             working_dir: metadata.working_dir.clone(),
             desc: metadata.desc.clone(),
-            icon_url: metadata.icon_url.clone()
+            icon_url: metadata.icon_url.clone(),
+            tags: metadata.tags.clone(),
+            hooks: metadata.hooks.clone(),
+            capture_output: metadata.capture_output,
+            launch_policy: metadata.launch_policy.clone(),
+            kind: metadata.kind.clone(),
+            generated_icon: metadata.generated_icon,
+            version: metadata.version.clone(),
+            version_source: metadata.version_source.clone(),
+            publisher: metadata.publisher.clone(),
+            snapshot: metadata.snapshot.clone()
         }
     }
 }
@@ -51,5 +353,203 @@ impl From<&AppMetadataWithName> for AppMetadata {
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct CategoryMetadata {
     pub name: String,
-    pub apps: Vec<String>
-}
\ No newline at end of file
+    pub apps: Vec<String>,
+    #[serde(default)]
+    pub children: Vec<CategoryMetadata>
+}
+
+impl CategoryMetadata {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            apps: Vec::new(),
+            children: Vec::new()
+        }
+    }
+
+    // whether the category itself or any of its descendants has the given name
+    pub fn contains(&self, category_name: &str) -> bool {
+        self.name == category_name || self.children.iter().any(|c| c.contains(category_name))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CategoryTreeNode {
+    pub name: String,
+    pub children: Vec<CategoryTreeNode>
+}
+
+impl From<&CategoryMetadata> for CategoryTreeNode {
+    fn from(category: &CategoryMetadata) -> Self {
+        Self {
+            name: category.name.clone(),
+            children: category.children.iter().map(CategoryTreeNode::from).collect()
+        }
+    }
+}
+
+// an app belongs to a smart category if it matches every criterion that is set
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub struct SmartCategoryQuery {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub path_pattern: Option<String>,
+    #[serde(default)]
+    pub name_contains: Option<String>,
+    #[serde(default)]
+    pub file_types: Vec<String>
+}
+
+impl SmartCategoryQuery {
+    pub fn matches(&self, app_name: &str, metadata: &AppMetadata) -> bool {
+        if !self.tags.iter().all(|tag| metadata.has_tag(tag)) {
+            return false;
+        }
+        if let Some(pattern) = &self.path_pattern {
+            let match_options = MatchOptions { case_sensitive: false, ..MatchOptions::new() };
+            if !Pattern::new(pattern).is_ok_and(|p| p.matches_path_with(&metadata.app_path, match_options)) {
+                return false;
+            }
+        }
+        if let Some(name_contains) = &self.name_contains &&
+            !app_name.to_lowercase().contains(&name_contains.to_lowercase()) {
+            return false;
+        }
+        if !self.file_types.is_empty() {
+            let Some(ext) = metadata.app_path.extension() else {
+                return false;
+            };
+            if !self.file_types.iter().any(|file_type| ext.eq_ignore_ascii_case(file_type.trim_start_matches('.'))) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// apps started together in order by launch_group
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct LaunchGroup {
+    pub name: String,
+    pub steps: Vec<LaunchStep>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub struct LaunchHooks {
+    // run before the app starts, the launch is aborted if it fails with abort_on_failure set
+    #[serde(default)]
+    pub pre_launch: Option<LaunchHook>,
+    // run after the app exits
+    #[serde(default)]
+    pub post_exit: Option<LaunchHook>
+}
+
+impl LaunchHooks {
+    // hooks of the app, falling back to the toolbox-wide defaults
+    pub fn or(&self, defaults: &LaunchHooks) -> LaunchHooks {
+        LaunchHooks {
+            pre_launch: self.pre_launch.clone().or_else(|| defaults.pre_launch.clone()),
+            post_exit: self.post_exit.clone().or_else(|| defaults.post_exit.clone())
+        }
+    }
+}
+
+pub const DEFAULT_HOOK_TIMEOUT_MS: u64 = 30000;
+
+fn default_hook_timeout() -> u64 {
+    DEFAULT_HOOK_TIMEOUT_MS
+}
+
+// a shell command run in the working directory of the app
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct LaunchHook {
+    pub command: String,
+    #[serde(default = "default_hook_timeout")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub abort_on_failure: bool
+}
+
+impl LaunchHook {
+    pub fn new(command: &str) -> Self {
+        Self { command: command.to_string(), timeout_ms: DEFAULT_HOOK_TIMEOUT_MS, abort_on_failure: false }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub struct LaunchStep {
+    pub app: String,
+    // waited before launching this step, after the previous app exits if wait_for_previous is set
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(default)]
+    pub wait_for_previous: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct SmartCategoryMetadata {
+    pub name: String,
+    pub query: SmartCategoryQuery
+}
+
+pub const DEFAULT_ICON_MAX_SIZE: u32 = 256;
+// icons are rendered as max_size² pixels, so the size is kept within a range that is cheap to allocate
+pub const MIN_ICON_MAX_SIZE: u32 = 16;
+pub const MAX_ICON_MAX_SIZE: u32 = 1024;
+
+fn default_icon_max_size() -> u32 {
+    DEFAULT_ICON_MAX_SIZE
+}
+
+fn clamped_icon_max_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    Ok(u32::deserialize(deserializer)?.clamp(MIN_ICON_MAX_SIZE, MAX_ICON_MAX_SIZE))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub enum IconFormat {
+    #[default]
+    Png,
+    // lossless, usually smaller than png
+    WebP
+}
+
+// what is done with svg files loaded as icons
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub enum SvgMode {
+    // rendered to a bitmap of the max size like any other icon
+    #[default]
+    Rasterize,
+    // kept as svg without scripts and external references
+    Sanitize
+}
+
+// where the color of the icons generated for the apps without one comes from
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub enum FallbackIconColor {
+    // a hue derived from the app name
+    #[default]
+    Name,
+    // one of the theme colors, picked by the app name
+    Theme
+}
+
+// how icons loaded from files and apps are stored in the config
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct IconSettings {
+    // larger icons are scaled down to fit in a square of this size
+    #[serde(default = "default_icon_max_size", deserialize_with = "clamped_icon_max_size")]
+    pub max_size: u32,
+    #[serde(default)]
+    pub format: IconFormat,
+    #[serde(default)]
+    pub svg_mode: SvgMode,
+    #[serde(default)]
+    pub fallback_color: FallbackIconColor
+}
+
+impl Default for IconSettings {
+    fn default() -> Self {
+        Self { max_size: DEFAULT_ICON_MAX_SIZE, format: IconFormat::default(), svg_mode: SvgMode::default(), fallback_color: FallbackIconColor::default() }
+    }
+}
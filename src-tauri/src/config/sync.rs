@@ -0,0 +1,60 @@
+use std::path::Path;
+use super::Config;
+use super::error::{ConfigError, ConfigErrorType};
+use super::super::integrity::FileDigest;
+
+// tracks the content of the config file as of the last load or save,
+// so that modifications made outside of the launcher can be detected
+#[derive(Clone, Debug, Default)]
+pub struct ConfigSync {
+    fingerprint: Option<String>,
+    conflict: bool
+}
+
+impl ConfigSync {
+    pub fn new() -> Self {
+        Self { fingerprint: None, conflict: false }
+    }
+
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<Config, ConfigError> {
+        let config = Config::from_file(&path)?;
+        self.mark_synced(&path);
+        Ok(config)
+    }
+
+    // refuse to overwrite a config file that has been modified since the last load or save
+    pub fn save<P: AsRef<Path>>(&mut self, config: &Config, path: P) -> Result<(), ConfigError> {
+        if self.conflict || self.is_modified_externally(&path) {
+            self.conflict = true;
+            return Err(ConfigError {
+                err_type: ConfigErrorType::ModifiedExternally,
+                config_path: Some(path.as_ref().to_string_lossy().to_string())
+            });
+        }
+        self.force_save(config, path)
+    }
+
+    // overwrite the config file regardless of external modifications, resolving any conflict
+    pub fn force_save<P: AsRef<Path>>(&mut self, config: &Config, path: P) -> Result<(), ConfigError> {
+        config.to_file(&path)?;
+        self.mark_synced(&path);
+        Ok(())
+    }
+
+    pub fn mark_synced<P: AsRef<Path>>(&mut self, path: P) {
+        self.fingerprint = Self::fingerprint_of(path);
+        self.conflict = false;
+    }
+
+    pub fn is_modified_externally<P: AsRef<Path>>(&self, path: P) -> bool {
+        Self::fingerprint_of(path) != self.fingerprint
+    }
+
+    pub fn has_conflict(&self) -> bool {
+        self.conflict
+    }
+
+    fn fingerprint_of<P: AsRef<Path>>(path: P) -> Option<String> {
+        FileDigest::from_file(path).ok().map(|digest| digest.sha256)
+    }
+}
@@ -0,0 +1,163 @@
+use image::{Rgba, RgbaImage};
+use pinyin::ToPinyin;
+use super::config::structure::{Theme, ThemeColor};
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+// shown when the name has no letter or digit to take
+const PLACEHOLDER_INITIAL: char = '#';
+const MAX_INITIALS: usize = 2;
+
+// 5x7 bitmap font, one row per byte with the leftmost pixel in the highest of the 5 bits
+const GLYPHS: [(char, [u8; 7]); 37] = [
+    ('A', [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11]),
+    ('B', [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E]),
+    ('C', [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E]),
+    ('D', [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E]),
+    ('E', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F]),
+    ('F', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10]),
+    ('G', [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F]),
+    ('H', [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11]),
+    ('I', [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('J', [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C]),
+    ('K', [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11]),
+    ('L', [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F]),
+    ('M', [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11]),
+    ('N', [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11]),
+    ('O', [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E]),
+    ('P', [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10]),
+    ('Q', [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D]),
+    ('R', [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11]),
+    ('S', [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E]),
+    ('T', [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04]),
+    ('U', [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E]),
+    ('V', [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04]),
+    ('W', [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A]),
+    ('X', [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11]),
+    ('Y', [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04]),
+    ('Z', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F]),
+    ('0', [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E]),
+    ('1', [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('2', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F]),
+    ('3', [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E]),
+    ('4', [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02]),
+    ('5', [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E]),
+    ('6', [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E]),
+    ('7', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08]),
+    ('8', [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E]),
+    ('9', [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C]),
+    ('#', [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A])
+];
+
+fn glyph_of(c: char) -> &'static [u8; 7] {
+    GLYPHS.iter().find(|(glyph_char, _)| *glyph_char == c)
+        .or_else(|| GLYPHS.iter().find(|(glyph_char, _)| *glyph_char == PLACEHOLDER_INITIAL))
+        .map(|(_, rows)| rows)
+        .unwrap()
+}
+
+// the letter a char can be written with in the bitmap font, chinese characters by the initial of their pinyin
+fn initial_of(c: char) -> Option<char> {
+    if c.is_ascii_alphanumeric() {
+        return Some(c.to_ascii_uppercase());
+    }
+    let pinyin = c.to_pinyin()?;
+    pinyin.plain().chars().next().map(|initial| initial.to_ascii_uppercase())
+}
+
+// the initials of the first words of the name, where every chinese character and every capitalized part
+// of a camel case word counts as a word, e.g. "VS" for "Visual Studio Code" and "PS" for "PowerShell"
+pub fn app_initials(app_name: &str) -> String {
+    let mut initials = String::new();
+    let mut prev: Option<char> = None;
+    for c in app_name.chars() {
+        let starts_word = c.to_pinyin().is_some() || match prev {
+            None => true,
+            Some(prev) => !prev.is_alphanumeric() || prev.to_pinyin().is_some() || (prev.is_lowercase() && c.is_uppercase())
+        };
+        if starts_word && let Some(initial) = initial_of(c) {
+            initials.push(initial);
+            if initials.len() == MAX_INITIALS {
+                break;
+            }
+        }
+        prev = Some(c);
+    }
+    if initials.is_empty() {
+        initials.push(PLACEHOLDER_INITIAL);
+    }
+    initials
+}
+
+// FNV-1a, which unlike the hasher of the standard library stays the same across versions
+fn name_hash(app_name: &str) -> u32 {
+    app_name.bytes().fold(0x811C9DC5u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193))
+}
+
+fn theme_colors(theme: &Theme) -> Vec<&ThemeColor> {
+    match theme {
+        Theme::Solid { color } => vec![color],
+        Theme::LinearGradient { stops, .. } | Theme::RadialGradient { stops } => stops.iter().map(|stop| &stop.color).collect(),
+        Theme::Image { .. } => Vec::new()
+    }
+}
+
+// a color picked by the name among the colors of the theme, or a hue derived from the name
+// when there is no theme or it is an image
+pub fn badge_color(app_name: &str, theme: Option<&Theme>) -> ThemeColor {
+    let hash = name_hash(app_name);
+    let colors = theme.map(theme_colors).unwrap_or_default();
+    if colors.is_empty() {
+        return ThemeColor::HSL { h: (hash % 360) as u16, s: 55, l: 45 };
+    }
+    let (r, g, b) = colors[hash as usize % colors.len()].blend_over((255, 255, 255));
+    ThemeColor::RGB { r, g, b }
+}
+
+// whether a pixel at the offsets from the nearest corner center is inside a corner of the radius,
+// as the coverage of the pixel for smooth edges
+fn corner_coverage(dx: f32, dy: f32, radius: f32) -> f32 {
+    if dx <= 0.0 || dy <= 0.0 {
+        return 1.0;
+    }
+    (radius + 0.5 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0)
+}
+
+// the initials in white or black, whichever reads better, on a rounded square of the color
+pub fn render_badge(initials: &str, color: &ThemeColor, size: u32) -> RgbaImage {
+    // a pixel of the font between the glyphs, and the badge at least a pixel wider than the text on each side
+    let glyphs: Vec<&[u8; 7]> = initials.chars().map(glyph_of).collect();
+    let text_width = (glyphs.len() as u32 * (GLYPH_WIDTH + 1)).saturating_sub(1).max(1);
+    let size = size.max(GLYPH_HEIGHT * 2).max(text_width + 2);
+    let (r, g, b) = color.to_rgb();
+    let white = ThemeColor::RGB { r: 255, g: 255, b: 255 };
+    let black = ThemeColor::RGB { r: 0, g: 0, b: 0 };
+    let text_rgb = if color.contrast_ratio(&white) >= color.contrast_ratio(&black) { [255, 255, 255] } else { [0, 0, 0] };
+
+    let radius = size as f32 / 5.0;
+    let mut image = RgbaImage::from_fn(size, size, |x, y| {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        let dx = (radius - x).max(x - (size as f32 - radius));
+        let dy = (radius - y).max(y - (size as f32 - radius));
+        Rgba([r, g, b, (corner_coverage(dx, dy, radius) * 255.0).round() as u8])
+    });
+
+    // the text takes at most half of the badge
+    let scale = (size / 2 / text_width).min(size / 2 / GLYPH_HEIGHT).max(1);
+    let left = size.saturating_sub(text_width * scale) / 2;
+    let top = size.saturating_sub(GLYPH_HEIGHT * scale) / 2;
+    for (index, rows) in glyphs.iter().enumerate() {
+        let glyph_left = left + index as u32 * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in rows.iter().enumerate() {
+            for col in (0..GLYPH_WIDTH).filter(|col| bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0) {
+                for y in 0..scale {
+                    for x in 0..scale {
+                        let (px, py) = (glyph_left + col * scale + x, top + row as u32 * scale + y);
+                        image.put_pixel(px, py, Rgba([text_rgb[0], text_rgb[1], text_rgb[2], 255]));
+                    }
+                }
+            }
+        }
+    }
+    image
+}
@@ -0,0 +1,139 @@
+use std::io::{Error as IoError, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread::{sleep, spawn};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use super::config::structure::LaunchHook;
+
+// the output is kept for the launch log, longer output is cut
+const MAX_HOOK_OUTPUT_LEN: usize = 8192;
+const POLL_INTERVAL_MS: u64 = 20;
+// processes started by the hook may still hold the pipes after it exits, don't wait for them forever
+const OUTPUT_GRACE_MS: u64 = 500;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HookResult {
+    // None if the hook timed out or was terminated by a signal
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    // stdout followed by stderr
+    pub output: String
+}
+
+impl HookResult {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+
+    pub fn summary(&self) -> String {
+        match (self.timed_out, self.exit_code) {
+            (true, _) => format!("timed out after {} ms", self.duration_ms),
+            (false, Some(code)) => format!("exited with code {} in {} ms", code, self.duration_ms),
+            (false, None) => format!("was terminated after {} ms", self.duration_ms)
+        }
+    }
+}
+
+fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").raw_arg(command).creation_flags(CREATE_NO_WINDOW);
+        shell
+    }
+    #[cfg(not(windows))]
+    {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    }
+}
+
+// read the pipe to the end in the background, so that a chatty hook never blocks on a full pipe
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = channel();
+    if let Some(mut pipe) = pipe {
+        spawn(move || {
+            let mut buf: Vec<u8> = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            let _ = sender.send(buf);
+        });
+    }
+    receiver
+}
+
+// kill the process along with the processes it started, which would otherwise keep running and hold the pipes
+fn kill_tree(child: &mut Child) {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let _ = Command::new("taskkill").args(["/T", "/F", "/PID", &child.id().to_string()])
+            .creation_flags(CREATE_NO_WINDOW).stdout(Stdio::null()).stderr(Stdio::null()).status();
+    }
+    #[cfg(not(windows))]
+    {
+        // the child leads its own process group, see run_with_timeout
+        let _ = Command::new("kill").args(["-s", "KILL", "--", &format!("-{}", child.id())])
+            .stdout(Stdio::null()).stderr(Stdio::null()).status();
+    }
+    let _ = child.kill();
+}
+
+// run the command with its output captured, killing it when it exceeds the timeout
+pub fn run_with_timeout(command: &mut Command, timeout_ms: u64) -> Result<HookResult, IoError> {
+    #[cfg(not(windows))]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let start = Instant::now();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    let timeout = Duration::from_millis(timeout_ms);
+    let (exit_code, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status.code(), false);
+        }
+        if start.elapsed() >= timeout {
+            kill_tree(&mut child);
+            let _ = child.wait();
+            break (None, true);
+        }
+        sleep(Duration::from_millis(POLL_INTERVAL_MS));
+    };
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let mut output_bytes: Vec<u8> = Vec::new();
+    for receiver in [stdout, stderr] {
+        if let Ok(buf) = receiver.recv_timeout(Duration::from_millis(OUTPUT_GRACE_MS)) {
+            output_bytes.extend(buf);
+        }
+    }
+    let mut output = String::from_utf8_lossy(&output_bytes).trim_end().to_string();
+    if output.len() > MAX_HOOK_OUTPUT_LEN {
+        let mut end = MAX_HOOK_OUTPUT_LEN;
+        while !output.is_char_boundary(end) {
+            end -= 1;
+        }
+        output.truncate(end);
+        output.push_str("\n...");
+    }
+    Ok(HookResult { exit_code, timed_out, duration_ms, output })
+}
+
+// run the hook command through the shell, killing it when it exceeds its timeout
+pub fn run_hook<P: AsRef<Path>>(hook: &LaunchHook, working_dir: P) -> Result<HookResult, IoError> {
+    run_with_timeout(shell_command(&hook.command).current_dir(working_dir), hook.timeout_ms)
+}
@@ -0,0 +1,211 @@
+use std::cmp::Reverse;
+use image::{DynamicImage, ImageError, ImageFormat, RgbImage, RgbaImage, load_from_memory_with_format};
+use image::error::{DecodingError, ImageFormatHint};
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const ICO_HEADER_SIZE: usize = 6;
+const ICO_ENTRY_SIZE: usize = 16;
+
+enum IconData {
+    Png(Vec<u8>),
+    // a bitmap of an ico or cur file, decoded as an ico file of its own
+    Ico(Vec<u8>),
+    // the rle compressed channels of the legacy icns images, with their separate 8-bit mask
+    IcnsRgb { data: Vec<u8>, mask: Option<Vec<u8>> },
+    IcnsArgb(Vec<u8>)
+}
+
+// one of the images in an icon file, only the selected one is decoded
+pub struct IconEntry {
+    pub width: u32,
+    pub height: u32,
+    pub bit_depth: u16,
+    data: IconData
+}
+
+impl IconEntry {
+    pub fn size(&self) -> u32 {
+        self.width.max(self.height)
+    }
+
+    pub fn decode(&self) -> Result<DynamicImage, ImageError> {
+        let invalid = || ImageError::Decoding(DecodingError::from_format_hint(ImageFormatHint::Name(String::from("icns"))));
+        match &self.data {
+            IconData::Png(data) => load_from_memory_with_format(data, ImageFormat::Png),
+            IconData::Ico(data) => load_from_memory_with_format(data, ImageFormat::Ico),
+            IconData::IcnsRgb { data, mask } => {
+                let pixel_count = (self.width * self.height) as usize;
+                let channels = unpack_icns_channels(data, pixel_count, 3).ok_or_else(invalid)?;
+                let pixels = |i: usize| [channels[i], channels[pixel_count + i], channels[pixel_count * 2 + i]];
+                match mask {
+                    Some(mask) if mask.len() == pixel_count => {
+                        let buf = (0..pixel_count).flat_map(|i| { let [r, g, b] = pixels(i); [r, g, b, mask[i]] }).collect();
+                        RgbaImage::from_vec(self.width, self.height, buf).map(DynamicImage::ImageRgba8).ok_or_else(invalid)
+                    },
+                    _ => {
+                        let buf = (0..pixel_count).flat_map(pixels).collect();
+                        RgbImage::from_vec(self.width, self.height, buf).map(DynamicImage::ImageRgb8).ok_or_else(invalid)
+                    }
+                }
+            },
+            IconData::IcnsArgb(data) => {
+                let pixel_count = (self.width * self.height) as usize;
+                let channels = unpack_icns_channels(data, pixel_count, 4).ok_or_else(invalid)?;
+                let buf = (0..pixel_count)
+                    .flat_map(|i| [channels[pixel_count + i], channels[pixel_count * 2 + i], channels[pixel_count * 3 + i], channels[i]])
+                    .collect();
+                RgbaImage::from_vec(self.width, self.height, buf).map(DynamicImage::ImageRgba8).ok_or_else(invalid)
+            }
+        }
+    }
+}
+
+fn read_u16_le(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+// width and height from the header of a png
+fn png_size(data: &[u8]) -> Option<(u32, u32)> {
+    if !data.starts_with(&PNG_SIGNATURE) {
+        return None;
+    }
+    Some((read_u32_be(data, 16)?, read_u32_be(data, 20)?))
+}
+
+pub fn is_ico(data: &[u8]) -> bool {
+    // the type is 1 for icons and 2 for cursors
+    data.len() >= ICO_HEADER_SIZE && data[0..2] == [0, 0] && matches!(data[2..4], [1, 0] | [2, 0])
+}
+
+pub fn is_icns(data: &[u8]) -> bool {
+    data.starts_with(b"icns")
+}
+
+// the images of an ico or cur file, the entries out of the file are left out
+pub fn parse_ico(data: &[u8]) -> Vec<IconEntry> {
+    if !is_ico(data) {
+        return Vec::new();
+    }
+    let count = read_u16_le(data, 4).unwrap_or(0) as usize;
+    (0..count).filter_map(|index| {
+        let entry = data.get(ICO_HEADER_SIZE + index * ICO_ENTRY_SIZE..ICO_HEADER_SIZE + (index + 1) * ICO_ENTRY_SIZE)?;
+        let size = read_u32_le(entry, 8)? as usize;
+        let offset = read_u32_le(entry, 12)? as usize;
+        let image_data = data.get(offset..offset.checked_add(size)?)?;
+        if let Some((width, height)) = png_size(image_data) {
+            return Some(IconEntry { width, height, bit_depth: 32, data: IconData::Png(image_data.to_vec()) });
+        }
+        // 0 stands for 256 in the directory, the bitmap header has the real size
+        let width = read_u32_le(image_data, 4).unwrap_or(if entry[0] == 0 { 256 } else { entry[0] as u32 });
+        let height = read_u32_le(image_data, 8).map(|h| h / 2).unwrap_or(if entry[1] == 0 { 256 } else { entry[1] as u32 });
+        let bit_depth = read_u16_le(image_data, 14).unwrap_or(0);
+        // a cursor has its hotspot in place of the planes and bit count, so they are filled in from the bitmap
+        let mut single_ico = vec![0, 0, 1, 0, 1, 0];
+        single_ico.extend_from_slice(&entry[0..4]);
+        single_ico.extend_from_slice(&[1, 0]);
+        single_ico.extend_from_slice(&bit_depth.to_le_bytes());
+        single_ico.extend_from_slice(&(size as u32).to_le_bytes());
+        single_ico.extend_from_slice(&((ICO_HEADER_SIZE + ICO_ENTRY_SIZE) as u32).to_le_bytes());
+        single_ico.extend_from_slice(image_data);
+        Some(IconEntry { width, height, bit_depth, data: IconData::Ico(single_ico) })
+    }).collect()
+}
+
+// size of the legacy rgb images and the type of their masks
+fn icns_rgb_type(os_type: &[u8]) -> Option<(u32, &'static [u8; 4])> {
+    match os_type {
+        b"is32" => Some((16, b"s8mk")),
+        b"il32" => Some((32, b"l8mk")),
+        b"ih32" => Some((48, b"h8mk")),
+        b"it32" => Some((128, b"t8mk")),
+        _ => None
+    }
+}
+
+fn icns_argb_size(os_type: &[u8]) -> Option<u32> {
+    match os_type {
+        b"ic04" => Some(16),
+        b"ic05" => Some(32),
+        _ => None
+    }
+}
+
+// the images of an icns file, jpeg 2000 images are left out as they cannot be decoded
+pub fn parse_icns(data: &[u8]) -> Vec<IconEntry> {
+    if !is_icns(data) {
+        return Vec::new();
+    }
+    let file_len = (read_u32_be(data, 4).unwrap_or(0) as usize).min(data.len());
+    let mut elements: Vec<(&[u8], &[u8])> = Vec::new();
+    let mut offset = 8;
+    while let Some(len) = read_u32_be(data, offset + 4) {
+        let len = len as usize;
+        if len < 8 || offset + len > file_len {
+            break;
+        }
+        elements.push((&data[offset..offset + 4], &data[offset + 8..offset + len]));
+        offset += len;
+    }
+    let mask_of = |mask_type: &[u8]| elements.iter().find(|(os_type, _)| *os_type == mask_type).map(|(_, mask)| mask.to_vec());
+    elements.iter().filter_map(|(os_type, element)| {
+        if let Some((width, height)) = png_size(element) {
+            return Some(IconEntry { width, height, bit_depth: 32, data: IconData::Png(element.to_vec()) });
+        }
+        if let Some((size, mask_type)) = icns_rgb_type(os_type) {
+            let mask = mask_of(mask_type);
+            // the largest one has 4 bytes of padding before its channels
+            let element = if *os_type == b"it32" { element.get(4..)? } else { element };
+            let bit_depth = if mask.is_some() { 32 } else { 24 };
+            return Some(IconEntry { width: size, height: size, bit_depth, data: IconData::IcnsRgb { data: element.to_vec(), mask } });
+        }
+        let size = icns_argb_size(os_type)?;
+        let element = element.strip_prefix(b"ARGB")?;
+        Some(IconEntry { width: size, height: size, bit_depth: 32, data: IconData::IcnsArgb(element.to_vec()) })
+    }).collect()
+}
+
+// the channels one after another, each of pixel_count bytes, either as they are or packed with the icns run length encoding
+fn unpack_icns_channels(data: &[u8], pixel_count: usize, channel_count: usize) -> Option<Vec<u8>> {
+    let total = pixel_count * channel_count;
+    if data.len() == total {
+        return Some(data.to_vec());
+    }
+    let mut channels: Vec<u8> = Vec::with_capacity(total);
+    let mut pos = 0;
+    while channels.len() < total {
+        let header = *data.get(pos)? as usize;
+        if header < 0x80 {
+            // header + 1 bytes as they are
+            channels.extend_from_slice(data.get(pos + 1..pos + 2 + header)?);
+            pos += 2 + header;
+        } else {
+            // the next byte repeated header - 125 times
+            let value = *data.get(pos + 1)?;
+            channels.extend(std::iter::repeat_n(value, header - 125));
+            pos += 2;
+        }
+    }
+    channels.truncate(total);
+    Some(channels)
+}
+
+pub fn parse_icon_file(data: &[u8]) -> Vec<IconEntry> {
+    if is_icns(data) { parse_icns(data) } else { parse_ico(data) }
+}
+
+// the smallest image not smaller than the display size, so that it is only scaled down, or the largest one,
+// the one with more colors if there are several of the same size
+pub fn select_icon(entries: &[IconEntry], display_size: u32) -> Option<&IconEntry> {
+    entries.iter()
+        .filter(|entry| entry.size() >= display_size)
+        .min_by_key(|entry| (entry.size(), Reverse(entry.bit_depth)))
+        .or_else(|| entries.iter().max_by_key(|entry| (entry.size(), entry.bit_depth)))
+}
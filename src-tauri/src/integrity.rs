@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::{File, canonicalize, read_dir, read_to_string as fs_read_to_string, write as fs_write};
+use std::io::{self, Error as IoError, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::Error as SerdeError;
+use sha2::{Digest, Sha256};
+use super::config::{Config, structure::AppMetadata};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct FileDigest {
+    pub size: u64,
+    pub sha256: String
+}
+
+impl FileDigest {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, IoError> {
+        let mut file = File::open(path)?;
+        let mut hasher = Sha256::new();
+        let size = io::copy(&mut file, &mut hasher)?;
+        Ok(Self { size, sha256: format!("{:x}", hasher.finalize()) })
+    }
+
+    // hash every file under the directory in a stable order, so the digest changes
+    // when any file is added, removed, renamed or modified
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<Self, IoError> {
+        let root = path.as_ref();
+        let mut files: Vec<PathBuf> = Vec::new();
+        collect_files(root, &mut files)?;
+        files.sort();
+
+        let mut hasher = Sha256::new();
+        let mut size = 0;
+        for file_path in files {
+            let relative_path = file_path.strip_prefix(root).unwrap_or(&file_path);
+            hasher.update(relative_path.to_string_lossy().replace('\\', "/").as_bytes());
+            hasher.update([0u8]);
+            // the length goes before the content, so it is taken from the metadata to stream the content
+            let file = File::open(&file_path)?;
+            let len = file.metadata()?.len();
+            hasher.update(len.to_le_bytes());
+            if io::copy(&mut file.take(len), &mut hasher)? != len {
+                return Err(IoError::new(ErrorKind::UnexpectedEof, "File changed while hashing"));
+            }
+            size += len;
+        }
+        Ok(Self { size, sha256: format!("{:x}", hasher.finalize()) })
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), IoError> {
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn app_dir(app_path: &Path) -> &Path {
+    app_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."))
+}
+
+// an app directly in the toolbox folder (or above it) has no folder of its own, hashing it would cover
+// the files the launcher writes there, e.g. the config, this manifest and the logs
+fn has_own_dir(app_path: &Path) -> bool {
+    match (canonicalize(app_dir(app_path)), canonicalize(".")) {
+        (Ok(dir), Ok(toolbox_dir)) => !toolbox_dir.starts_with(dir),
+        _ => false
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct IntegrityEntry {
+    pub app_path: PathBuf,
+    pub file: FileDigest,
+    // digest of the whole directory containing the app, if requested when generating
+    pub dir: Option<FileDigest>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(tag = "type")]
+pub enum IntegrityStatus {
+    Valid,
+    Unlisted,
+    Missing,
+    PathChanged,
+    Modified,
+    DirectoryModified
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
+pub struct IntegrityManifest {
+    pub entries: BTreeMap<String, IntegrityEntry>
+}
+
+impl IntegrityManifest {
+    pub fn new() -> Self {
+        Self { entries: BTreeMap::new() }
+    }
+
+    pub fn from_config(config: &Config, include_dirs: bool) -> Result<Self, IntegrityError> {
+        let mut manifest = Self::new();
+        for app_name in config.get_all_app_name_list() {
+            let metadata = config.get_app(app_name).expect("App should exist");
+            // folders and urls have no file to hash
+            if !metadata.kind.is_file() {
+                continue;
+            }
+            let app_error = |e| IntegrityError { err_type: IntegrityErrorType::AppReadError(app_name.clone(), e), manifest_path: None };
+            let file = FileDigest::from_file(&metadata.app_path).map_err(app_error)?;
+            let dir = if include_dirs && has_own_dir(&metadata.app_path) {
+                Some(FileDigest::from_dir(app_dir(&metadata.app_path)).map_err(app_error)?)
+            } else {
+                None
+            };
+            manifest.entries.insert(app_name.clone(), IntegrityEntry { app_path: metadata.app_path.clone(), file, dir });
+        }
+        Ok(manifest)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, IntegrityError> {
+        let path = path.as_ref();
+        let path_str = path.to_string_lossy().to_string();
+
+        if !path.is_file() {
+            return Err(IntegrityError { err_type: IntegrityErrorType::FileNotExist, manifest_path: Some(path_str) });
+        }
+        match fs_read_to_string(path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(manifest) => Ok(manifest),
+                Err(e) => Err(IntegrityError { err_type: IntegrityErrorType::ParseError(e), manifest_path: Some(path_str) })
+            },
+            Err(e) => Err(IntegrityError { err_type: IntegrityErrorType::ReadError(e), manifest_path: Some(path_str) })
+        }
+    }
+
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), IntegrityError> {
+        let path = path.as_ref();
+        let path_str = path.to_string_lossy().to_string();
+        let content = serde_json::to_string_pretty(self).expect("Failed to serialize integrity manifest");
+
+        if let Err(e) = fs_write(path, content) {
+            Err(IntegrityError { err_type: IntegrityErrorType::WriteError(e), manifest_path: Some(path_str) })
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn verify_app(&self, app_name: &str, metadata: &AppMetadata) -> IntegrityStatus {
+        let Some(entry) = self.entries.get(app_name) else {
+            return IntegrityStatus::Unlisted;
+        };
+        if entry.app_path != metadata.app_path {
+            return IntegrityStatus::PathChanged;
+        }
+        if !metadata.app_path.is_file() {
+            return IntegrityStatus::Missing;
+        }
+        match FileDigest::from_file(&metadata.app_path) {
+            Ok(digest) if digest == entry.file => (),
+            _ => return IntegrityStatus::Modified
+        }
+        // manifests generated before root-level apps were left out may still have their folder
+        if let Some(dir_digest) = &entry.dir && has_own_dir(&metadata.app_path) {
+            match FileDigest::from_dir(app_dir(&metadata.app_path)) {
+                Ok(digest) if digest == *dir_digest => (),
+                _ => return IntegrityStatus::DirectoryModified
+            }
+        }
+        IntegrityStatus::Valid
+    }
+
+    pub fn verify(&self, config: &Config) -> BTreeMap<String, IntegrityStatus> {
+        config.get_all_app_name_list().into_iter().filter_map(|app_name| {
+            let metadata = config.get_app(app_name).expect("App should exist");
+            metadata.kind.is_file().then(|| (app_name.clone(), self.verify_app(app_name, metadata)))
+        }).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct IntegrityError {
+    pub err_type: IntegrityErrorType,
+    pub manifest_path: Option<String>
+}
+
+impl Display for IntegrityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let manifest_path_str = self.manifest_path.clone().unwrap_or(String::from("<unknown>"));
+        match &self.err_type {
+            IntegrityErrorType::ParseError(e) => write!(f, "Failed to parse integrity manifest '{}': {}", manifest_path_str, e),
+            IntegrityErrorType::ReadError(e) => write!(f, "Failed to read integrity manifest '{}': {}", manifest_path_str, e),
+            IntegrityErrorType::WriteError(e) => write!(f, "Failed to write integrity manifest '{}': {}", manifest_path_str, e),
+            IntegrityErrorType::FileNotExist => write!(f, "Integrity manifest '{}' does not exist", manifest_path_str),
+            IntegrityErrorType::AppReadError(app_name, e) => write!(f, "Failed to hash files of app '{}': {}", app_name, e)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum IntegrityErrorType {
+    ParseError(SerdeError),
+    ReadError(IoError),
+    WriteError(IoError),
+    FileNotExist,
+    AppReadError(String, IoError)
+}
@@ -0,0 +1,331 @@
+use std::fs::File;
+use std::io::Error as IoError;
+use std::path::{Path, absolute};
+use std::process::{Child, Command};
+use std::sync::Mutex;
+use std::thread::{JoinHandle, sleep, spawn};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri_plugin_opener::{open_path, open_url};
+use super::config::{Config, structure::{AppKind, AppMetadata, IntegrityPolicy, LaunchHook, LaunchPolicy}};
+use super::hooks::run_hook;
+use super::integrity::{IntegrityManifest, IntegrityStatus};
+use super::logs::{APP_LOG_DIR, LAUNCH_LOG_PATH, append_log, create_app_log};
+use super::process::ProcessTracker;
+
+pub const INTEGRITY_MANIFEST_PATH: &str = "integrity.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(tag = "type")]
+pub enum LaunchStatus {
+    Launched,
+    AppNotExist,
+    FileNotExist,
+    IntegrityCheckFailed { status: IntegrityStatus },
+    SpawnFailed { reason: String },
+    PreLaunchHookFailed { reason: String },
+    // with ask set, the frontend asks the user and launches again with `allow_another` set;
+    // otherwise the running instance is brought to the front if it has a window
+    AlreadyRunning { ask: bool, focused: bool }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchStepResult {
+    pub app_name: String,
+    pub status: LaunchStatus
+}
+
+// an app started by the launcher, watched by a thread until it exits;
+// entries opened by the system have no process to watch
+pub struct RunningApp {
+    watcher: Option<JoinHandle<()>>
+}
+
+impl RunningApp {
+    // wait for the app to exit, and for its post-exit hook to finish
+    pub fn wait(self) {
+        if let Some(watcher) = self.watcher {
+            let _ = watcher.join();
+        }
+    }
+}
+
+// check that the app exists and passes the integrity policy of the config,
+// the returned metadata carries the hooks to run with the toolbox-wide defaults applied
+pub fn check_app(config: &Config, app_name: &str, confirmed: bool) -> Result<AppMetadata, LaunchStatus> {
+    let Some(metadata) = config.get_app(app_name) else {
+        return Err(LaunchStatus::AppNotExist);
+    };
+    if !metadata.is_target_valid() {
+        return Err(LaunchStatus::FileNotExist);
+    }
+    // folders and urls have no file to verify
+    if config.integrity_policy != IntegrityPolicy::Off && metadata.kind.is_file() {
+        let status = IntegrityManifest::from_file(INTEGRITY_MANIFEST_PATH)
+            .map(|manifest| manifest.verify_app(app_name, metadata))
+            .unwrap_or(IntegrityStatus::Unlisted);
+        // in Confirm mode, the frontend asks the user and launches again with `confirmed` set
+        if status != IntegrityStatus::Valid &&
+            (config.integrity_policy == IntegrityPolicy::Enforce || !confirmed) {
+            return Err(LaunchStatus::IntegrityCheckFailed { status });
+        }
+    }
+    Ok(AppMetadata { hooks: metadata.hooks.or(&config.default_hooks), ..metadata.clone() })
+}
+
+// the console output goes to the file if given, otherwise it is discarded
+pub fn spawn_app(metadata: &AppMetadata, output: Option<File>) -> Result<Child, IoError> {
+    let absolute_app_path = absolute(&metadata.app_path)?;
+    let absolute_working_dir = absolute(&metadata.working_dir)?;
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x00000008;
+        const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+        let creation_flag: u32;
+        let is_powershell_script: bool;
+        if let Some(ext) = metadata.app_path.extension() &&
+            (ext.to_ascii_lowercase() == "bat" || ext.to_ascii_lowercase() == "cmd" || ext.to_ascii_lowercase() == "ps1") {
+            // for batch files and powershell scripts, we should create a new console for it
+            creation_flag = CREATE_NEW_CONSOLE;
+            is_powershell_script = ext.to_ascii_lowercase() == "ps1";
+        } else {
+            // for other executables, we want to prevent the console window from appearing if it is a GUI app
+            creation_flag = DETACHED_PROCESS;
+            is_powershell_script = false;
+        }
+        let mut command: Command;
+        if is_powershell_script {
+            command = Command::new("powershell");
+            command.arg("-File");
+        } else {
+            command = Command::new("cmd");
+            command.arg("/C");
+        }
+        command.arg(absolute_app_path)
+            .current_dir(absolute_working_dir)
+            .creation_flags(creation_flag);
+        if !metadata.launch_args.is_empty() {
+            command.raw_arg(&metadata.launch_args);
+        }
+        if let Some(file) = output {
+            command.stdout(file.try_clone()?).stderr(file);
+        }
+        command.spawn()
+    }
+}
+
+// run the hook and write its outcome to the launch log, returning the reason if it failed
+fn run_logged_hook(app_name: &str, hook_type: &str, hook: &LaunchHook, working_dir: &Path) -> Result<(), String> {
+    let (entry, outcome) = match run_hook(hook, working_dir) {
+        Ok(result) => {
+            let mut entry = format!("{} hook of '{}' `{}` {}", hook_type, app_name, hook.command, result.summary());
+            for line in result.output.lines() {
+                entry.push_str("\n    ");
+                entry.push_str(line);
+            }
+            (entry, if result.success() { Ok(()) } else { Err(result.summary()) })
+        },
+        Err(e) => (format!("{} hook of '{}' `{}` failed to start: {}", hook_type, app_name, hook.command, e), Err(e.to_string()))
+    };
+    let _ = append_log(LAUNCH_LOG_PATH, &entry);
+    outcome
+}
+
+fn run_pre_launch_hook(app_name: &str, metadata: &AppMetadata) -> Result<(), LaunchStatus> {
+    // only executables need a working directory
+    let working_dir = if metadata.working_dir.as_os_str().is_empty() { Path::new(".") } else { metadata.working_dir.as_path() };
+    if let Some(hook) = &metadata.hooks.pre_launch &&
+        let Err(reason) = run_logged_hook(app_name, "Pre-launch", hook, working_dir) &&
+        hook.abort_on_failure {
+        let _ = append_log(LAUNCH_LOG_PATH, &format!("Launch of '{}' aborted", app_name));
+        return Err(LaunchStatus::PreLaunchHookFailed { reason });
+    }
+    Ok(())
+}
+
+// run the pre-launch hook and spawn the app
+fn spawn_with_hooks(app_name: &str, metadata: &AppMetadata) -> Result<Child, LaunchStatus> {
+    run_pre_launch_hook(app_name, metadata)?;
+    // failing to create the log file should not stop the app from launching
+    let output = if metadata.capture_output {
+        create_app_log(APP_LOG_DIR, app_name)
+            .inspect_err(|e| { let _ = append_log(LAUNCH_LOG_PATH, &format!("Failed to create the output log of '{}': {}", app_name, e)); })
+            .ok()
+    } else {
+        None
+    };
+    let output_path = output.as_ref().map(|(_, path)| path.to_string_lossy().to_string());
+    let child = match spawn_app(metadata, output.map(|(file, _)| file)) {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = append_log(LAUNCH_LOG_PATH, &format!("Failed to launch '{}': {}", app_name, e));
+            return Err(LaunchStatus::SpawnFailed { reason: e.to_string() });
+        }
+    };
+    let _ = append_log(LAUNCH_LOG_PATH, &match output_path {
+        Some(path) => format!("Launched '{}', output is captured to '{}'", app_name, path),
+        None => format!("Launched '{}'", app_name)
+    });
+    Ok(child)
+}
+
+// bring a top-level window of the processes to the front; the apps are started through cmd,
+// so the windows of their child processes are searched too
+pub fn focus_app_window(pids: &[u32]) -> bool {
+    #[cfg(windows)]
+    {
+        use std::collections::HashSet;
+        use std::mem::size_of;
+        use windows::core::BOOL;
+        use windows::Win32::Foundation::{CloseHandle, HWND, LPARAM};
+        use windows::Win32::System::Diagnostics::ToolHelp::{CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS};
+        use windows::Win32::UI::WindowsAndMessaging::{EnumWindows, GetWindow, GetWindowThreadProcessId, IsIconic, IsWindowVisible, SetForegroundWindow, ShowWindow, GW_OWNER, SW_RESTORE};
+
+        struct WindowSearch {
+            pids: HashSet<u32>,
+            found: Option<HWND>
+        }
+
+        unsafe extern "system" fn search_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            let search = unsafe { &mut *(lparam.0 as *mut WindowSearch) };
+            let mut pid: u32 = 0;
+            unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)); }
+            // only visible windows without an owner, which are the ones shown in the taskbar
+            let is_main_window = unsafe { IsWindowVisible(hwnd).as_bool() && GetWindow(hwnd, GW_OWNER).is_err() };
+            if is_main_window && search.pids.contains(&pid) {
+                search.found = Some(hwnd);
+                // stop enumerating
+                return BOOL(0);
+            }
+            BOOL(1)
+        }
+
+        let mut search = WindowSearch { pids: pids.iter().cloned().collect(), found: None };
+        unsafe {
+            let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+                return false;
+            };
+            let mut processes: Vec<(u32, u32)> = Vec::new();
+            let mut entry = PROCESSENTRY32W { dwSize: size_of::<PROCESSENTRY32W>() as u32, ..Default::default() };
+            if Process32FirstW(snapshot, &mut entry).is_ok() {
+                loop {
+                    processes.push((entry.th32ProcessID, entry.th32ParentProcessID));
+                    if Process32NextW(snapshot, &mut entry).is_err() {
+                        break;
+                    }
+                }
+            }
+            let _ = CloseHandle(snapshot);
+            // add the descendants until there are no more
+            loop {
+                let count = search.pids.len();
+                for (pid, parent_pid) in &processes {
+                    if search.pids.contains(parent_pid) {
+                        search.pids.insert(*pid);
+                    }
+                }
+                if search.pids.len() == count {
+                    break;
+                }
+            }
+            let _ = EnumWindows(Some(search_window), LPARAM(&mut search as *mut WindowSearch as isize));
+        }
+        let Some(hwnd) = search.found else {
+            return false;
+        };
+        unsafe {
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            SetForegroundWindow(hwnd).as_bool()
+        }
+    }
+}
+
+// documents, folders and urls are handed to the system opener, which leaves no process to track
+fn open_entry(app_name: &str, metadata: &AppMetadata) -> Result<RunningApp, LaunchStatus> {
+    run_pre_launch_hook(app_name, metadata)?;
+    let opened = if metadata.kind == AppKind::Url {
+        open_url(metadata.app_path.to_string_lossy(), None::<&str>)
+    } else {
+        absolute(&metadata.app_path).map_err(Into::into).and_then(|path| open_path(path, None::<&str>))
+    };
+    if let Err(e) = opened {
+        let _ = append_log(LAUNCH_LOG_PATH, &format!("Failed to open '{}': {}", app_name, e));
+        return Err(LaunchStatus::SpawnFailed { reason: e.to_string() });
+    }
+    let _ = append_log(LAUNCH_LOG_PATH, &format!("Opened '{}'", app_name));
+    Ok(RunningApp { watcher: None })
+}
+
+// apply the launch policy of the app, then start it and watch it until it exits
+pub fn start_app(app_name: &str, metadata: &AppMetadata, tracker: &ProcessTracker, allow_another: bool) -> Result<RunningApp, LaunchStatus> {
+    if metadata.kind != AppKind::Executable {
+        return open_entry(app_name, metadata);
+    }
+    let ask = metadata.launch_policy == LaunchPolicy::Ask;
+    let allow_running = metadata.launch_policy == LaunchPolicy::AllowMultiple || (ask && allow_another);
+    if !tracker.begin_launch(app_name, allow_running) {
+        let focused = !ask && focus_app_window(&tracker.get_pids(app_name));
+        return Err(LaunchStatus::AlreadyRunning { ask, focused });
+    }
+    let spawned = spawn_with_hooks(app_name, metadata);
+    tracker.end_launch(app_name, spawned.as_ref().ok().map(Child::id));
+    let mut child = spawned?;
+
+    let pid = child.id();
+    let tracker = tracker.clone();
+    let app_name = app_name.to_string();
+    let working_dir = metadata.working_dir.clone();
+    let post_exit_hook = metadata.hooks.post_exit.clone();
+    Ok(RunningApp {
+        watcher: Some(spawn(move || {
+            let _ = child.wait();
+            tracker.exited(pid);
+            if let Some(hook) = post_exit_hook {
+                let _ = run_logged_hook(&app_name, "Post-exit", &hook, &working_dir);
+            }
+        }))
+    })
+}
+
+// the config is only locked while checking the app, as the pre-launch hook may take a while
+pub fn launch_app(config_state: &Mutex<Config>, tracker: &ProcessTracker, app_name: &str, confirmed: bool, allow_another: bool) -> LaunchStatus {
+    let checked = check_app(&config_state.lock().unwrap(), app_name, confirmed);
+    match checked.and_then(|metadata| start_app(app_name, &metadata, tracker, allow_another)) {
+        Ok(_) => LaunchStatus::Launched,
+        Err(status) => status
+    }
+}
+
+// launch the steps of the group one by one, a failed step does not stop the following ones;
+// the config is only locked while checking each step, as the group may take a while to start
+pub fn launch_group(config_state: &Mutex<Config>, tracker: &ProcessTracker, group_name: &str, confirmed: bool) -> Option<Vec<LaunchStepResult>> {
+    let steps = config_state.lock().unwrap().get_launch_group(group_name)?.steps.clone();
+    let mut results: Vec<LaunchStepResult> = Vec::new();
+    let mut previous: Option<RunningApp> = None;
+    for step in steps {
+        if step.wait_for_previous && let Some(app) = previous.take() {
+            app.wait();
+        }
+        if step.delay_ms > 0 {
+            sleep(Duration::from_millis(step.delay_ms));
+        }
+        let checked = check_app(&config_state.lock().unwrap(), &step.app, confirmed);
+        // the user is not asked in the middle of a group, an app that asks is reported as already running
+        let status = match checked.and_then(|metadata| start_app(&step.app, &metadata, tracker, false)) {
+            Ok(app) => {
+                previous = Some(app);
+                LaunchStatus::Launched
+            },
+            Err(status) => {
+                previous = None;
+                status
+            }
+        };
+        results.push(LaunchStepResult { app_name: step.app, status });
+    }
+    Some(results)
+}
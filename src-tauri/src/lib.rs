@@ -1,11 +1,29 @@
 pub mod config;
 pub mod util;
+pub mod integrity;
+pub mod signature;
+pub mod search;
+pub mod theme;
+pub mod hooks;
+pub mod logs;
+pub mod process;
+pub mod icons;
+pub mod svg;
+pub mod fallback_icon;
+pub mod squashfs;
+pub mod app_icon;
+pub mod version_info;
+pub mod versions;
+mod watcher;
+mod launcher;
 mod commands;
 
 use std::sync::Mutex;
 use tauri::{Builder, Manager, generate_handler, generate_context};
 use tauri_plugin_prevent_default::Flags;
-use config::Config;
+use config::{Config, active_config_path, history::EditHistory, sync::ConfigSync};
+use signature::ConfigVerifier;
+use process::ProcessTracker;
 use commands::*;
 
 pub fn run() {
@@ -16,6 +34,12 @@ pub fn run() {
     Builder::default()
         .setup(|app| {
             app.manage(Mutex::new(Config::new()));
+            app.manage(Mutex::new(ConfigVerifier::new(app.path().app_config_dir().ok())));
+            app.manage(Mutex::new(EditHistory::default()));
+            app.manage(Mutex::new(ConfigSync::new()));
+            app.manage(ProcessTracker::new());
+            // keep the watcher alive as long as the app is running
+            app.manage(Mutex::new(watcher::watch_config(app.handle().clone(), active_config_path()).ok()));
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
@@ -24,25 +48,85 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .invoke_handler(generate_handler![
             load_config,
+            resolve_config_conflict,
+            has_config_conflict,
+            get_config_signature_status,
+            sign_config,
+            generate_signing_key_file,
             get_config_basic_info,
             set_config_basic_info,
+            export_config_changelog,
+            merge_config_file,
+            convert_config_file,
             launch_app,
+            launch_group,
+            get_running_app_list,
+            get_app_log_list,
+            read_app_log,
+            clear_app_logs,
             open_app_file_location,
             get_category_list,
+            get_sub_category_list,
+            get_category_tree,
             get_all_app_list,
             get_app_list_by_category,
             get_available_app_list_by_category,
+            get_smart_category_list,
+            get_app_list_by_smart_category,
+            get_all_tags,
+            get_app_list_by_tag,
+            search_apps,
             add_category,
             update_categories,
+            add_sub_category,
+            update_sub_categories,
+            move_category,
+            remove_category,
             rename_category,
+            add_smart_category,
+            update_smart_category,
+            remove_smart_category,
             add_app_to_category,
             add_app_list_to_category,
             update_apps_in_category,
+            get_favorite_app_list,
+            pin_app,
+            unpin_app,
+            update_favorites,
+            get_launch_group_list,
+            add_launch_group,
+            update_launch_group,
+            rename_launch_group,
+            remove_launch_group,
             add_app,
             update_app,
             remove_app,
+            regenerate_fallback_icons,
+            scan_app_versions,
+            undo,
+            redo,
+            get_edit_history_state,
+            generate_integrity_manifest,
+            verify_integrity,
+            check_app_integrity,
+            get_integrity_policy,
+            set_integrity_policy,
+            get_default_hooks,
+            set_default_hooks,
+            get_icon_settings,
+            set_icon_settings,
             load_icon_from_file,
             load_icon_from_app,
+            load_metadata_from_app,
+            get_default_icon,
+            get_theme_presets,
+            save_theme_preset,
+            remove_theme_preset,
+            import_theme_preset,
+            export_theme_preset,
+            check_theme_contrast,
+            import_theme_image,
+            load_theme_image,
             get_relative_path,
             show_window
         ])
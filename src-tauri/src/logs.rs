@@ -0,0 +1,140 @@
+use std::fs::{File, OpenOptions, create_dir_all, read_dir, remove_dir_all, remove_file};
+use std::io::{Error as IoError, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+// launches and hook outputs of all apps, next to the config
+pub const LAUNCH_LOG_PATH: &str = "logs/launch.log";
+// captured console output, one directory per app and one file per launch
+pub const APP_LOG_DIR: &str = "logs/apps";
+// the oldest files of an app are removed beyond this number
+pub const MAX_APP_LOG_FILES: usize = 10;
+// how much of a log is read when the caller does not say
+pub const DEFAULT_LOG_TAIL_BYTES: u64 = 64 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLogInfo {
+    pub file_name: String,
+    pub size: u64,
+    pub modified: String
+}
+
+// UTC time in the form of "2024-01-31 08:00:00"
+pub fn format_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+    // civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, secs_of_day / 3600, secs_of_day % 3600 / 60, secs_of_day % 60)
+}
+
+// append a timestamped entry to the log, creating its directory if needed
+pub fn append_log<P: AsRef<Path>>(path: P, message: &str) -> Result<(), IoError> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() && !dir.as_os_str().is_empty() {
+        create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    // one write per entry, so that entries from different threads do not interleave
+    file.write_all(format!("[{}] {}\n", format_timestamp(SystemTime::now()), message).as_bytes())
+}
+
+// app names may contain characters that are not allowed in file names
+pub fn app_log_dir<P: AsRef<Path>>(log_dir: P, app_name: &str) -> PathBuf {
+    let dir_name: String = app_name.chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    // Windows ignores trailing dots and spaces, and a leading dot may escape the directory
+    let dir_name = dir_name.trim_end_matches(['.', ' ']).trim_start_matches('.');
+    log_dir.as_ref().join(if dir_name.is_empty() { "_" } else { dir_name })
+}
+
+// log files of the app, the newest first
+fn app_log_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries.filter_map(|entry| {
+        let path = entry.ok()?.path();
+        (path.is_file() && path.extension().is_some_and(|ext| ext == "log")).then_some(path)
+    }).collect();
+    // the file names start with the launch time, so they sort in time order
+    files.sort();
+    files.reverse();
+    files
+}
+
+// create the log file of a new launch of the app, rotating out the oldest files
+pub fn create_app_log<P: AsRef<Path>>(log_dir: P, app_name: &str) -> Result<(File, PathBuf), IoError> {
+    let dir = app_log_dir(log_dir, app_name);
+    create_dir_all(&dir)?;
+    let now = SystemTime::now();
+    let millis = now.duration_since(UNIX_EPOCH).map(|d| d.subsec_millis()).unwrap_or(0);
+    let stem = format!("{}-{:03}", format_timestamp(now).replace(['-', ':'], "").replace(' ', "-"), millis);
+    // the counter tells apart launches within the same millisecond, fixed width to keep the sort order
+    let mut index = 0;
+    let (file, path) = loop {
+        let path = dir.join(format!("{}-{:02}.log", stem, index));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => break (file, path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => index += 1,
+            Err(e) => return Err(e)
+        }
+    };
+    for old_file in app_log_files(&dir).into_iter().skip(MAX_APP_LOG_FILES) {
+        let _ = remove_file(old_file);
+    }
+    Ok((file, path))
+}
+
+pub fn list_app_logs<P: AsRef<Path>>(log_dir: P, app_name: &str) -> Vec<AppLogInfo> {
+    app_log_files(&app_log_dir(log_dir, app_name)).into_iter().filter_map(|path| {
+        let metadata = path.metadata().ok()?;
+        Some(AppLogInfo {
+            file_name: path.file_name()?.to_string_lossy().to_string(),
+            size: metadata.len(),
+            modified: format_timestamp(metadata.modified().unwrap_or(UNIX_EPOCH))
+        })
+    }).collect()
+}
+
+// the last max_bytes of the log, starting from a whole line if it is cut
+pub fn read_log_tail<P: AsRef<Path>>(log_dir: P, app_name: &str, file_name: &str, max_bytes: u64) -> Result<String, IoError> {
+    // only the files listed for the app can be read
+    let dir = app_log_dir(log_dir, app_name);
+    let Some(path) = app_log_files(&dir).into_iter().find(|path| path.file_name().is_some_and(|name| name == file_name)) else {
+        return Err(IoError::new(ErrorKind::NotFound, format!("Log file '{}' does not exist", file_name)));
+    };
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf: Vec<u8> = Vec::new();
+    file.read_to_end(&mut buf)?;
+    if start > 0 && let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+        buf.drain(..=pos);
+    }
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+// clear the logs of the app, or of all apps
+pub fn clear_app_logs<P: AsRef<Path>>(log_dir: P, app_name: Option<&str>) -> Result<(), IoError> {
+    let dir = match app_name {
+        Some(app_name) => app_log_dir(log_dir, app_name),
+        None => log_dir.as_ref().to_path_buf()
+    };
+    match remove_dir_all(dir) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(())
+    }
+}
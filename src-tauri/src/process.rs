@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct TrackedProcesses {
+    // app name of each running process spawned by the launcher
+    running: HashMap<u32, String>,
+    // launches that are running their pre-launch hooks or spawning, counted per app
+    starting: HashMap<String, usize>
+}
+
+// processes spawned by the launcher, shared with the threads that wait for them to exit
+#[derive(Clone, Default)]
+pub struct ProcessTracker {
+    processes: Arc<Mutex<TrackedProcesses>>
+}
+
+impl ProcessTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // start a launch of the app, which fails if the app is running or starting and that is not allowed,
+    // so that clicking an app repeatedly does not start it more than once
+    pub fn begin_launch(&self, app_name: &str, allow_running: bool) -> bool {
+        let mut processes = self.processes.lock().unwrap();
+        if !allow_running && (processes.running.values().any(|name| name == app_name) || processes.starting.contains_key(app_name)) {
+            return false;
+        }
+        *processes.starting.entry(app_name.to_string()).or_default() += 1;
+        true
+    }
+
+    // finish a launch started by begin_launch, with the spawned process if any
+    pub fn end_launch(&self, app_name: &str, pid: Option<u32>) {
+        let mut processes = self.processes.lock().unwrap();
+        if let Some(count) = processes.starting.get_mut(app_name) {
+            *count -= 1;
+            if *count == 0 {
+                processes.starting.remove(app_name);
+            }
+        }
+        if let Some(pid) = pid {
+            processes.running.insert(pid, app_name.to_string());
+        }
+    }
+
+    pub fn exited(&self, pid: u32) {
+        self.processes.lock().unwrap().running.remove(&pid);
+    }
+
+    pub fn is_running(&self, app_name: &str) -> bool {
+        self.processes.lock().unwrap().running.values().any(|name| name == app_name)
+    }
+
+    pub fn get_pids(&self, app_name: &str) -> Vec<u32> {
+        let mut pids: Vec<u32> = self.processes.lock().unwrap().running.iter()
+            .filter(|(_, name)| *name == app_name)
+            .map(|(pid, _)| *pid)
+            .collect();
+        pids.sort();
+        pids
+    }
+
+    pub fn get_running_app_list(&self) -> Vec<String> {
+        let mut app_names: Vec<String> = self.processes.lock().unwrap().running.values().cloned().collect();
+        app_names.sort();
+        app_names.dedup();
+        app_names
+    }
+
+    // keep tracking the processes of a renamed app
+    pub fn rename_app(&self, app_name: &str, new_app_name: &str) {
+        let mut processes = self.processes.lock().unwrap();
+        for name in processes.running.values_mut().filter(|name| *name == app_name) {
+            *name = new_app_name.to_string();
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::cmp::Reverse;
+use pinyin::ToPinyin;
+use serde::{Deserialize, Serialize};
+use super::config::{Config, structure::AppMetadata};
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 16;
+const BOUNDARY_BONUS: i64 = 8;
+const EXACT_BONUS: i64 = 64;
+const MAX_LEADING_PENALTY: i64 = 8;
+// matching by pinyin is ranked slightly below matching the characters themselves
+const PINYIN_PENALTY: i64 = 8;
+const PINYIN_INITIALS_PENALTY: i64 = 12;
+
+// fields are declared in ranking order, a match in the name beats any match in the description
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(tag = "type")]
+pub enum SearchField {
+    Name,
+    Description,
+    Tag { index: usize },
+    FileName
+}
+
+// range of matched characters (counted in chars, end exclusive) for highlighting
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct TextMatch {
+    pub score: i64,
+    pub ranges: Vec<MatchRange>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct SearchMatch {
+    pub field: SearchField,
+    pub score: i64,
+    pub ranges: Vec<MatchRange>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub app_name: String,
+    // every matched field, the first one decides the ranking
+    pub matches: Vec<SearchMatch>
+}
+
+impl SearchResult {
+    fn rank_key(&self) -> (&SearchField, Reverse<i64>, &String) {
+        let best_match = &self.matches[0];
+        (&best_match.field, Reverse(best_match.score), &self.app_name)
+    }
+}
+
+pub fn search_apps(config: &Config, query: &str) -> Vec<SearchResult> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut results: Vec<SearchResult> = config.get_all_app_name_list().into_iter().filter_map(|app_name| {
+        let metadata = config.get_app(app_name).expect("App should exist");
+        let matches = match_app(query, app_name, metadata);
+        if matches.is_empty() {
+            None
+        } else {
+            Some(SearchResult { app_name: app_name.clone(), matches })
+        }
+    }).collect();
+    results.sort_by(|a, b| a.rank_key().cmp(&b.rank_key()));
+    results
+}
+
+fn match_app(query: &str, app_name: &str, metadata: &AppMetadata) -> Vec<SearchMatch> {
+    let mut fields = vec![
+        (SearchField::Name, app_name.to_string()),
+        (SearchField::Description, metadata.desc.clone())
+    ];
+    fields.extend(metadata.tags.iter().enumerate().map(|(index, tag)| (SearchField::Tag { index }, tag.clone())));
+    if let Some(file_name) = metadata.app_path.file_name() {
+        fields.push((SearchField::FileName, file_name.to_string_lossy().to_string()));
+    }
+    let mut matches: Vec<SearchMatch> = fields.into_iter().filter_map(|(field, text)| {
+        let text_match = fuzzy_match(query, &text)?;
+        Some(SearchMatch { field, score: text_match.score, ranges: text_match.ranges })
+    }).collect();
+    matches.sort_by(|a, b| a.field.cmp(&b.field).then(b.score.cmp(&a.score)));
+    matches
+}
+
+// case-insensitive subsequence match, falling back to full pinyin and pinyin initials for Chinese text
+pub fn fuzzy_match(query: &str, text: &str) -> Option<TextMatch> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let text_chars: Vec<char> = text.chars().collect();
+    if query.is_empty() || text_chars.is_empty() {
+        return None;
+    }
+    let lower_text: Vec<char> = text_chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let boundaries: Vec<bool> = (0..text_chars.len()).map(|i| is_word_boundary(&text_chars, i)).collect();
+    let mut best = match_chars(&query, &lower_text, &boundaries);
+
+    if text_chars.iter().any(|c| c.to_pinyin().is_some()) {
+        // spaces between syllables are optional when typing pinyin
+        let query: Vec<char> = query.into_iter().filter(|c| !c.is_whitespace()).collect();
+        let (full, initials) = pinyin_of(&text_chars);
+        for (pinyin_text, penalty) in [(full, PINYIN_PENALTY), (initials, PINYIN_INITIALS_PENALTY)] {
+            if let Some((score, positions)) = match_chars(&query, &pinyin_text.chars, &pinyin_text.boundaries) {
+                let score = score - penalty;
+                if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+                    let mut positions: Vec<usize> = positions.into_iter().map(|pos| pinyin_text.owners[pos]).collect();
+                    positions.dedup();
+                    best = Some((score, positions));
+                }
+            }
+        }
+    }
+    best.map(|(score, positions)| TextMatch { score, ranges: to_ranges(&positions) })
+}
+
+fn is_word_boundary(text: &[char], i: usize) -> bool {
+    if i == 0 {
+        return true;
+    }
+    let (prev, cur) = (text[i - 1], text[i]);
+    !prev.is_alphanumeric() || (prev.is_lowercase() && cur.is_uppercase()) || (!prev.is_ascii() && cur.is_ascii())
+}
+
+// find the best scoring alignment of the query as a subsequence of the text
+fn match_chars(query: &[char], text: &[char], boundaries: &[bool]) -> Option<(i64, Vec<usize>)> {
+    let (m, n) = (query.len(), text.len());
+    if m == 0 || m > n {
+        return None;
+    }
+    let char_score = |j: usize| if boundaries[j] { MATCH_SCORE + BOUNDARY_BONUS } else { MATCH_SCORE };
+    // scores[i][j] is the best score with query[i] matched at text[j], and parents[i][j]
+    // is where query[i - 1] was matched in that case
+    let mut scores: Vec<Vec<Option<i64>>> = vec![vec![None; n]; m];
+    let mut parents: Vec<Vec<usize>> = vec![vec![0; n]; m];
+    for j in 0..n {
+        if text[j] == query[0] {
+            scores[0][j] = Some(char_score(j) - (j as i64).min(MAX_LEADING_PENALTY));
+        }
+    }
+    for i in 1..m {
+        // best (score + position) of the earlier matches of query[i - 1], so the gap penalty is linear
+        let mut best_prev: Option<(i64, usize)> = None;
+        for j in 1..n {
+            if let Some(score) = scores[i - 1][j - 1] &&
+                best_prev.is_none_or(|(best, _)| score + (j - 1) as i64 > best) {
+                best_prev = Some((score + (j - 1) as i64, j - 1));
+            }
+            if text[j] != query[i] {
+                continue;
+            }
+            let mut candidate = best_prev.map(|(best, k)| (best - j as i64 + 1, k));
+            if let Some(score) = scores[i - 1][j - 1] &&
+                candidate.is_none_or(|(c, _)| score + CONSECUTIVE_BONUS > c) {
+                candidate = Some((score + CONSECUTIVE_BONUS, j - 1));
+            }
+            if let Some((score, k)) = candidate {
+                scores[i][j] = Some(score + char_score(j));
+                parents[i][j] = k;
+            }
+        }
+    }
+
+    let (mut score, mut pos) = (None, 0);
+    for (j, s) in scores[m - 1].iter().enumerate() {
+        if let Some(s) = *s && score.is_none_or(|best| s > best) {
+            (score, pos) = (Some(s), j);
+        }
+    }
+    let mut score = score?;
+    let mut positions = vec![pos];
+    for i in (1..m).rev() {
+        pos = parents[i][pos];
+        positions.push(pos);
+    }
+    positions.reverse();
+    if m == n {
+        score += EXACT_BONUS;
+    }
+    Some((score, positions))
+}
+
+// pinyin spelling of a text, remembering which original char every pinyin char comes from
+#[derive(Default)]
+struct PinyinText {
+    chars: Vec<char>,
+    owners: Vec<usize>,
+    boundaries: Vec<bool>
+}
+
+impl PinyinText {
+    fn push(&mut self, c: char, owner: usize, boundary: bool) {
+        self.chars.push(c);
+        self.owners.push(owner);
+        self.boundaries.push(boundary);
+    }
+}
+
+// returns the full pinyin and the pinyin initials of the text
+fn pinyin_of(text: &[char]) -> (PinyinText, PinyinText) {
+    let mut full = PinyinText::default();
+    let mut initials = PinyinText::default();
+    for (i, c) in text.iter().enumerate() {
+        if c.is_whitespace() {
+            continue;
+        }
+        match c.to_pinyin() {
+            Some(pinyin) => {
+                for (j, p) in pinyin.plain().chars().enumerate() {
+                    full.push(p, i, j == 0);
+                }
+                initials.push(pinyin.first_letter().chars().next().expect("Pinyin should not be empty"), i, true);
+            },
+            None => {
+                let lower = c.to_lowercase().next().unwrap_or(*c);
+                let boundary = is_word_boundary(text, i);
+                full.push(lower, i, boundary);
+                initials.push(lower, i, boundary);
+            }
+        }
+    }
+    (full, initials)
+}
+
+fn to_ranges(positions: &[usize]) -> Vec<MatchRange> {
+    let mut ranges: Vec<MatchRange> = Vec::new();
+    for &pos in positions {
+        match ranges.last_mut() {
+            Some(range) if range.end == pos => range.end += 1,
+            _ => ranges.push(MatchRange { start: pos, end: pos + 1 })
+        }
+    }
+    ranges
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::{read as fs_read, read_to_string as fs_read_to_string, write as fs_write};
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+use base64::prelude::*;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Error as SerdeError;
+use super::config::Config;
+use super::integrity::FileDigest;
+
+// public keys (base64) of the publishers trusted by every build of the launcher
+pub const EMBEDDED_TRUSTED_KEYS: &[&str] = &[];
+// additional trusted public keys configured for this launcher, one per line, in the config dir of the launcher
+// instead of next to config.json, so that whoever can edit the config cannot trust a key of their own
+pub const TRUSTED_KEYS_FILE_NAME: &str = "trusted_keys.txt";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq)]
+#[serde(tag = "type")]
+pub enum SignatureStatus {
+    #[default]
+    Unsigned,
+    Valid,
+    Tampered
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ConfigSignature {
+    pub public_key: String,
+    pub signature: String,
+    // SHA-256 of the referenced app executables, covered by the signature as well
+    pub app_hashes: Option<BTreeMap<String, String>>
+}
+
+impl ConfigSignature {
+    pub fn sign<P: AsRef<Path>>(config_path: P, signing_key: &SigningKey, config: Option<&Config>) -> Result<Self, SignatureError> {
+        let config_path = config_path.as_ref();
+        let config_content = fs_read(config_path).map_err(|e| SignatureError {
+            err_type: SignatureErrorType::ReadError(e), signature_path: Some(config_path.to_string_lossy().to_string())
+        })?;
+        let app_hashes = match config {
+            Some(config) => Some(hash_apps(config)?),
+            None => None
+        };
+        let signature = signing_key.sign(&signed_message(&config_content, &app_hashes));
+        Ok(Self {
+            public_key: BASE64_STANDARD.encode(signing_key.verifying_key().as_bytes()),
+            signature: BASE64_STANDARD.encode(signature.to_bytes()),
+            app_hashes
+        })
+    }
+
+    pub fn verify<P: AsRef<Path>>(&self, config_path: P, trusted_keys: &[VerifyingKey], config: &Config) -> SignatureStatus {
+        let Some(public_key) = decode_public_key(&self.public_key) else {
+            return SignatureStatus::Tampered;
+        };
+        // a config re-signed with an unknown key cannot be told apart from a tampered one
+        if !trusted_keys.contains(&public_key) {
+            return SignatureStatus::Tampered;
+        }
+        let Some(signature) = BASE64_STANDARD.decode(&self.signature).ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok()) else {
+            return SignatureStatus::Tampered;
+        };
+        let Ok(config_content) = fs_read(config_path) else {
+            return SignatureStatus::Tampered;
+        };
+        if public_key.verify(&signed_message(&config_content, &self.app_hashes), &signature).is_err() {
+            return SignatureStatus::Tampered;
+        }
+        if let Some(app_hashes) = &self.app_hashes &&
+            !hash_apps(config).is_ok_and(|current_hashes| current_hashes == *app_hashes) {
+            return SignatureStatus::Tampered;
+        }
+        SignatureStatus::Valid
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SignatureError> {
+        let path = path.as_ref();
+        let path_str = path.to_string_lossy().to_string();
+
+        if !path.is_file() {
+            return Err(SignatureError { err_type: SignatureErrorType::FileNotExist, signature_path: Some(path_str) });
+        }
+        match fs_read_to_string(path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(signature) => Ok(signature),
+                Err(e) => Err(SignatureError { err_type: SignatureErrorType::ParseError(e), signature_path: Some(path_str) })
+            },
+            Err(e) => Err(SignatureError { err_type: SignatureErrorType::ReadError(e), signature_path: Some(path_str) })
+        }
+    }
+
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), SignatureError> {
+        let path = path.as_ref();
+        let path_str = path.to_string_lossy().to_string();
+        let content = serde_json::to_string_pretty(self).expect("Failed to serialize signature");
+
+        if let Err(e) = fs_write(path, content) {
+            Err(SignatureError { err_type: SignatureErrorType::WriteError(e), signature_path: Some(path_str) })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// the detached signature is stored next to the config, e.g. config.json.sig
+pub fn signature_path_of<P: AsRef<Path>>(config_path: P) -> PathBuf {
+    let mut path = config_path.as_ref().as_os_str().to_owned();
+    path.push(".sig");
+    PathBuf::from(path)
+}
+
+pub fn verify_config_file<P: AsRef<Path>>(config_path: P, trusted_keys: &[VerifyingKey], config: &Config) -> SignatureStatus {
+    let signature_path = signature_path_of(&config_path);
+    if !signature_path.is_file() {
+        return SignatureStatus::Unsigned;
+    }
+    match ConfigSignature::from_file(signature_path) {
+        Ok(signature) => signature.verify(config_path, trusted_keys, config),
+        Err(_) => SignatureStatus::Tampered
+    }
+}
+
+fn signed_message(config_content: &[u8], app_hashes: &Option<BTreeMap<String, String>>) -> Vec<u8> {
+    let mut message = config_content.to_vec();
+    if let Some(app_hashes) = app_hashes {
+        message.push(0);
+        message.extend(serde_json::to_vec(app_hashes).expect("Failed to serialize app hashes"));
+    }
+    message
+}
+
+fn hash_apps(config: &Config) -> Result<BTreeMap<String, String>, SignatureError> {
+    let mut app_hashes = BTreeMap::new();
+    for app_name in config.get_all_app_name_list() {
+        let metadata = config.get_app(app_name).expect("App should exist");
+        // folders and urls have no file to hash
+        if !metadata.kind.is_file() {
+            continue;
+        }
+        let digest = FileDigest::from_file(&metadata.app_path).map_err(|e| SignatureError {
+            err_type: SignatureErrorType::AppReadError(app_name.clone(), e), signature_path: None
+        })?;
+        app_hashes.insert(app_name.clone(), digest.sha256);
+    }
+    Ok(app_hashes)
+}
+
+fn decode_public_key(key: &str) -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = BASE64_STANDARD.decode(key.trim()).ok()?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+fn embedded_trusted_keys() -> Vec<VerifyingKey> {
+    EMBEDDED_TRUSTED_KEYS.iter().filter_map(|key| decode_public_key(key)).collect()
+}
+
+// load the embedded keys plus the ones listed (one base64 key per line) in the given file
+pub fn load_trusted_keys<P: AsRef<Path>>(path: P) -> Vec<VerifyingKey> {
+    let mut keys = embedded_trusted_keys();
+    if let Ok(content) = fs_read_to_string(path) {
+        keys.extend(content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(decode_public_key));
+    }
+    keys
+}
+
+// the signature status of the config file, verified again once the config or its signature changed,
+// e.g. after an edit in the launcher or signing it
+#[derive(Clone, Debug, Default)]
+pub struct ConfigVerifier {
+    trusted_keys_path: Option<PathBuf>,
+    status: SignatureStatus,
+    // SHA-256 of the config and its signature as of the last verification
+    verified_digests: Option<(Option<String>, Option<String>)>
+}
+
+impl ConfigVerifier {
+    // without a config dir, only the embedded keys are trusted
+    pub fn new(trusted_keys_dir: Option<PathBuf>) -> Self {
+        Self { trusted_keys_path: trusted_keys_dir.map(|dir| dir.join(TRUSTED_KEYS_FILE_NAME)), ..Default::default() }
+    }
+
+    pub fn status<P: AsRef<Path>>(&mut self, config_path: P, config: &Config) -> SignatureStatus {
+        let digest_of = |path: &Path| FileDigest::from_file(path).ok().map(|digest| digest.sha256);
+        let digests = (digest_of(config_path.as_ref()), digest_of(&signature_path_of(&config_path)));
+        if self.verified_digests.as_ref() != Some(&digests) {
+            let trusted_keys = match &self.trusted_keys_path {
+                Some(path) => load_trusted_keys(path),
+                None => embedded_trusted_keys()
+            };
+            self.status = verify_config_file(&config_path, &trusted_keys, config);
+            self.verified_digests = Some(digests);
+        }
+        self.status.clone()
+    }
+
+    // verify again on the next request, e.g. after the trusted keys changed
+    pub fn invalidate(&mut self) {
+        self.verified_digests = None;
+    }
+}
+
+pub fn generate_signing_key() -> SigningKey {
+    SigningKey::generate(&mut OsRng)
+}
+
+pub fn signing_key_from_file<P: AsRef<Path>>(path: P) -> Result<SigningKey, SignatureError> {
+    let path = path.as_ref();
+    let path_str = path.to_string_lossy().to_string();
+    let content = fs_read_to_string(path).map_err(|e| SignatureError {
+        err_type: SignatureErrorType::ReadError(e), signature_path: Some(path_str.clone())
+    })?;
+    let bytes: [u8; 32] = BASE64_STANDARD.decode(content.trim()).ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(SignatureError { err_type: SignatureErrorType::InvalidKey, signature_path: Some(path_str) })?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+pub fn signing_key_to_file<P: AsRef<Path>>(signing_key: &SigningKey, path: P) -> Result<(), SignatureError> {
+    let path = path.as_ref();
+    if let Err(e) = fs_write(path, BASE64_STANDARD.encode(signing_key.to_bytes())) {
+        Err(SignatureError { err_type: SignatureErrorType::WriteError(e), signature_path: Some(path.to_string_lossy().to_string()) })
+    } else {
+        Ok(())
+    }
+}
+
+pub fn encode_public_key(signing_key: &SigningKey) -> String {
+    BASE64_STANDARD.encode(signing_key.verifying_key().as_bytes())
+}
+
+#[derive(Debug)]
+pub struct SignatureError {
+    pub err_type: SignatureErrorType,
+    pub signature_path: Option<String>
+}
+
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let signature_path_str = self.signature_path.clone().unwrap_or(String::from("<unknown>"));
+        match &self.err_type {
+            SignatureErrorType::ParseError(e) => write!(f, "Failed to parse signature file '{}': {}", signature_path_str, e),
+            SignatureErrorType::ReadError(e) => write!(f, "Failed to read file '{}': {}", signature_path_str, e),
+            SignatureErrorType::WriteError(e) => write!(f, "Failed to write file '{}': {}", signature_path_str, e),
+            SignatureErrorType::FileNotExist => write!(f, "Signature file '{}' does not exist", signature_path_str),
+            SignatureErrorType::InvalidKey => write!(f, "Key file '{}' does not contain a valid ed25519 key", signature_path_str),
+            SignatureErrorType::AppReadError(app_name, e) => write!(f, "Failed to hash app '{}': {}", app_name, e)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SignatureErrorType {
+    ParseError(SerdeError),
+    ReadError(IoError),
+    WriteError(IoError),
+    FileNotExist,
+    InvalidKey,
+    AppReadError(String, IoError)
+}
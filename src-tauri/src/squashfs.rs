@@ -0,0 +1,350 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::io::{Error as IoError, Read, Seek, SeekFrom, Write};
+
+const SQUASHFS_MAGIC: &[u8; 4] = b"hsqs";
+const SUPERBLOCK_SIZE: usize = 96;
+const METADATA_BLOCK_SIZE: usize = 8192;
+// set in the header of a metadata block, or in the size of a data block, when it is stored uncompressed
+const METADATA_UNCOMPRESSED: u16 = 0x8000;
+const DATA_UNCOMPRESSED: u32 = 0x0100_0000;
+const NO_FRAGMENT: u32 = 0xFFFF_FFFF;
+const FRAGMENT_ENTRY_SIZE: usize = 16;
+const MAX_SYMLINK_HOPS: usize = 8;
+// files are read whole into memory, which is meant for small files like icons
+pub const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Compression {
+    Gzip,
+    Xz,
+    Zstd
+}
+
+#[derive(Clone, Debug)]
+enum Inode {
+    Dir { block: u32, offset: u16, size: u32 },
+    File { blocks_start: u64, size: u64, fragment: u32, fragment_offset: u32, block_sizes: Vec<u32> },
+    Symlink(String),
+    Other
+}
+
+// read-only access to the files of a squashfs image, e.g. the one embedded in an AppImage
+pub struct SquashFs<R: Read + Seek> {
+    reader: R,
+    // position of the image in the reader, all the positions in the image are relative to it
+    start: u64,
+    compression: Compression,
+    block_size: u32,
+    root_inode: u64,
+    inode_table: u64,
+    dir_table: u64,
+    fragment_table: u64
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], pos: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(pos..pos + 8)?.try_into().ok()?))
+}
+
+fn corrupted() -> SquashFsError {
+    SquashFsError { err_type: SquashFsErrorType::Corrupted, path: None }
+}
+
+// fails the write once more than the limit is written, so a decoder stops before inflating all of its input
+struct LimitedWriter {
+    out: Vec<u8>,
+    limit: usize
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.out.len() + buf.len() > self.limit {
+            return Err(IoError::other("decompressed data exceeds the limit"));
+        }
+        self.out.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> SquashFs<R> {
+    // open the image starting at the position of the reader
+    pub fn new(mut reader: R, start: u64) -> Result<Self, SquashFsError> {
+        let mut superblock = [0u8; SUPERBLOCK_SIZE];
+        reader.seek(SeekFrom::Start(start)).and_then(|_| reader.read_exact(&mut superblock))
+            .map_err(|e| SquashFsError { err_type: SquashFsErrorType::ReadError(e), path: None })?;
+        let block_size = read_u32(&superblock, 12).unwrap();
+        // blocks are from 4 KiB to 1 MiB
+        let valid_block_size = block_size.is_power_of_two() && (4096..=1024 * 1024).contains(&block_size);
+        if &superblock[0..4] != SQUASHFS_MAGIC || read_u16(&superblock, 28) != Some(4) || !valid_block_size {
+            return Err(SquashFsError { err_type: SquashFsErrorType::InvalidImage, path: None });
+        }
+        let compression = match read_u16(&superblock, 20).unwrap() {
+            1 => Compression::Gzip,
+            4 => Compression::Xz,
+            6 => Compression::Zstd,
+            id => return Err(SquashFsError { err_type: SquashFsErrorType::UnsupportedCompression(id), path: None })
+        };
+        Ok(Self {
+            reader,
+            start,
+            compression,
+            block_size,
+            root_inode: read_u64(&superblock, 32).unwrap(),
+            inode_table: read_u64(&superblock, 64).unwrap(),
+            dir_table: read_u64(&superblock, 72).unwrap(),
+            fragment_table: read_u64(&superblock, 80).unwrap()
+        })
+    }
+
+    fn read_at(&mut self, pos: u64, len: usize) -> Result<Vec<u8>, SquashFsError> {
+        let pos = self.start.checked_add(pos).ok_or_else(corrupted)?;
+        let mut buf = vec![0u8; len];
+        self.reader.seek(SeekFrom::Start(pos)).and_then(|_| self.reader.read_exact(&mut buf))
+            .map_err(|e| SquashFsError { err_type: SquashFsErrorType::ReadError(e), path: None })?;
+        Ok(buf)
+    }
+
+    fn decompress(&self, data: &[u8], max_size: usize) -> Result<Vec<u8>, SquashFsError> {
+        let mut out: Vec<u8> = Vec::new();
+        let result = match self.compression {
+            Compression::Gzip => flate2::read::ZlibDecoder::new(data).take(max_size as u64).read_to_end(&mut out).map(|_| ()),
+            Compression::Zstd => ruzstd::decoding::StreamingDecoder::new(data)
+                .map_err(|e| IoError::other(e.to_string()))
+                .and_then(|decoder| decoder.take(max_size as u64).read_to_end(&mut out).map(|_| ())),
+            Compression::Xz => {
+                let mut writer = LimitedWriter { out: Vec::new(), limit: max_size };
+                let result = lzma_rs::xz_decompress(&mut &data[..], &mut writer).map_err(|e| IoError::other(e.to_string()));
+                out = writer.out;
+                result
+            }
+        };
+        if result.is_err() || out.len() > max_size {
+            return Err(corrupted());
+        }
+        Ok(out)
+    }
+
+    // len bytes from the metadata blocks of the table, starting at the offset in the block at the position
+    fn read_metadata(&mut self, table: u64, block: u64, offset: usize, len: usize) -> Result<Vec<u8>, SquashFsError> {
+        let mut pos = table.checked_add(block).ok_or_else(corrupted)?;
+        let end = offset.checked_add(len).ok_or_else(corrupted)?;
+        let mut data: Vec<u8> = Vec::new();
+        while data.len() < end {
+            let header = read_u16(&self.read_at(pos, 2)?, 0).unwrap();
+            let size = (header & !METADATA_UNCOMPRESSED) as usize;
+            if size == 0 || size > METADATA_BLOCK_SIZE {
+                return Err(corrupted());
+            }
+            let block_data = self.read_at(pos.checked_add(2).ok_or_else(corrupted)?, size)?;
+            if header & METADATA_UNCOMPRESSED != 0 {
+                data.extend_from_slice(&block_data);
+            } else {
+                data.extend(self.decompress(&block_data, METADATA_BLOCK_SIZE)?);
+            }
+            pos = pos.checked_add(2 + size as u64).ok_or_else(corrupted)?;
+        }
+        Ok(data[offset..end].to_vec())
+    }
+
+    // inode by its reference, the position of its metadata block in the upper bits and the offset in the block in the lower 16 bits
+    fn read_inode(&mut self, inode_ref: u64) -> Result<Inode, SquashFsError> {
+        let (block, offset) = (inode_ref >> 16, (inode_ref & 0xFFFF) as usize);
+        // the header common to all inodes, then the fixed part of the type
+        let data = self.read_metadata(self.inode_table, block, offset, 16)?;
+        let inode_type = read_u16(&data, 0).unwrap();
+        let fixed_len = match inode_type { 1 => 16, 2 => 16, 3 => 8, 8 => 24, 9 => 40, 10 => 8, _ => 0 };
+        let data = self.read_metadata(self.inode_table, block, offset + 16, fixed_len)?;
+        let inode = match inode_type {
+            1 => Inode::Dir { block: read_u32(&data, 0).unwrap(), offset: read_u16(&data, 10).unwrap(), size: read_u16(&data, 8).unwrap() as u32 },
+            8 => Inode::Dir { block: read_u32(&data, 8).unwrap(), offset: read_u16(&data, 18).unwrap(), size: read_u32(&data, 4).unwrap() },
+            2 | 9 => {
+                let (blocks_start, size, fragment, fragment_offset) = if inode_type == 2 {
+                    (read_u32(&data, 0).unwrap() as u64, read_u32(&data, 12).unwrap() as u64, read_u32(&data, 4).unwrap(), read_u32(&data, 8).unwrap())
+                } else {
+                    (read_u64(&data, 0).unwrap(), read_u64(&data, 8).unwrap(), read_u32(&data, 28).unwrap(), read_u32(&data, 32).unwrap())
+                };
+                let block_size = self.block_size as u64;
+                // the tail of the file is in a fragment shared with other files, if it has one
+                let block_count = if fragment == NO_FRAGMENT { size.div_ceil(block_size) } else { size / block_size };
+                if size > MAX_FILE_SIZE {
+                    return Err(SquashFsError { err_type: SquashFsErrorType::FileTooLarge, path: None });
+                }
+                let sizes = self.read_metadata(self.inode_table, block, offset + 16 + fixed_len, block_count as usize * 4)?;
+                let block_sizes = sizes.chunks_exact(4).map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())).collect();
+                Inode::File { blocks_start, size, fragment, fragment_offset, block_sizes }
+            },
+            3 | 10 => {
+                let target_size = read_u32(&data, 4).unwrap() as usize;
+                let target = self.read_metadata(self.inode_table, block, offset + 16 + fixed_len, target_size.min(4096))?;
+                Inode::Symlink(String::from_utf8_lossy(&target).to_string())
+            },
+            _ => Inode::Other
+        };
+        Ok(inode)
+    }
+
+    // names and inode references of the entries of the directory
+    fn read_dir(&mut self, block: u32, offset: u16, size: u32) -> Result<Vec<(String, u64)>, SquashFsError> {
+        // the size counts 3 bytes more than the listing
+        if size <= 3 {
+            return Ok(Vec::new());
+        }
+        let data = self.read_metadata(self.dir_table, block as u64, offset as usize, size as usize - 3)?;
+        let mut entries: Vec<(String, u64)> = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let count = read_u32(&data, pos).ok_or_else(corrupted)? as usize + 1;
+            let start = read_u32(&data, pos + 4).ok_or_else(corrupted)? as u64;
+            pos += 12;
+            for _ in 0..count {
+                let inode_offset = read_u16(&data, pos).ok_or_else(corrupted)? as u64;
+                let name_len = read_u16(&data, pos + 6).ok_or_else(corrupted)? as usize + 1;
+                let name = data.get(pos + 8..pos + 8 + name_len).ok_or_else(corrupted)?;
+                entries.push((String::from_utf8_lossy(name).to_string(), (start << 16) | inode_offset));
+                pos += 8 + name_len;
+            }
+        }
+        Ok(entries)
+    }
+
+    // the inode at the path, following symlinks within the image
+    fn lookup(&mut self, path: &str) -> Result<Inode, SquashFsError> {
+        let not_found = || SquashFsError { err_type: SquashFsErrorType::NotFound, path: Some(path.to_string()) };
+        let mut components: VecDeque<String> = path.split('/').map(String::from).collect();
+        let root = self.read_inode(self.root_inode)?;
+        let mut dirs: Vec<Inode> = vec![root];
+        let mut hops = 0;
+        while let Some(component) = components.pop_front() {
+            match component.as_str() {
+                "" | "." => continue,
+                ".." => {
+                    if dirs.len() > 1 {
+                        dirs.pop();
+                    }
+                    continue;
+                },
+                _ => {}
+            }
+            let Some(Inode::Dir { block, offset, size }) = dirs.last().cloned() else {
+                return Err(not_found());
+            };
+            let entries = self.read_dir(block, offset, size)?;
+            let Some((_, inode_ref)) = entries.into_iter().find(|(name, _)| *name == component) else {
+                return Err(not_found());
+            };
+            match self.read_inode(inode_ref)? {
+                Inode::Symlink(target) => {
+                    hops += 1;
+                    // absolute targets point out of the image
+                    if hops > MAX_SYMLINK_HOPS || target.starts_with('/') {
+                        return Err(not_found());
+                    }
+                    for target_component in target.split('/').rev() {
+                        components.push_front(target_component.to_string());
+                    }
+                },
+                inode => dirs.push(inode)
+            }
+        }
+        Ok(dirs.pop().unwrap())
+    }
+
+    // names of the entries of the directory at the path, "" for the root
+    pub fn list_dir(&mut self, path: &str) -> Result<Vec<String>, SquashFsError> {
+        match self.lookup(path)? {
+            Inode::Dir { block, offset, size } => Ok(self.read_dir(block, offset, size)?.into_iter().map(|(name, _)| name).collect()),
+            _ => Err(SquashFsError { err_type: SquashFsErrorType::NotADirectory, path: Some(path.to_string()) })
+        }
+    }
+
+    fn read_data_block(&mut self, pos: u64, size_field: u32, max_size: usize) -> Result<Vec<u8>, SquashFsError> {
+        let size = (size_field & !DATA_UNCOMPRESSED) as usize;
+        // blocks are never stored larger than the block size, compressed or not
+        if size > self.block_size as usize {
+            return Err(corrupted());
+        }
+        let data = self.read_at(pos, size)?;
+        if size_field & DATA_UNCOMPRESSED != 0 { Ok(data) } else { self.decompress(&data, max_size) }
+    }
+
+    pub fn read_file(&mut self, path: &str) -> Result<Vec<u8>, SquashFsError> {
+        let Inode::File { blocks_start, size, fragment, fragment_offset, block_sizes } = self.lookup(path)? else {
+            return Err(SquashFsError { err_type: SquashFsErrorType::NotAFile, path: Some(path.to_string()) });
+        };
+        let block_size = self.block_size as usize;
+        let mut content: Vec<u8> = Vec::with_capacity(size as usize);
+        let mut pos = blocks_start;
+        for size_field in block_sizes {
+            let remaining = (size as usize).checked_sub(content.len()).ok_or_else(corrupted)?;
+            if size_field == 0 {
+                // sparse block
+                content.resize(content.len() + remaining.min(block_size), 0);
+                continue;
+            }
+            content.extend(self.read_data_block(pos, size_field, block_size)?);
+            pos = pos.checked_add((size_field & !DATA_UNCOMPRESSED) as u64).ok_or_else(corrupted)?;
+        }
+        if fragment != NO_FRAGMENT {
+            // the fragment table lists where the metadata blocks with the fragment entries are
+            let entry_index = fragment as usize * FRAGMENT_ENTRY_SIZE;
+            let table_pos = self.fragment_table.checked_add((entry_index / METADATA_BLOCK_SIZE) as u64 * 8).ok_or_else(corrupted)?;
+            let block_pos = read_u64(&self.read_at(table_pos, 8)?, 0).unwrap();
+            let entry = self.read_metadata(0, block_pos, entry_index % METADATA_BLOCK_SIZE, FRAGMENT_ENTRY_SIZE)?;
+            let fragment_block = self.read_data_block(read_u64(&entry, 0).unwrap(), read_u32(&entry, 8).unwrap(), block_size)?;
+            let tail_len = (size as usize).checked_sub(content.len()).ok_or_else(corrupted)?;
+            let tail_start = fragment_offset as usize;
+            let tail = fragment_block.get(tail_start..tail_start.checked_add(tail_len).ok_or_else(corrupted)?).ok_or_else(corrupted)?;
+            content.extend_from_slice(tail);
+        }
+        if content.len() != size as usize {
+            return Err(corrupted());
+        }
+        Ok(content)
+    }
+}
+
+#[derive(Debug)]
+pub struct SquashFsError {
+    pub err_type: SquashFsErrorType,
+    pub path: Option<String>
+}
+
+impl Display for SquashFsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path_str = self.path.clone().unwrap_or(String::from("<unknown>"));
+        match &self.err_type {
+            SquashFsErrorType::ReadError(e) => write!(f, "Failed to read squashfs image: {}", e),
+            SquashFsErrorType::InvalidImage => write!(f, "Not a squashfs 4.0 image"),
+            SquashFsErrorType::UnsupportedCompression(id) => write!(f, "Squashfs compression {} is not supported", id),
+            SquashFsErrorType::Corrupted => write!(f, "Squashfs image is corrupted"),
+            SquashFsErrorType::NotFound => write!(f, "'{}' does not exist in squashfs image", path_str),
+            SquashFsErrorType::NotADirectory => write!(f, "'{}' is not a directory", path_str),
+            SquashFsErrorType::NotAFile => write!(f, "'{}' is not a file", path_str),
+            SquashFsErrorType::FileTooLarge => write!(f, "File '{}' is too large to read", path_str)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SquashFsErrorType {
+    ReadError(IoError),
+    InvalidImage,
+    UnsupportedCompression(u16),
+    Corrupted,
+    NotFound,
+    NotADirectory,
+    NotAFile,
+    FileTooLarge
+}
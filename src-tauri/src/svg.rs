@@ -0,0 +1,124 @@
+use image::RgbaImage;
+use resvg::{tiny_skia, usvg};
+use usvg::roxmltree::{self, Document, Node, NodeType};
+use usvg::{Error as SvgError, ImageHrefResolver, Options};
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+// elements that run code or embed other documents
+const BLOCKED_ELEMENTS: [&str; 5] = ["script", "foreignObject", "iframe", "embed", "object"];
+// elements that change the attributes of their target, which may turn a safe link into a script url
+const ANIMATION_ELEMENTS: [&str; 4] = ["set", "animate", "animateTransform", "animateMotion"];
+
+// render the svg to fit in a square of the size, keeping its aspect ratio
+pub fn rasterize_svg(data: &[u8], size: u32) -> Result<RgbaImage, SvgError> {
+    // only images embedded as data urls are loaded, never files or urls
+    let options = Options {
+        image_href_resolver: ImageHrefResolver { resolve_string: Box::new(|_, _| None), ..Default::default() },
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_data(data, &options)?;
+    let scale = size.max(1) as f32 / tree.size().width().max(tree.size().height());
+    let width = (tree.size().width() * scale).round().max(1.0) as u32;
+    let height = (tree.size().height() * scale).round().max(1.0) as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or(SvgError::InvalidSize)?;
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    // the pixmap has premultiplied alpha
+    let buf = pixmap.pixels().iter().flat_map(|pixel| {
+        let color = pixel.demultiply();
+        [color.red(), color.green(), color.blue(), color.alpha()]
+    }).collect();
+    RgbaImage::from_vec(width, height, buf).ok_or(SvgError::InvalidSize)
+}
+
+// whether the value refers to anything but the elements of the document itself
+fn has_external_reference(value: &str) -> bool {
+    let value = value.to_ascii_lowercase();
+    value.contains("@import") || value.match_indices("url(").any(|(pos, _)| {
+        !value[pos + 4..].trim_start_matches([' ', '\'', '"']).starts_with('#')
+    })
+}
+
+fn is_unsafe_attribute(name: &str, value: &str) -> bool {
+    if name.to_ascii_lowercase().starts_with("on") {
+        return true;
+    }
+    if name == "href" {
+        let value = value.trim().to_ascii_lowercase();
+        // embedded svg documents are not sanitized, so only raster images may be embedded
+        return !(value.starts_with('#') || (value.starts_with("data:image/") && !value.starts_with("data:image/svg")));
+    }
+    has_external_reference(value)
+}
+
+// an animation of the link or of an event handler, whatever value it animates to
+fn is_unsafe_animation(node: Node) -> bool {
+    ANIMATION_ELEMENTS.contains(&node.tag_name().name()) && node.attribute("attributeName").is_some_and(|name| {
+        let name = name.trim().rsplit(':').next().unwrap_or_default().to_ascii_lowercase();
+        name == "href" || name.starts_with("on")
+    })
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn write_node(node: Node, out: &mut String) {
+    match node.node_type() {
+        NodeType::Element => write_element(node, out),
+        NodeType::Text => out.push_str(&escape_xml(node.text().unwrap_or(""))),
+        // comments and processing instructions are dropped
+        _ => {}
+    }
+}
+
+fn write_element(node: Node, out: &mut String) {
+    let tag_name = node.tag_name();
+    // elements of other namespaces, like the metadata of editors, are dropped with their children
+    if tag_name.namespace() != Some(SVG_NAMESPACE) || BLOCKED_ELEMENTS.contains(&tag_name.name()) || is_unsafe_animation(node) {
+        return;
+    }
+    if tag_name.name() == "style" && node.children().any(|child| child.text().is_some_and(has_external_reference)) {
+        return;
+    }
+    out.push('<');
+    out.push_str(tag_name.name());
+    if node.parent().is_some_and(|parent| parent.is_root()) {
+        out.push_str(&format!(" xmlns=\"{}\" xmlns:xlink=\"{}\"", SVG_NAMESPACE, XLINK_NAMESPACE));
+    }
+    for attr in node.attributes() {
+        let name = match attr.namespace() {
+            None => attr.name().to_string(),
+            Some(XLINK_NAMESPACE) => format!("xlink:{}", attr.name()),
+            Some(XML_NAMESPACE) => format!("xml:{}", attr.name()),
+            Some(_) => continue
+        };
+        if !is_unsafe_attribute(attr.name(), attr.value()) {
+            out.push_str(&format!(" {}=\"{}\"", name, escape_xml(attr.value())));
+        }
+    }
+    if !node.has_children() {
+        out.push_str("/>");
+        return;
+    }
+    out.push('>');
+    for child in node.children() {
+        write_node(child, out);
+    }
+    out.push_str(&format!("</{}>", tag_name.name()));
+}
+
+// rewrite the svg with only the svg elements and attributes that cannot run code or load anything
+pub fn sanitize_svg(data: &[u8]) -> Result<String, SvgError> {
+    let text = std::str::from_utf8(data).map_err(|_| SvgError::NotAnUtf8Str)?;
+    // documents with a DTD are refused, icons have no use for entities
+    let document = Document::parse(text.trim_start_matches('\u{feff}'))?;
+    let root = document.root_element();
+    if !root.has_tag_name((SVG_NAMESPACE, "svg")) {
+        return Err(roxmltree::Error::NoRootNode.into());
+    }
+    let mut out = String::new();
+    write_element(root, &mut out);
+    Ok(out)
+}
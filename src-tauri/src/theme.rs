@@ -0,0 +1,206 @@
+use std::fmt::Display;
+use std::fs::{create_dir_all, read_dir, read_to_string as fs_read_to_string, remove_file, write as fs_write};
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::Error as SerdeError;
+use super::config::structure::{ColorStop, DEFAULT_GRADIENT_ANGLE, Theme, ThemeColor};
+
+// custom presets are stored next to the config, one json file per preset
+pub const THEME_PRESET_DIR: &str = "theme_presets";
+// the header text is drawn in white on top of the theme
+pub const HEADER_TEXT_COLOR: ThemeColor = ThemeColor::RGB { r: 255, g: 255, b: 255 };
+// WCAG AA for large text, which the header text is
+pub const MIN_CONTRAST_RATIO: f64 = 3.0;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ThemePreset {
+    pub name: String,
+    pub theme: Theme,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub builtin: bool
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl ThemePreset {
+    pub fn new(name: &str, theme: Theme) -> Self {
+        Self { name: name.to_string(), theme, builtin: false }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        let path = path.as_ref();
+        let path_str = path.to_string_lossy().to_string();
+
+        if !path.is_file() {
+            return Err(ThemeError { err_type: ThemeErrorType::FileNotExist, preset_path: Some(path_str) });
+        }
+        match fs_read_to_string(path) {
+            Ok(content) => match serde_json::from_str::<ThemePreset>(&content) {
+                // a file never makes a built-in preset
+                Ok(preset) => Ok(Self { builtin: false, ..preset }),
+                Err(e) => Err(ThemeError { err_type: ThemeErrorType::ParseError(e), preset_path: Some(path_str) })
+            },
+            Err(e) => Err(ThemeError { err_type: ThemeErrorType::ReadError(e), preset_path: Some(path_str) })
+        }
+    }
+
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ThemeError> {
+        let path = path.as_ref();
+        let path_str = path.to_string_lossy().to_string();
+        let content = serde_json::to_string_pretty(&Self { builtin: false, ..self.clone() }).expect("Failed to serialize theme preset");
+
+        if let Err(e) = fs_write(path, content) {
+            Err(ThemeError { err_type: ThemeErrorType::WriteError(e), preset_path: Some(path_str) })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn gradient_preset(name: &str, from: (u8, u8, u8), to: (u8, u8, u8)) -> ThemePreset {
+    ThemePreset {
+        name: name.to_string(),
+        theme: Theme::LinearGradient {
+            stops: vec![
+                ColorStop { color: ThemeColor::RGB { r: from.0, g: from.1, b: from.2 }, position: 0 },
+                ColorStop { color: ThemeColor::RGB { r: to.0, g: to.1, b: to.2 }, position: 100 }
+            ],
+            angle: DEFAULT_GRADIENT_ANGLE
+        },
+        builtin: true
+    }
+}
+
+pub fn builtin_presets() -> Vec<ThemePreset> {
+    vec![
+        gradient_preset("Azure", (0x28, 0x54, 0xB5), (0x14, 0xC0, 0xD3)),
+        gradient_preset("Violet", (0x70, 0x28, 0xAC), (0xAB, 0x59, 0xC7)),
+        gradient_preset("Coral", (0xBE, 0x5F, 0x48), (0xE0, 0xAA, 0x67)),
+        gradient_preset("Teal", (0x00, 0x78, 0x74), (0x59, 0xB1, 0xBA)),
+        gradient_preset("Pink", (0xB3, 0x37, 0x7C), (0xE2, 0x78, 0xB1)),
+        gradient_preset("Forest", (0x1D, 0x4D, 0x2B), (0x3E, 0x8E, 0x5A)),
+        gradient_preset("Midnight", (0x14, 0x1E, 0x30), (0x24, 0x3B, 0x55)),
+        ThemePreset {
+            name: String::from("Graphite"),
+            theme: Theme::Solid { color: ThemeColor::RGB { r: 0x3A, g: 0x3D, b: 0x42 } },
+            builtin: true
+        }
+    ]
+}
+
+// custom presets in the directory sorted by name, unreadable files are skipped
+pub fn load_custom_presets<P: AsRef<Path>>(dir: P) -> Vec<ThemePreset> {
+    let Ok(entries) = read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut presets: Vec<ThemePreset> = entries.filter_map(|entry| {
+        let path = entry.ok()?.path();
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            ThemePreset::from_file(path).ok()
+        } else {
+            None
+        }
+    }).collect();
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    presets
+}
+
+pub fn preset_path_of<P: AsRef<Path>>(dir: P, preset_name: &str) -> Result<PathBuf, ThemeError> {
+    let invalid = preset_name.trim().is_empty() ||
+        preset_name.chars().any(|c| c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')) ||
+        preset_name.starts_with('.');
+    if invalid {
+        return Err(ThemeError { err_type: ThemeErrorType::InvalidName(preset_name.to_string()), preset_path: None });
+    }
+    Ok(dir.as_ref().join(format!("{}.json", preset_name)))
+}
+
+// save the preset into the directory, replacing the custom preset of the same name
+pub fn save_custom_preset<P: AsRef<Path>>(dir: P, preset: &ThemePreset) -> Result<(), ThemeError> {
+    if builtin_presets().iter().any(|p| p.name == preset.name) {
+        return Err(ThemeError { err_type: ThemeErrorType::BuiltinPreset(preset.name.clone()), preset_path: None });
+    }
+    let path = preset_path_of(&dir, &preset.name)?;
+    if let Err(e) = create_dir_all(&dir) {
+        return Err(ThemeError { err_type: ThemeErrorType::WriteError(e), preset_path: Some(dir.as_ref().to_string_lossy().to_string()) });
+    }
+    preset.to_file(path)
+}
+
+pub fn remove_custom_preset<P: AsRef<Path>>(dir: P, preset_name: &str) -> Result<(), ThemeError> {
+    let path = preset_path_of(dir, preset_name)?;
+    let path_str = path.to_string_lossy().to_string();
+    if !path.is_file() {
+        return Err(ThemeError { err_type: ThemeErrorType::FileNotExist, preset_path: Some(path_str) });
+    }
+    remove_file(path).map_err(|e| ThemeError { err_type: ThemeErrorType::WriteError(e), preset_path: Some(path_str) })
+}
+
+// import a preset file shared by others into the directory
+pub fn import_preset<P: AsRef<Path>, Q: AsRef<Path>>(dir: P, path: Q) -> Result<ThemePreset, ThemeError> {
+    let preset = ThemePreset::from_file(path)?;
+    save_custom_preset(dir, &preset)?;
+    Ok(preset)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeContrast {
+    pub min_ratio: f64,
+    pub average_ratio: f64,
+    pub readable: bool,
+    // some stop is below the minimum even though the gradient as a whole is readable
+    pub has_unreadable_stop: bool
+}
+
+// contrast of the text against the colors of the theme, None for image themes
+pub fn check_contrast(theme: &Theme, text_color: &ThemeColor) -> Option<ThemeContrast> {
+    let colors: Vec<&ThemeColor> = match theme {
+        Theme::Solid { color } => vec![color],
+        Theme::LinearGradient { stops, .. } | Theme::RadialGradient { stops } => stops.iter().map(|stop| &stop.color).collect(),
+        Theme::Image { .. } => return None
+    };
+    let ratios: Vec<f64> = colors.iter().map(|color| color.contrast_ratio(text_color)).collect();
+    let min_ratio = ratios.iter().cloned().fold(f64::INFINITY, f64::min);
+    let average_ratio = ratios.iter().sum::<f64>() / ratios.len() as f64;
+    // the header spans the whole gradient, so a single light stop does not make it unreadable
+    Some(ThemeContrast {
+        min_ratio,
+        average_ratio,
+        readable: average_ratio >= MIN_CONTRAST_RATIO,
+        has_unreadable_stop: min_ratio < MIN_CONTRAST_RATIO
+    })
+}
+
+#[derive(Debug)]
+pub struct ThemeError {
+    pub err_type: ThemeErrorType,
+    pub preset_path: Option<String>
+}
+
+impl Display for ThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let preset_path_str = self.preset_path.clone().unwrap_or(String::from("<unknown>"));
+        match &self.err_type {
+            ThemeErrorType::ParseError(e) => write!(f, "Failed to parse theme preset '{}': {}", preset_path_str, e),
+            ThemeErrorType::ReadError(e) => write!(f, "Failed to read file '{}': {}", preset_path_str, e),
+            ThemeErrorType::WriteError(e) => write!(f, "Failed to write file '{}': {}", preset_path_str, e),
+            ThemeErrorType::FileNotExist => write!(f, "Theme preset '{}' does not exist", preset_path_str),
+            ThemeErrorType::InvalidName(name) => write!(f, "'{}' is not a valid theme preset name", name),
+            ThemeErrorType::BuiltinPreset(name) => write!(f, "Theme preset '{}' is built in", name)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ThemeErrorType {
+    ParseError(SerdeError),
+    ReadError(IoError),
+    WriteError(IoError),
+    FileNotExist,
+    InvalidName(String),
+    BuiltinPreset(String)
+}
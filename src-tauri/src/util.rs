@@ -1,40 +1,160 @@
-use std::io;
+use std::fmt::Display;
+use std::io::{Cursor, Error as IoError};
 use std::path::Path;
 use std::fs::read as fs_read;
 use base64::prelude::*;
+use image::{DynamicImage, ImageError, ImageFormat, guess_format, load_from_memory_with_format};
+use image::imageops::FilterType;
+use resvg::usvg::Error as SvgError;
+#[cfg(not(windows))]
+use super::app_icon::{appimage_icon, desktop_entry_icon, interpreter_badge, script_interpreter};
+use super::config::structure::{AppKind, FallbackIconColor, IconFormat, IconSettings, SvgMode, Theme};
+use super::fallback_icon::{app_initials, badge_color, render_badge};
+use super::icons::{is_icns, is_ico, parse_icon_file, select_icon};
+use super::svg::{rasterize_svg, sanitize_svg};
 
 fn encode_image_url_from_bytes<T: AsRef<[u8]>>(image_data: T, mime_type: String) -> String {
     format!("data:{};base64,{}", mime_type, BASE64_STANDARD.encode(image_data.as_ref()))
 }
 
-pub fn encode_image_url_from_file<P: AsRef<Path>>(path: P) -> Result<String, io::Error> {
-    let path = path.as_ref();
-    let image_data = fs_read(path)?;
-    let mime_type;
-    if let Some(ext) = path.extension() {
-        let ext_str: &str = &ext.to_string_lossy().to_ascii_lowercase();
-        mime_type = match ext_str {
-            "bmp" => "image/bmp",
-            "gif" => "image/gif",
-            "jpg" | "jpeg" => "image/jpeg",
-            "png" => "image/png",
-            "svg" => "image/svg+xml",
-            "webp" => "image/webp",
-            "tiff" | "tif" => "image/tiff",
-            _ => "application/octet-stream"
+// svg is text, so look for its root element near the start instead of magic bytes
+fn is_svg(data: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&data[..data.len().min(4096)]);
+    let mut head = head.trim_start_matches('\u{feff}').trim_start();
+    // skip the xml declaration, comments and the doctype before the root element
+    loop {
+        let end = if head.starts_with("<?") {
+            head.find("?>").map(|pos| pos + 2)
+        } else if head.starts_with("<!--") {
+            head.find("-->").map(|pos| pos + 3)
+        } else if head.starts_with("<!DOCTYPE") {
+            head.find('>').map(|pos| pos + 1)
+        } else {
+            break;
+        };
+        let Some(end) = end else {
+            return false;
         };
+        head = head[end..].trim_start();
+    }
+    head.strip_prefix("<svg").is_some_and(|rest| rest.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/'))
+}
+
+// the mime type of the image by its content, None if it is not an image that can be loaded
+pub fn sniff_image_type(data: &[u8]) -> Option<&'static str> {
+    if is_svg(data) {
+        return Some("image/svg+xml");
     }
-    else {
-        mime_type = "application/octet-stream";
+    guess_format(data).ok()
+        .filter(ImageFormat::reading_enabled)
+        .map(|format| format.to_mime_type())
+}
+
+fn encode_sanitized_svg(image_data: &[u8], path_str: &str) -> Result<String, IconError> {
+    let svg = sanitize_svg(image_data).map_err(|e| IconError { err_type: IconErrorType::InvalidSvg(e), path: Some(path_str.to_string()) })?;
+    Ok(encode_image_url_from_bytes(svg, String::from("image/svg+xml")))
+}
+
+// embed the image as it is, for the images shown at their own size like theme backgrounds
+pub fn encode_image_url_from_file<P: AsRef<Path>>(path: P) -> Result<String, IconError> {
+    let path = path.as_ref();
+    let path_str = path.to_string_lossy().to_string();
+    let image_data = fs_read(path).map_err(|e| IconError { err_type: IconErrorType::ReadError(e), path: Some(path_str.clone()) })?;
+    let Some(mime_type) = sniff_image_type(&image_data) else {
+        return Err(IconError { err_type: IconErrorType::UnsupportedFormat, path: Some(path_str) });
+    };
+    if mime_type == "image/svg+xml" {
+        return encode_sanitized_svg(&image_data, &path_str);
     }
     Ok(encode_image_url_from_bytes(image_data, mime_type.to_string()))
 }
 
+// scale the icon down to the max size and encode it in the configured format, keeping its transparency
+pub fn normalize_icon(image: DynamicImage, settings: &IconSettings) -> Result<(Vec<u8>, &'static str), ImageError> {
+    let max_size = settings.max_size.max(1);
+    let mut image = image;
+    if image.width() > max_size || image.height() > max_size {
+        image = image.resize(max_size, max_size, FilterType::Lanczos3);
+    }
+    // 8 bits per channel is what both encoders accept
+    let image = if image.color().has_alpha() {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    };
+    let format = match settings.format {
+        IconFormat::Png => ImageFormat::Png,
+        IconFormat::WebP => ImageFormat::WebP
+    };
+    let mut encoded_image: Vec<u8> = Vec::new();
+    image.write_to(&mut Cursor::new(&mut encoded_image), format)?;
+    Ok((encoded_image, format.to_mime_type()))
+}
+
+// load an icon from an image file, normalizing it unless it is svg and svg is set to be kept
+pub fn encode_icon_url_from_file<P: AsRef<Path>>(path: P, settings: &IconSettings) -> Result<String, IconError> {
+    let path = path.as_ref();
+    let path_str = path.to_string_lossy().to_string();
+    let image_data = fs_read(path).map_err(|e| IconError { err_type: IconErrorType::ReadError(e), path: Some(path_str.clone()) })?;
+    encode_icon_url_from_bytes(&image_data, &path_str, settings)
+}
+
+fn encode_icon_url_from_bytes(image_data: &[u8], path_str: &str, settings: &IconSettings) -> Result<String, IconError> {
+    let path_str = path_str.to_string();
+    let image = if is_svg(image_data) {
+        if settings.svg_mode == SvgMode::Sanitize {
+            return encode_sanitized_svg(image_data, &path_str);
+        }
+        let image = rasterize_svg(image_data, settings.max_size)
+            .map_err(|e| IconError { err_type: IconErrorType::InvalidSvg(e), path: Some(path_str.clone()) })?;
+        DynamicImage::ImageRgba8(image)
+    } else if is_ico(image_data) || is_icns(image_data) {
+        // ico, cur and icns files hold the same icon in several sizes, only the one fitting best is kept
+        let entries = parse_icon_file(image_data);
+        let Some(entry) = select_icon(&entries, settings.max_size) else {
+            return Err(IconError { err_type: IconErrorType::NoIconImage, path: Some(path_str) });
+        };
+        entry.decode().map_err(|e| IconError { err_type: IconErrorType::DecodeError(e), path: Some(path_str.clone()) })?
+    } else {
+        load_image(image_data, &path_str)?
+    };
+    let (icon_data, mime_type) = normalize_icon(image, settings)
+        .map_err(|e| IconError { err_type: IconErrorType::EncodeError(e), path: Some(path_str) })?;
+    Ok(encode_image_url_from_bytes(icon_data, mime_type.to_string()))
+}
+
+fn load_image(image_data: &[u8], path_str: &str) -> Result<DynamicImage, IconError> {
+    let Some(format) = guess_format(image_data).ok().filter(ImageFormat::reading_enabled) else {
+        return Err(IconError { err_type: IconErrorType::UnsupportedFormat, path: Some(path_str.to_string()) });
+    };
+    load_from_memory_with_format(image_data, format)
+        .map_err(|e| IconError { err_type: IconErrorType::DecodeError(e), path: Some(path_str.to_string()) })
+}
+
+// icon of the entries without their own, by the kind of the entry
+pub fn default_icon_url(kind: &AppKind) -> String {
+    let svg = match kind {
+        AppKind::Executable => include_str!("../icons/default/executable.svg"),
+        AppKind::Document => include_str!("../icons/default/document.svg"),
+        AppKind::Folder => include_str!("../icons/default/folder.svg"),
+        AppKind::Url => include_str!("../icons/default/url.svg")
+    };
+    encode_image_url_from_bytes(svg, String::from("image/svg+xml"))
+}
+
+// icon with the initials of the app, for the apps without one
+pub fn fallback_icon_url(app_name: &str, theme: &Theme, settings: &IconSettings) -> Result<String, IconError> {
+    let theme = (settings.fallback_color == FallbackIconColor::Theme).then_some(theme);
+    let badge = render_badge(&app_initials(app_name), &badge_color(app_name, theme), settings.max_size);
+    let (icon_data, mime_type) = normalize_icon(DynamicImage::ImageRgba8(badge), settings)
+        .map_err(|e| IconError { err_type: IconErrorType::EncodeError(e), path: None })?;
+    Ok(encode_image_url_from_bytes(icon_data, mime_type.to_string()))
+}
+
 #[cfg(windows)]
-pub fn encode_image_url_from_app_icon<P: AsRef<Path>>(path: P) -> Option<String> {
+pub fn encode_image_url_from_app_icon<P: AsRef<Path>>(path: P, settings: &IconSettings) -> Option<String> {
     use std::mem::{MaybeUninit, size_of, swap};
-    use std::io::Cursor;
-    use image::{RgbaImage, ImageFormat};
+    use image::RgbaImage;
     use windows::core::HSTRING;
     use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
     use windows::Win32::UI::Shell::{SHGetFileInfoW, SHGetImageList, SHFILEINFOW, SHGFI_SYSICONINDEX, SHIL_EXTRALARGE};
@@ -117,9 +237,55 @@ pub fn encode_image_url_from_app_icon<P: AsRef<Path>>(path: P) -> Option<String>
         swap(b, r);
     }
     let img = RgbaImage::from_vec(bitmap.bmWidth as u32, bitmap.bmHeight as u32, bitmap_buf).expect("Invalid bitmap data");
-    let mut encoded_img_buf: Vec<u8> = Vec::new();
-    encoded_img_buf.reserve(bitmap_width * bitmap_height * 2); // reserve space for avoiding reallocations
+    let (icon_data, mime_type) = normalize_icon(DynamicImage::ImageRgba8(img), settings).expect("Failed to encode image");
+    Some(encode_image_url_from_bytes(icon_data, mime_type.to_string()))
+}
 
-    img.write_to(&mut Cursor::new(&mut encoded_img_buf), ImageFormat::Png).expect("Failed to encode image");    
-    Some(encode_image_url_from_bytes(encoded_img_buf, "image/png".to_string()))
-}
\ No newline at end of file
+// icon of an AppImage, of the desktop file next to the app, or a badge of the interpreter of a script
+#[cfg(not(windows))]
+pub fn encode_image_url_from_app_icon<P: AsRef<Path>>(path: P, settings: &IconSettings) -> Option<String> {
+    let path = path.as_ref();
+    if let Some((icon_data, icon_path)) = appimage_icon(path) {
+        let path_str = format!("{}/{}", path.to_string_lossy(), icon_path);
+        if let Ok(data_url) = encode_icon_url_from_bytes(&icon_data, &path_str, settings) {
+            return Some(data_url);
+        }
+    }
+    if let Some(icon_path) = desktop_entry_icon(path) && let Ok(data_url) = encode_icon_url_from_file(icon_path, settings) {
+        return Some(data_url);
+    }
+    let (label, color) = interpreter_badge(&script_interpreter(path)?);
+    let badge = render_badge(&label, &color, settings.max_size);
+    let (icon_data, mime_type) = normalize_icon(DynamicImage::ImageRgba8(badge), settings).ok()?;
+    Some(encode_image_url_from_bytes(icon_data, mime_type.to_string()))
+}
+
+#[derive(Debug)]
+pub struct IconError {
+    pub err_type: IconErrorType,
+    pub path: Option<String>
+}
+
+impl Display for IconError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path_str = self.path.clone().unwrap_or(String::from("<unknown>"));
+        match &self.err_type {
+            IconErrorType::ReadError(e) => write!(f, "Failed to read image file '{}': {}", path_str, e),
+            IconErrorType::UnsupportedFormat => write!(f, "File '{}' is not a supported image", path_str),
+            IconErrorType::NoIconImage => write!(f, "Icon file '{}' has no image that can be loaded", path_str),
+            IconErrorType::DecodeError(e) => write!(f, "Failed to decode image file '{}': {}", path_str, e),
+            IconErrorType::InvalidSvg(e) => write!(f, "Failed to load svg file '{}': {}", path_str, e),
+            IconErrorType::EncodeError(e) => write!(f, "Failed to encode icon from '{}': {}", path_str, e)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum IconErrorType {
+    ReadError(IoError),
+    UnsupportedFormat,
+    NoIconImage,
+    DecodeError(ImageError),
+    InvalidSvg(SvgError),
+    EncodeError(ImageError)
+}
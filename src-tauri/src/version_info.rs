@@ -0,0 +1,253 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufReader, Error as IoError, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const DOS_MAGIC: &[u8; 2] = b"MZ";
+const PE_MAGIC: &[u8; 4] = b"PE\0\0";
+const PE32_MAGIC: u16 = 0x10B;
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+const RESOURCE_DIRECTORY_INDEX: usize = 2;
+const SECTION_HEADER_SIZE: usize = 40;
+const RT_VERSION: u32 = 16;
+// set in a resource directory entry when it points to a subdirectory, or is named instead of having an id
+const RESOURCE_SUBDIRECTORY: u32 = 0x8000_0000;
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+const MAX_VERSION_RESOURCE_SIZE: u32 = 1024 * 1024;
+const LANG_ENGLISH_US: u16 = 0x0409;
+
+// what the version resource of an executable tells about it
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VersionInfo {
+    pub file_version: Option<String>,
+    pub product_version: Option<String>,
+    // the string tables by their language and code page, e.g. "040904B0"
+    pub string_tables: Vec<(String, Vec<(String, String)>)>
+}
+
+impl VersionInfo {
+    // a non-empty value in the table of the language, else in the english one, else in the first table that has it
+    pub fn string(&self, key: &str, lang_id: Option<u16>) -> Option<&str> {
+        let table_lang = |table_key: &str| u16::from_str_radix(table_key.get(0..4)?, 16).ok();
+        let mut tables: Vec<&(String, Vec<(String, String)>)> = self.string_tables.iter().collect();
+        tables.sort_by_key(|(table_key, _)| match table_lang(table_key) {
+            lang if lang.is_some() && lang == lang_id => 0,
+            Some(LANG_ENGLISH_US) => 1,
+            _ => 2
+        });
+        tables.iter().flat_map(|(_, strings)| strings.iter())
+            .find(|(string_key, value)| string_key == key && !value.is_empty())
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// the windows language id of a locale of the toolbox
+pub fn lang_id_of(locale: &str) -> Option<u16> {
+    match locale {
+        "zh-CN" => Some(0x0804),
+        "zh-TW" => Some(0x0404),
+        locale if locale.starts_with("en") => Some(LANG_ENGLISH_US),
+        _ => None
+    }
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn align4(pos: usize) -> usize {
+    pos.div_ceil(4) * 4
+}
+
+fn read_at<R: Read + Seek>(reader: &mut R, pos: u64, len: usize) -> Result<Vec<u8>, IoError> {
+    let mut buf = vec![0u8; len];
+    reader.seek(SeekFrom::Start(pos))?;
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+// a null terminated utf-16 string, with the position right after it
+fn read_utf16z(data: &[u8], pos: usize, end: usize) -> Option<(String, usize)> {
+    let units: Vec<u16> = data.get(pos..end)?.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]])).collect();
+    let len = units.iter().position(|unit| *unit == 0).unwrap_or(units.len());
+    Some((String::from_utf16_lossy(&units[..len]), pos + (len + 1) * 2))
+}
+
+// one of the nested blocks of a version resource, with its key, its value and where its children are
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    children: (usize, usize)
+}
+
+fn read_block(data: &[u8], pos: usize) -> Option<(VersionBlock<'_>, usize)> {
+    let len = read_u16(data, pos)? as usize;
+    let value_len = read_u16(data, pos + 2)? as usize;
+    let is_text = read_u16(data, pos + 4)? == 1;
+    let end = (pos + len).min(data.len());
+    if len < 6 {
+        return None;
+    }
+    let (key, key_end) = read_utf16z(data, pos + 6, end)?;
+    let value_start = align4(key_end).min(end);
+    // the length of text values is counted in characters, which some compilers get wrong,
+    // so a text value goes to the end of its block, which then has no children
+    let value_end = match (value_len, is_text) {
+        (0, _) => value_start,
+        (_, true) => end,
+        (_, false) => (value_start + value_len).min(end)
+    };
+    let value = &data[value_start..value_end];
+    let children = (align4(value_end).min(end), end);
+    Some((VersionBlock { key, value, children }, align4(end)))
+}
+
+fn read_children(data: &[u8], (start, end): (usize, usize)) -> Vec<VersionBlock<'_>> {
+    let mut children: Vec<VersionBlock> = Vec::new();
+    let mut pos = start;
+    while pos < end && let Some((block, next)) = read_block(&data[..end], pos) {
+        children.push(block);
+        pos = next;
+    }
+    children
+}
+
+fn format_version(most_significant: u32, least_significant: u32) -> String {
+    format!("{}.{}.{}.{}", most_significant >> 16, most_significant & 0xFFFF, least_significant >> 16, least_significant & 0xFFFF)
+}
+
+// the VS_VERSIONINFO structure of a version resource
+pub fn parse_version_resource(data: &[u8]) -> Option<VersionInfo> {
+    let (root, _) = read_block(data, 0)?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+    let mut version_info = VersionInfo::default();
+    if read_u32(root.value, 0) == Some(FIXED_FILE_INFO_SIGNATURE) {
+        let fixed = root.value;
+        version_info.file_version = Some(format_version(read_u32(fixed, 8)?, read_u32(fixed, 12)?));
+        version_info.product_version = Some(format_version(read_u32(fixed, 16)?, read_u32(fixed, 20)?));
+    }
+    for string_file_info in read_children(data, root.children).into_iter().filter(|block| block.key == "StringFileInfo") {
+        for table in read_children(data, string_file_info.children) {
+            let strings = read_children(data, table.children).into_iter()
+                .filter_map(|string| {
+                    let (value, _) = read_utf16z(string.value, 0, string.value.len() & !1)?;
+                    Some((string.key, value.trim().to_string()))
+                })
+                .collect();
+            version_info.string_tables.push((table.key.to_uppercase(), strings));
+        }
+    }
+    Some(version_info)
+}
+
+// the version resource of a PE file, found through its resource directory
+fn read_version_resource<R: Read + Seek>(reader: &mut R) -> Result<Vec<u8>, VersionInfoErrorType> {
+    let not_pe = || VersionInfoErrorType::NotAPeFile;
+    let dos_header = read_at(reader, 0, 64).map_err(|_| not_pe())?;
+    if !dos_header.starts_with(DOS_MAGIC) {
+        return Err(not_pe());
+    }
+    let pe_offset = read_u32(&dos_header, 0x3C).unwrap() as u64;
+    let headers = read_at(reader, pe_offset, 24).map_err(|_| not_pe())?;
+    if !headers.starts_with(PE_MAGIC) {
+        return Err(not_pe());
+    }
+    let section_count = read_u16(&headers, 6).unwrap() as usize;
+    let optional_header_size = read_u16(&headers, 20).unwrap() as usize;
+    let optional_header = &read_at(reader, pe_offset + 24, optional_header_size).map_err(|_| not_pe())?;
+    let (directory_count_pos, directories_pos) = match read_u16(optional_header, 0).ok_or_else(not_pe)? {
+        PE32_MAGIC => (92, 96),
+        PE32_PLUS_MAGIC => (108, 112),
+        _ => return Err(not_pe())
+    };
+    let no_version_info = || VersionInfoErrorType::NoVersionInfo;
+    if read_u32(optional_header, directory_count_pos).ok_or_else(not_pe)? as usize <= RESOURCE_DIRECTORY_INDEX {
+        return Err(no_version_info());
+    }
+    let resource_rva = read_u32(optional_header, directories_pos + RESOURCE_DIRECTORY_INDEX * 8).ok_or_else(not_pe)?;
+    if resource_rva == 0 {
+        return Err(no_version_info());
+    }
+
+    // file offsets of the sections, to find the resources by their virtual addresses
+    let sections_pos = pe_offset + 24 + optional_header_size as u64;
+    let section_table = read_at(reader, sections_pos, section_count * SECTION_HEADER_SIZE).map_err(|_| not_pe())?;
+    let sections: Vec<(u32, u32, u32)> = section_table.chunks_exact(SECTION_HEADER_SIZE).map(|section| {
+        let virtual_size = read_u32(section, 8).unwrap().max(read_u32(section, 16).unwrap());
+        (read_u32(section, 12).unwrap(), virtual_size, read_u32(section, 20).unwrap())
+    }).collect();
+    let offset_of = |rva: u32, not_found: VersionInfoErrorType| -> Result<u64, VersionInfoErrorType> {
+        let (address, _, raw_pos) = sections.iter()
+            .find(|(address, size, _)| rva >= *address && rva - address < *size)
+            .ok_or(not_found)?;
+        (rva - address).checked_add(*raw_pos).map(u64::from).ok_or(VersionInfoErrorType::Corrupted)
+    };
+    let resource_pos = offset_of(resource_rva, no_version_info())?;
+
+    // the version resource is under its type, then its id, then its language, the first of each is taken
+    let invalid = |_| VersionInfoErrorType::InvalidVersionInfo;
+    let mut entry_offset = 0u32;
+    for level in 0..3 {
+        let directory = read_at(reader, resource_pos + entry_offset as u64, 16).map_err(invalid)?;
+        let entry_count = read_u16(&directory, 12).unwrap() as usize + read_u16(&directory, 14).unwrap() as usize;
+        let entries = read_at(reader, resource_pos + entry_offset as u64 + 16, entry_count * 8).map_err(invalid)?;
+        let entry = entries.chunks_exact(8)
+            .map(|entry| (read_u32(entry, 0).unwrap(), read_u32(entry, 4).unwrap()))
+            .find(|(name, _)| level > 0 || *name == RT_VERSION)
+            .ok_or_else(no_version_info)?;
+        let is_directory = entry.1 & RESOURCE_SUBDIRECTORY != 0;
+        if is_directory != (level < 2) {
+            return Err(VersionInfoErrorType::InvalidVersionInfo);
+        }
+        entry_offset = entry.1 & !RESOURCE_SUBDIRECTORY;
+    }
+    let data_entry = read_at(reader, resource_pos + entry_offset as u64, 8).map_err(invalid)?;
+    let (data_rva, data_size) = (read_u32(&data_entry, 0).unwrap(), read_u32(&data_entry, 4).unwrap());
+    if data_size > MAX_VERSION_RESOURCE_SIZE {
+        return Err(VersionInfoErrorType::InvalidVersionInfo);
+    }
+    let data_pos = offset_of(data_rva, VersionInfoErrorType::InvalidVersionInfo)?;
+    read_at(reader, data_pos, data_size as usize).map_err(invalid)
+}
+
+pub fn read_version_info<P: AsRef<Path>>(path: P) -> Result<VersionInfo, VersionInfoError> {
+    let path_str = path.as_ref().to_string_lossy().to_string();
+    let file = File::open(path).map_err(|e| VersionInfoError { err_type: VersionInfoErrorType::ReadError(e), path: Some(path_str.clone()) })?;
+    let data = read_version_resource(&mut BufReader::new(file))
+        .map_err(|err_type| VersionInfoError { err_type, path: Some(path_str.clone()) })?;
+    parse_version_resource(&data).ok_or(VersionInfoError { err_type: VersionInfoErrorType::InvalidVersionInfo, path: Some(path_str) })
+}
+
+#[derive(Debug)]
+pub struct VersionInfoError {
+    pub err_type: VersionInfoErrorType,
+    pub path: Option<String>
+}
+
+impl Display for VersionInfoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path_str = self.path.clone().unwrap_or(String::from("<unknown>"));
+        match &self.err_type {
+            VersionInfoErrorType::ReadError(e) => write!(f, "Failed to read file '{}': {}", path_str, e),
+            VersionInfoErrorType::NotAPeFile => write!(f, "File '{}' is not a windows executable", path_str),
+            VersionInfoErrorType::NoVersionInfo => write!(f, "Executable '{}' has no version information", path_str),
+            VersionInfoErrorType::InvalidVersionInfo => write!(f, "Version information of '{}' is invalid", path_str),
+            VersionInfoErrorType::Corrupted => write!(f, "Executable '{}' is corrupted", path_str)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum VersionInfoErrorType {
+    ReadError(IoError),
+    NotAPeFile,
+    NoVersionInfo,
+    InvalidVersionInfo,
+    Corrupted
+}
@@ -0,0 +1,186 @@
+use std::fs::metadata as fs_metadata;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf, absolute};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+use serde::{Deserialize, Serialize};
+use super::config::Config;
+use super::config::structure::{AppKind, AppMetadata, AppSnapshot, VersionSource};
+use super::hooks::run_with_timeout;
+use super::integrity::FileDigest;
+use super::launcher::check_app;
+use super::version_info::read_version_info;
+
+// apps that do not know --version may start their window instead, which is closed after this
+pub const VERSION_PROBE_TIMEOUT_MS: u64 = 3000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum VersionChangeType {
+    // scanned for the first time
+    New,
+    // the file or its version changed since the last scan
+    Updated,
+    // the file is gone, its last snapshot is kept
+    Missing
+}
+
+// corresponding to the AppVersionChange interface in types.ts
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppVersionChange {
+    pub app_name: String,
+    pub change_type: VersionChangeType,
+    pub old_version: Option<String>,
+    pub new_version: Option<String>
+}
+
+// the first word of the output that looks like a version, e.g. "1.2.3" in "tool v1.2.3 (build 45)"
+pub fn parse_version_output(output: &str) -> Option<String> {
+    output.split(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')' | '[' | ']' | '"' | '\''))
+        .map(|word| word.trim_start_matches(['v', 'V']).trim_end_matches(['.', ':', ';']))
+        .find(|word| word.split_once('.').is_some_and(|(major, rest)| {
+            !major.is_empty() && major.chars().all(|c| c.is_ascii_digit()) && rest.starts_with(|c: char| c.is_ascii_digit())
+        }))
+        .map(String::from)
+}
+
+// run the app with --version and read the version from its output
+pub fn probe_version(metadata: &AppMetadata) -> Option<String> {
+    let mut command = Command::new(absolute(&metadata.app_path).ok()?);
+    command.arg("--version").current_dir(&metadata.working_dir);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let result = run_with_timeout(&mut command, VERSION_PROBE_TIMEOUT_MS).ok()?;
+    if result.timed_out {
+        return None;
+    }
+    parse_version_output(&result.output)
+}
+
+// the version of the app from its version resource, or from probing it if allowed
+pub fn detect_version(metadata: &AppMetadata, allow_probe: bool) -> Option<(String, VersionSource)> {
+    if metadata.kind != AppKind::Executable {
+        return None;
+    }
+    if let Ok(version_info) = read_version_info(&metadata.app_path) {
+        let version = version_info.string("ProductVersion", None).map(String::from).or(version_info.product_version);
+        if let Some(version) = version {
+            return Some((version, VersionSource::VersionInfo));
+        }
+    }
+    if allow_probe {
+        return probe_version(metadata).map(|version| (version, VersionSource::Probe));
+    }
+    None
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = fs_metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|duration| duration.as_secs())
+}
+
+// the state of the file, hashed again only if its size or modification time differ from the previous snapshot
+pub fn take_snapshot(path: &Path, previous: Option<&AppSnapshot>, version: String) -> Result<AppSnapshot, IoError> {
+    let size = fs_metadata(path)?.len();
+    let modified = modified_secs(path);
+    if let Some(previous) = previous && previous.size == size && modified.is_some() && previous.modified == modified {
+        return Ok(AppSnapshot { sha256: previous.sha256.clone(), size, modified, version });
+    }
+    let digest = FileDigest::from_file(path)?;
+    Ok(AppSnapshot { size: digest.size, sha256: digest.sha256, modified, version })
+}
+
+// the version of the app as of now, detected again unless it was typed in
+fn current_version(metadata: &AppMetadata, allow_probe: bool) -> (String, VersionSource) {
+    let detected = match metadata.version_source {
+        VersionSource::Manual if !metadata.version.is_empty() => None,
+        VersionSource::Manual => detect_version(metadata, allow_probe),
+        VersionSource::VersionInfo => detect_version(metadata, false),
+        VersionSource::Probe => if allow_probe { probe_version(metadata).map(|version| (version, VersionSource::Probe)) } else { None }
+    };
+    detected.unwrap_or((metadata.version.clone(), metadata.version_source.clone()))
+}
+
+// the new snapshot of an app file, applied to the config once the scan is done
+#[derive(Clone, Debug)]
+pub struct VersionScan {
+    pub app_name: String,
+    // the scanned file and the version fields the scan started from,
+    // the scan is dropped if any of them was edited by the time it is applied
+    pub app_path: PathBuf,
+    pub old_version: String,
+    pub old_version_source: VersionSource,
+    pub version: String,
+    pub version_source: VersionSource,
+    pub snapshot: AppSnapshot
+}
+
+// snapshot every app file without changing the config, so that the slow part can run on a copy of it,
+// reporting the apps that are new, updated or missing since the last snapshot
+pub fn scan_app_files(config: &Config, allow_probe: bool) -> (Vec<AppVersionChange>, Vec<VersionScan>) {
+    let mut app_names: Vec<String> = config.get_all_app_name_list().into_iter().cloned().collect();
+    app_names.sort();
+    let mut changes: Vec<AppVersionChange> = Vec::new();
+    let mut scans: Vec<VersionScan> = Vec::new();
+    for app_name in app_names {
+        let metadata = config.get_app(&app_name).unwrap().clone();
+        if !metadata.kind.is_file() {
+            continue;
+        }
+        let old_version = metadata.snapshot.as_ref().map(|snapshot| snapshot.version.clone());
+        if !metadata.app_path.is_file() {
+            if metadata.snapshot.is_some() {
+                changes.push(AppVersionChange { app_name, change_type: VersionChangeType::Missing, old_version, new_version: None });
+            }
+            continue;
+        }
+        // only apps that would be allowed to launch are run with --version
+        let (version, version_source) = current_version(&metadata, allow_probe && check_app(config, &app_name, false).is_ok());
+        let Ok(snapshot) = take_snapshot(&metadata.app_path, metadata.snapshot.as_ref(), version.clone()) else {
+            continue;
+        };
+        let change_type = match &metadata.snapshot {
+            None => Some(VersionChangeType::New),
+            Some(previous) if previous.sha256 != snapshot.sha256 || previous.version != snapshot.version => Some(VersionChangeType::Updated),
+            Some(_) => None
+        };
+        if let Some(change_type) = change_type {
+            changes.push(AppVersionChange { app_name: app_name.clone(), change_type, old_version, new_version: Some(version.clone()) });
+        }
+        scans.push(VersionScan {
+            app_name,
+            app_path: metadata.app_path,
+            old_version: metadata.version,
+            old_version_source: metadata.version_source,
+            version,
+            version_source,
+            snapshot
+        });
+    }
+    (changes, scans)
+}
+
+// store the snapshots in the config, keeping the other changes made to the apps since the scan started
+pub fn apply_version_scans(config: &mut Config, scans: Vec<VersionScan>) {
+    for scan in scans {
+        let Some(metadata) = config.get_app(&scan.app_name) else {
+            continue;
+        };
+        if metadata.app_path != scan.app_path || metadata.version != scan.old_version || metadata.version_source != scan.old_version_source {
+            continue;
+        }
+        let metadata = AppMetadata { version: scan.version, version_source: scan.version_source, snapshot: Some(scan.snapshot), ..metadata.clone() };
+        let _ = config.update_app(&scan.app_name, metadata);
+    }
+}
+
+// take a new snapshot of every app file, reporting the apps that are new, updated or missing since the last one
+pub fn scan_versions(config: &mut Config, allow_probe: bool) -> Vec<AppVersionChange> {
+    let (changes, scans) = scan_app_files(config, allow_probe);
+    apply_version_scans(config, scans);
+    changes
+}
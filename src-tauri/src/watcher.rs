@@ -0,0 +1,50 @@
+use std::path::{Path, absolute};
+use std::sync::Mutex;
+use std::time::Duration;
+use notify_debouncer_mini::{DebounceEventResult, Debouncer, new_debouncer};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode, Result as NotifyResult};
+use tauri::{AppHandle, Emitter, Manager};
+use super::config::{Config, history::EditHistory, sync::ConfigSync};
+
+pub const CONFIG_RELOADED_EVENT: &str = "config-reloaded";
+pub const CONFIG_RELOAD_FAILED_EVENT: &str = "config-reload-failed";
+pub const CONFIG_CONFLICT_EVENT: &str = "config-conflict";
+
+pub fn watch_config(app_handle: AppHandle, config_path: &'static str) -> NotifyResult<Debouncer<RecommendedWatcher>> {
+    let mut debouncer = new_debouncer(Duration::from_millis(300), move |result: DebounceEventResult| {
+        if let Ok(events) = result &&
+            events.iter().any(|event| event.path.file_name() == Path::new(config_path).file_name()) {
+            reload_config(&app_handle, config_path);
+        }
+    })?;
+    // watch the directory instead of the file, as editors often replace the file when saving
+    let config_dir = absolute(config_path)?.parent().expect("Config path should have a parent").to_path_buf();
+    debouncer.watcher().watch(&config_dir, RecursiveMode::NonRecursive)?;
+    Ok(debouncer)
+}
+
+fn reload_config(app_handle: &AppHandle, config_path: &str) {
+    let config_state = app_handle.state::<Mutex<Config>>();
+    let sync_state = app_handle.state::<Mutex<ConfigSync>>();
+    let mut config = config_state.lock().unwrap();
+    let mut sync = sync_state.lock().unwrap();
+    // ignore the events caused by our own saves
+    if !sync.is_modified_externally(config_path) {
+        return;
+    }
+    // both versions have been changed, let the user decide which one to keep
+    if sync.has_conflict() {
+        let _ = app_handle.emit(CONFIG_CONFLICT_EVENT, ());
+        return;
+    }
+    match sync.load(config_path) {
+        Ok(new_config) => {
+            // the external change can be undone like any other edit
+            app_handle.state::<Mutex<EditHistory>>().lock().unwrap().record(std::mem::replace(&mut *config, new_config));
+            let _ = app_handle.emit(CONFIG_RELOADED_EVENT, ());
+        },
+        Err(e) => {
+            let _ = app_handle.emit(CONFIG_RELOAD_FAILED_EVENT, e.to_string());
+        }
+    }
+}
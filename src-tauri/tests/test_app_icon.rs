@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+use rapid_toolbox_lib::app_icon::*;
+
+#[test]
+fn test_appimage_icon() {
+    let (icon_data, icon_path) = appimage_icon("tests/app_icon/tool.AppImage").expect("Failed to load icon");
+    assert_eq!(icon_path, ".DirIcon");
+    assert!(icon_data.starts_with(b"\x89PNG"));
+    // the icon named by the desktop file, when there is no .DirIcon
+    let (_, icon_path) = appimage_icon("tests/app_icon/desktop_icon.AppImage").expect("Failed to load icon");
+    assert_eq!(icon_path, "usr/share/icons/hicolor/48x48/apps/tool.png");
+    assert!(appimage_icon("tests/app_icon/launch.sh").is_none());
+    assert!(appimage_icon("tests/app_icon/missing.AppImage").is_none());
+}
+
+#[test]
+fn test_desktop_entry_icon() {
+    let desktop_entry = "# comment\n[Desktop Entry]\nName=Tool\nName[de]=Werkzeug\nIcon = tool\n[Desktop Action New]\nExec=tool --new\n";
+    assert_eq!(desktop_entry_value(desktop_entry, "Name").as_deref(), Some("Tool"));
+    assert_eq!(desktop_entry_value(desktop_entry, "Icon").as_deref(), Some("tool"));
+    assert_eq!(desktop_entry_value(desktop_entry, "Exec"), None);
+
+    // launcher.desktop runs launch.sh
+    assert_eq!(desktop_entry_icon("tests/app_icon/launch.sh"), Some(PathBuf::from("tests/app_icon/launch-icon.png")));
+    assert_eq!(desktop_entry_icon("tests/app_icon/convert.py"), None);
+}
+
+#[test]
+fn test_script_interpreter() {
+    assert_eq!(shebang_interpreter("#!/usr/bin/python3.12\n").as_deref(), Some("python"));
+    assert_eq!(shebang_interpreter("#! /bin/bash -e").as_deref(), Some("bash"));
+    assert_eq!(shebang_interpreter("#!/usr/bin/env -S NODE_ENV=production node --harmony").as_deref(), Some("node"));
+    assert_eq!(shebang_interpreter("#!/usr/bin/env").as_deref(), None);
+    assert_eq!(shebang_interpreter("echo \"#!/bin/sh\"").as_deref(), None);
+
+    assert_eq!(script_interpreter("tests/app_icon/convert.py").as_deref(), Some("python"));
+    assert_eq!(script_interpreter("tests/app_icon/launch.sh").as_deref(), Some("bash"));
+    // no shebang, by the extension
+    assert_eq!(script_interpreter("tests/app_icon/build.js").as_deref(), Some("node"));
+    assert_eq!(script_interpreter("tests/app_icon/launch-icon.png"), None);
+
+    assert_eq!(interpreter_badge("python").0, "PY");
+    assert_eq!(interpreter_badge("zsh").0, "SH");
+    assert_eq!(interpreter_badge("tclsh").0, "TC");
+    assert_eq!(interpreter_badge("+").0, "#");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_encode_image_url_from_app_icon() {
+    use rapid_toolbox_lib::config::structure::IconSettings;
+    use rapid_toolbox_lib::util::encode_image_url_from_app_icon;
+    let settings = IconSettings::default();
+    for path in ["tests/app_icon/tool.AppImage", "tests/app_icon/desktop_icon.AppImage", "tests/app_icon/launch.sh", "tests/app_icon/convert.py"] {
+        let data_url = encode_image_url_from_app_icon(path, &settings);
+        assert!(data_url.is_some_and(|url| url.starts_with("data:image/png;base64,")), "No icon for {}", path);
+    }
+    assert!(encode_image_url_from_app_icon("tests/app_icon/launch-icon.png", &settings).is_none());
+}
@@ -1,8 +1,8 @@
-use std::fs::remove_file;
+use std::fs::{create_dir_all, remove_dir_all, remove_file};
 use std::path::PathBuf;
-use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::{Config, ConfigFormat, FAVORITES_CATEGORY, find_config_file};
 use rapid_toolbox_lib::config::structure::*;
-use rapid_toolbox_lib::config::error::ConfigErrorType;
+use rapid_toolbox_lib::config::error::{ConfigErrorType, FormatError};
 
 struct Common;
 impl Common {
@@ -13,7 +13,9 @@ impl Common {
             working_dir: PathBuf::from("."),
             desc: "An app for testing purpose".to_string(),
             icon_url: "data:image/jpeg;base64,/9j/4AAQSkZJRgABAQEAYABgAAD/4QFwRXhpZgAATU0AKgAAAAgABQEaAAUAAAABAAAASgEbAAUAAAABAAAAUgEoAAMAAAABAAIAAAEyAAIAAAAUAAAAWodpAAQAAAABAAAAbgAAAAAAAABgAAAAAQAAAGAAAAABMjAyMjowMjoxNSAxODoxNToxMQAAD5AAAAcAAAAEMDIyMZADAAIAAAAUAAABKJAEAAIAAAAUAAABPJAQAAIAAAAHAAABUJARAAIAAAAHAAABWJASAAIAAAAHAAABYJEBAAcAAAAEAQIDAJKQAAIAAAAEMDQ0AJKRAAIAAAAEMDQ0AJKSAAIAAAAEMDQ0AKAAAAcAAAAEMDEwMKABAAMAAAABAAEAAKACAAQAAAABAAABwqADAAQAAAABAAABwqQGAAMAAAABAAAAAAAAAAAyMDIyOjAyOjE1IDE4OjE1OjExADIwMjI6MDI6MTUgMTg6MTU6MTEAKzA4OjAwAAArMDg6MDAAACswODowMAAA/+0AeFBob3Rvc2hvcCAzLjAAOEJJTQQEAAAAAAA/HAFaAAMbJUccAgAAAgACHAI/AAYxODE1MTEcAj4ACDIwMjIwMjE1HAI3AAgyMDIyMDIxNRwCPAAGMTgxNTExADhCSU0EJQAAAAAAEK9IIs785J05nfNZCwBq9pz/2wBDAAEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQH/2wBDAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQH/wAARCABAAEADASIAAhEBAxEB/8QAHwAAAQUBAQEBAQEAAAAAAAAAAAECAwQFBgcICQoL/8QAtRAAAgEDAwIEAwUFBAQAAAF9AQIDAAQRBRIhMUEGE1FhByJxFDKBkaEII0KxwRVS0fAkM2JyggkKFhcYGRolJicoKSo0NTY3ODk6Q0RFRkdISUpTVFVWV1hZWmNkZWZnaGlqc3R1dnd4eXqDhIWGh4iJipKTlJWWl5iZmqKjpKWmp6ipqrKztLW2t7i5usLDxMXGx8jJytLT1NXW19jZ2uHi4+Tl5ufo6erx8vP09fb3+Pn6/8QAHwEAAwEBAQEBAQEBAQAAAAAAAAECAwQFBgcICQoL/8QAtREAAgECBAQDBAcFBAQAAQJ3AAECAxEEBSExBhJBUQdhcRMiMoEIFEKRobHBCSMzUvAVYnLRChYkNOEl8RcYGRomJygpKjU2Nzg5OkNERUZHSElKU1RVVldYWVpjZGVmZ2hpanN0dXZ3eHl6goOEhYaHiImKkpOUlZaXmJmaoqOkpaanqKmqsrO0tba3uLm6wsPExcbHyMnK0tPU1dbX2Nna4uPk5ebn6Onq8vP09fb3+Pn6/9oADAMBAAIRAxEAPwD++iiiopJAgPP+f8/5zQA8sB7/AEpnmrnAI/MZ/rX4v/tff8Fh/AXwc+LOsfsr/ssfCLxv+25+2HpcNuNX+E3wqubXTvBHw3ubxZpYYfjF8V54NS0jwdfx2tvLeN4X0zTPEfi1k+zpc6Pp8d7BdD4Q8L/Gj/g4i+N8N/468OJ+yH8I/DxvdQsLbwZ4c+B3jP4lSaPdaZcy217pup+JfG/xc8HX2q61p9xG9jqbadotpZ/a7eVLeyQhlr63LOCc/wA0w6xlPD4fB4OXJy4vNMdgsqw0/aOUaap18fXw9Obm6dRR5W+Zwny3UZcvxuecf8K8P4qOAx+Y1auYSU5f2flWXZnnmYRjSjSnUlUwOTYTH4qlCEa9GUp1KUIJVad5e/C/9SAkB/8ArHNSV/KD4E/4K5f8FN/2avEFzbfth/s7fCX9qX4X6Xfz2niXxd+ynZeIPht8d/B0NpOYdRvLz4NfEDxDrXh/x1cac8c6SaH4Y8TaBqLKjGOS4mj+zyftt8Ef+Cqf/BPX49+AfDvxA8D/ALXvwFs7HxChT/hHPHPxJ8KfDvx9oWpxStb3mgeKfAPjbVdD8WeG9esbpHtrnTdU0qCRnUTWrXFpNb3E2fEPBfE3C1WlRzvKcVg/bx5sPV5VVw+IjZNvD4ik50aySlFt05yWq1NeFuO+EONcNWxXDGf5fm9PDyUMVChV5MThJttKGMwdZU8VhJtxklDE0aUnyysnZn6EUViaL4h0bxHptlrXh/V9M13R9RhS50/VNIvrXUtNvreTlJ7O+s5Zra5hYcrLDK6N1DGtuvl2mm00007NNWafZp7M+tunazvfVea7oK/HP/gtX+2D8TP2WP2YvDHg74BTrYftG/tY/FDSP2cfg74jYCUfDu58TaPrWt+Nfiw9qQ5uW+HngjRNZ1XTcpJDB4in0K4u4ri0imtZ/wBbfFHiXRvB3h3W/FXiLUbTR/D/AIc0nUdd13V7+Zbaw0rRtIs57/U9Svbh8JBaWNlbzXVzMxCxQxO7EKDX8Yf7Zn7ep/4KdftEfsO/Ej9mz9nX40Qfs9fs7eN/jfq+ofGz4ojwh4E8NfEXSfib8P18Bab4r+H/AIRuvEV543vtL0y4spdQ06+1bQ9LudRsL6VVsrGU8/deHXDGI4o4ryXBLAYzG5d/aeCWbTwlGpWWGwM68I1q1Z04ydOlGL9+o/ditZNaHwHidxfheCuCeI87qZjl2AzHD5Nmk8kjmOLw+Fhjc3pYGvWwODofWKlNV69atTioUYNzqWaitzvP2Q/Dq/sP6dYW/wAC5U07XUsdRXxJ4v16ytfEfiPxzr+vtBc+JvF3i/UtXiu59Y8Ta7qMIv7rU7ppJ1bFtA0diotq77VPFPiHWdZ1LxDqOr30ur6vq1/ruoXaTvbmbVtTupL6+vRHbGGGGW4upZJW8iONQzYRVAAHnemWXjKDxh4nvtU13S7zwbe6foMXhfQYNHe11bRdQtVvh4hn1DWDeSx6rBqbyWL2Ua2lsbNYJYz5mfNl6WRkjy7uqJuALswVQWIAGSRyWYKPUkAcnFf6eYLIsmwdR4jDZTgcPXeEoZe60MLQjVlgcJzrDYf2kYuTw0Oec6VKUrRVR3jGTaP8S814j4jzJvD47iLMs0pVcbWziVOpj8bVoxzTM6dGeOxDpVuRLGzcYUcTVhB80qNqdSpSUJysvI85eWZmkklZ5JZHZnkld2Lu8jMSzu7EszElmJJJJNfk3+2Z+yP8PvDPi9P20fBnwa8B+PtY8GWN5L8ePhXrfgzQNf074tfDd3juPEOu6ZY6pZXNtY/EzwrZRz6zpmtW0UV7rNvbS6dfTXGY7e6/WIZxyc9fyqtLFHIkkM0aSxSI0ckciho5I3BVkdD8rI6kqytlWBKkEZp5zk2CzjBSwuIoUZShKNfCVZUKVWWFxdL3qGIpRqRceanLSUH7lak50KqnSqThLThbizNeE84pZnga9dRnGeFzPCQxNahTzTLcRaGMwOJnRlGahXptunWg1VwuIVHF4aVPE0KVSNn/AIN4tK8Z6h+0n8fPiX+zX4U+Jngn/gmN4z+DWjf8I7Y+LtP13w38LfF37TkXjiBbzxV+z14R8SJDNY+G9P8ABtv4l0H4hat4ftLHQL/xTDpdo63c2m2v2T+vgdB9B/Kv5u/+DfT4mL4W0z9sz9gyaWT+zf2SvjXpXjb4SWUqhV0f4G/tOaZqPxK8P+GbFj89zZeFvH9n8R7OCZ2doLK90+zDBLeNB/SIOg+g/lX+XPiC8Z/rlxBHH4XBYPGUsxxFDEYfLqXsMFCpQm6Uvq9O75abcLq7bd7tybcn/t14dPL58DcJVcqx2ZZlluIyDLsVgcbnGJeLzTEYXFYaliKM8fiGl7XE+zqRjVaUUpLlSSSM7WNJ07XdL1DRtXsbXU9K1WyutN1PTb6CK7stQ0++gktryyvLWdXgubW6t5ZILi3mR4poXeN1ZWIP83nj/wD4N49A8Iw+OJP2L/21/wBpT9mLRdUttfv/AAN8F7qTwL8Vfgp4G12+hvrqw0vw1YeOPCmpeMfDngsa1PFNPpVp4mv7mys2uY9OnjP2dIP6VaayK2cjrXiZRn2c5BXliMmzPHZZWnHkqVMDiauGnOF03CUqUoScXZXV1suyPezjIMi4gwqwWfZPlmc4ONRVlhc0wOGx+HjVimlUjRxVKrTU0m0pKN0m1s2fxl/sq+OP2bf2NPhrovwd/wCCxEf7Xn7Nf7QOkXer/wBt/GX41O3xB/Z2+L+pTancGfU/g/8AGz4QeAdT8L2Ph4/6/SPBPjJtG8R+G9KltLC5uNX8prtu0/4KDeBP2PP2pP2VIp/+CZn7Rvw4/aW+Mfhz4rfBnx3efA74dftBfC/XfiD8RPh74T+IGi61400Pwz4Tv9W0nXJfFdvp9smq2GkXE1jLqP8AZk+nQW9zqU9pZz/pF/wXN/av+Mn7Nvwm+APw9+CviXwz8J9V/aj+NZ+EPiD9oPxr4W0Pxj4a+EGhWfhDXvF0n2fQfFlvP4OvfGXjqbR08L+EU8Uxz6SJZNRcWsl+tncWn8/Hw2l/Yx+FHxYvPhL/AMFjfBP7Lfxr+C/xa8CeJ/FHwb/bLl/Zt8CfBv40eFPiL4V+wt4i+G/izxH+z14c0LXvEN74n0XWIdb+HXiTSI/+EpGvaTNpkJvZ7ux/s79pyrPfFPH8N4ni2hn2eYvI8trUcHjqEs0jiKfK/ZRcKmAqVFWqUJKpThJ8rT53FVb3R+HZlwj4FZZxtlnCmI4X4ay7i3PaGIznKJwyOWFqVZUJVpzqYTMqNCGEo4qi6FarChGvGpGNLnVHkcb+5/Df4d/tmw+O/E3xm/aK+Fvjv9nj4ReL9HsfCnwQ+C/j6Hw8niy5bQrg33ij4h+NrbRrvV5fDWvapNfWum6R4autVW4h0aCSa6tZGWC6k92Zsn2HSvRP2aPFHxB8S/8ABL3TYfiRrvxb8UeEtF/bE+L3h39kDxl+0Pp+t6V8bvHn7I+gaprVt8I/FHim28V6dpPi28c6Bc3Gl22qa/p9vf32i2WjXM6LHNZJXxx8d/j9pHwcsNG0PSNF1H4j/Gj4hajD4W+DHwS8Jq2oePPih411GT7Lpej6NpVsJLmDTlunSTWtfuY49L0WwSa6u7lCsccn9beGfG8844BfEvElajg3hcbmlPG42c5ww9WFCv7SNenGpOfs4xhVjh1QpycVVoyp0oq6pr/Pfxx8M45N4vLgrgjB1Mwjj8vyFZRlWEo05YqhKtg4YZ4evOjGPtqsp4eeOxGNxVqsqeI9viqjSlVf2h/wRM0+88Qf8FQP+Ck/jDTFmPhrwd8BP2SPhpr1wiFbK58bajd/ErxjBamTASe90vw68ImALPbx6jGjFRIor+rAdB9BX5Vf8Ei/2JfFH7F37NF5a/F660zWv2mfjz421f44ftJ+IdIlN1ph+IHiaK2tdN8F6FeON03hn4aeErHRfBWj+S/2K4m0zUtXso4o9XZa/Vav89+Pc7ocRcYcQ5zheZYXH5piq+G50oy9hOrJ03JJtKTha9m7vW7uf6t+HPDdfhHgXhLhrFVI1MXkmQZXl+KnTbdOWKw+DowxLpNpSdP28Z+zcknyWugooor5E+0eqa8j+dn/AIOQdVu9T/ZA+C3wO1VrXS/hN+01+1v8Ifg18b/F1zp9jczeGvAb2Pizx7YxaXqmp2t3Z+FNX8S+NfBPhXw3ZeKniEumDUpoLWWG7vYZF/HPxR4nvv2eJf2V/wBm39mX4Naj8bPjD478VWnw8+Anwyt/iBb+HdbsIPCXh2/1zU/Gd3478SxaxPpOl+ENNsIbrVfEF48Ysbac3L30Edvsb+1P43fAj4SftG/DbxT8H/jj8PvDPxQ+GPjSzjsfE3gvxdpsWqaNqcMM8V5aSmF8S2uoadfW9tqGlarYy22paVqNtbajpt5a3tvDOnyN+yX/AMEpf2FP2JPGusfEf9nf4GWnhf4g6zpI8O/8Jt4p8afEP4o+KdD8MmTzX8M+Edc+KXivxhqHg7w9cOIzeaT4XuNJtL9YLZb+O6S1txF+08A+K1HgThfPMswWUQqZ9mU19WzefsakKVK0F7Gvh61OrGtSptTnCm1ySnUcqkZ8sUvwjxO8Fl4n8W8J5vm/EGKp8L5BGrPG8LUvrGHWOxUudwxdDMMJi8PWwddqVOlWqRjOr7CjyUKlF1akn+J2if8ABP3/AILc/tSaxaX3x3+JP7O/7IfhI2rWbahN4o8V/tefHrTLQsrG30qO+Phb4V2QlBfy7h9c1FbacCaTTbtR5bfr7+wx/wAEmf2Zf2Hda1z4k+Hx4x+Mv7RHi6zisfF/7SHxy1i18Z/FS/sfLT7Vofhm6jsNP0T4f+FJ5w8r+HfBmk6Rb3UYtbfVp9VTT7FoP1JVAAAcH9f6U/GOlfD59x9xXxJRhhc0zatPBUm/ZZfh408Hl9LVv91gcLGlhYb292kr9bn6Fwx4dcF8HVq2J4e4ewWCx2IjyYjNKntcdm2JglG0cRmuOq4nMK8dE7VcTJe6rrREcUYjUKMcelSUUV8cfbH/2Q=="
-                .to_string()
+                .to_string(),
+            tags: vec![String::from("test")],
+            ..Default::default()
         }
     }
 
@@ -40,6 +42,69 @@ fn test_save_load() {
     remove_file("test_config.json").expect("Failed to remove test config file");
 }
 
+#[test]
+fn test_save_load_formats() {
+    let mut config = Common::get_test_config();
+    config.dark_theme = Some(Theme::LinearGradient {
+        stops: vec![
+            ColorStop { color: ThemeColor::HSL { h: 220, s: 40, l: 20 }, position: 0 },
+            ColorStop { color: ThemeColor::RGBA { r: 0, g: 0, b: 0, a: 50 }, position: 100 }
+        ],
+        angle: 90
+    });
+    config.default_hooks = LaunchHooks { pre_launch: Some(LaunchHook::new("mount")), post_exit: None };
+    config.add_sub_category("test_sub_category", "test_category").unwrap();
+    config.add_launch_group("test_group", vec![LaunchStep { app: "test_app".to_string(), delay_ms: 500, wait_for_previous: true }]).unwrap();
+    config.update_app("test_app", AppMetadata {
+        version: String::from("1.0"),
+        snapshot: Some(AppSnapshot { size: 1024, sha256: String::from("abc"), modified: None, version: String::from("1.0") }),
+        ..Common::get_test_app_metadata()
+    }).unwrap();
+
+    for (path, expected_line) in [
+        ("test_config_formats.toml", "header_text = \"Test Toolbox\""),
+        ("test_config_formats.yaml", "header_text: Test Toolbox"),
+        ("test_config_formats.yml", "header_text: Test Toolbox")
+    ] {
+        config.to_file(path).expect("Failed to create config file");
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.lines().any(|line| line == expected_line), "Unexpected content of {}", path);
+        assert_eq!(Config::from_file(path).expect("Failed to load config file"), config);
+        remove_file(path).expect("Failed to remove test config file");
+    }
+}
+
+#[test]
+fn test_config_format() {
+    assert_eq!(ConfigFormat::from_path("config.json"), ConfigFormat::Json);
+    assert_eq!(ConfigFormat::from_path("config.TOML"), ConfigFormat::Toml);
+    assert_eq!(ConfigFormat::from_path("dir/config.yml"), ConfigFormat::Yaml);
+    assert_eq!(ConfigFormat::from_path("config.yaml"), ConfigFormat::Yaml);
+    // JSON is the default for any other file
+    assert_eq!(ConfigFormat::from_path("config"), ConfigFormat::Json);
+    assert_eq!(ConfigFormat::from_path("config.txt"), ConfigFormat::Json);
+
+    let config = Common::get_test_config();
+    let content = config.serialize(ConfigFormat::Toml).expect("Failed to serialize config");
+    assert_eq!(Config::parse(&content, ConfigFormat::Toml).expect("Failed to parse config"), config);
+    Config::parse(&content, ConfigFormat::Json).expect_err("Expect error");
+}
+
+#[test]
+fn test_find_config_file() {
+    create_dir_all("test_find_config_file").expect("Failed to create test directory");
+    // a new toolbox gets a JSON config
+    assert_eq!(find_config_file("test_find_config_file"), "config.json");
+    let config = Common::get_test_config();
+    config.to_file("test_find_config_file/config.yaml").expect("Failed to create config file");
+    assert_eq!(find_config_file("test_find_config_file"), "config.yaml");
+    config.to_file("test_find_config_file/config.toml").expect("Failed to create config file");
+    assert_eq!(find_config_file("test_find_config_file"), "config.toml");
+    config.to_file("test_find_config_file/config.json").expect("Failed to create config file");
+    assert_eq!(find_config_file("test_find_config_file"), "config.json");
+    remove_dir_all("test_find_config_file").expect("Failed to remove test directory");
+}
+
 #[test]
 fn test_file_not_exist() {
     let result = Config::from_file("non_existent_config.json");
@@ -64,6 +129,18 @@ fn test_parse_error() {
     let ConfigErrorType::ParseError(_) = e.err_type else {
         panic!("Expect ParseError, got {:?}", e.err_type);
     };
+
+    result = Config::from_file("tests/config/invalid_toml_config.toml");
+    e = result.expect_err("Expect error");
+    let ConfigErrorType::ParseError(FormatError::TomlParse(_)) = e.err_type else {
+        panic!("Expect TOML ParseError, got {:?}", e.err_type);
+    };
+
+    result = Config::from_file("tests/config/invalid_yaml_config.yaml");
+    e = result.expect_err("Expect error");
+    let ConfigErrorType::ParseError(FormatError::Yaml(_)) = e.err_type else {
+        panic!("Expect YAML ParseError, got {:?}", e.err_type);
+    };
 }
 
 #[test]
@@ -204,6 +281,16 @@ fn test_update_categories() {
         panic!("Expect CategoryNotExist, got {:?}", e.err_type);
     };
     assert_eq!(category_name, "non_existent_category");
+
+    // subcategories are not moved to the top level
+    config.add_sub_category("test_sub_category", "test_category").unwrap();
+    let result = config.update_categories(vec!["test_category".to_string(), "test_category_2".to_string(), "test_sub_category".to_string()]);
+    let e = result.expect_err("Expect error");
+    let ConfigErrorType::CategoryNotExist(category_name) = e.err_type else {
+        panic!("Expect CategoryNotExist, got {:?}", e.err_type);
+    };
+    assert_eq!(category_name, "test_sub_category");
+    assert_eq!(config.get_category_list(), vec![&"test_category_2".to_string(), &"test_category".to_string()]);
 }
 
 #[test]
@@ -233,4 +320,230 @@ fn test_update_apps_in_category() {
         panic!("Expect AppNotExist, got {:?}", e.err_type);
     };
     assert_eq!(app_name, "non_existent_app");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_tags() {
+    let mut config = Common::get_test_config();
+    let mut app_metadata = Common::get_test_app_metadata();
+    app_metadata.tags = vec![String::from("Network"), String::from("TEST")];
+    config.add_app("test_app_2", app_metadata).unwrap();
+    assert_eq!(config.get_all_tags(), vec!["Network".to_string(), "test".to_string()]);
+    let mut app_names = config.get_app_name_list_by_tag("test");
+    app_names.sort();
+    assert_eq!(app_names, vec!["test_app", "test_app_2"]);
+    assert_eq!(config.get_app_name_list_by_tag("network"), vec!["test_app_2"]);
+}
+
+#[test]
+fn test_smart_category() {
+    let mut config = Common::get_test_config();
+    let mut app_metadata = Common::get_test_app_metadata();
+    app_metadata.app_path = PathBuf::from("tools/network/test_script.ps1");
+    app_metadata.tags = vec![String::from("Network")];
+    config.add_app("network_script", app_metadata).unwrap();
+
+    config.add_smart_category("test_tag", SmartCategoryQuery { tags: vec![String::from("test")], ..Default::default() }).unwrap();
+    assert_eq!(config.get_smart_category_app_name_list("test_tag").unwrap(), vec!["test_app"]);
+
+    config.add_smart_category("scripts", SmartCategoryQuery { file_types: vec![String::from(".PS1"), String::from("bat")], ..Default::default() }).unwrap();
+    assert_eq!(config.get_smart_category_app_name_list("scripts").unwrap(), vec!["network_script"]);
+
+    config.update_smart_category("scripts", SmartCategoryQuery {
+        path_pattern: Some(String::from("tools/*/*")),
+        name_contains: Some(String::from("SCRIPT")),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(config.get_smart_category_app_name_list("scripts").unwrap(), vec!["network_script"]);
+    assert!(config.get_smart_category_app_name_list("non_existent_category").is_none());
+
+    // smart categories share their names with manual categories
+    let result = config.add_smart_category("test_category", SmartCategoryQuery::default());
+    let e = result.expect_err("Expect error");
+    let ConfigErrorType::CategoryExist(category_name) = e.err_type else {
+        panic!("Expect CategoryExist, got {:?}", e.err_type);
+    };
+    assert_eq!(category_name, "test_category");
+    assert!(config.add_category("scripts").is_err());
+
+    config.remove_smart_category("scripts").unwrap();
+    assert_eq!(config.get_smart_category_list().len(), 1);
+    assert!(config.remove_smart_category("scripts").is_err());
+}
+
+#[test]
+fn test_sub_category() {
+    let mut config = Common::get_test_config();
+    config.add_sub_category("sub_category", "test_category").unwrap();
+    config.add_sub_category("sub_sub_category", "sub_category").unwrap();
+    config.add_app_to_category("test_app", "sub_sub_category").unwrap();
+    assert_eq!(config.get_category_list(), vec!["test_category"]);
+    assert_eq!(config.get_sub_category_list("test_category").unwrap(), vec!["sub_category"]);
+    assert_eq!(config.get_category("sub_sub_category").unwrap().apps, vec!["test_app"]);
+
+    // names are unique across the whole tree
+    let e = config.add_category("sub_sub_category").expect_err("Expect error");
+    let ConfigErrorType::CategoryExist(category_name) = e.err_type else {
+        panic!("Expect CategoryExist, got {:?}", e.err_type);
+    };
+    assert_eq!(category_name, "sub_sub_category");
+    assert!(config.add_sub_category("other_category", "non_existent_category").is_err());
+
+    config.rename_app("test_app", "test_app_renamed").unwrap();
+    assert_eq!(config.get_category("sub_sub_category").unwrap().apps, vec!["test_app_renamed"]);
+    config.remove_app("test_app_renamed").unwrap();
+    assert!(config.get_category("sub_sub_category").unwrap().apps.is_empty());
+
+    config.remove_category("sub_category").unwrap();
+    assert!(config.get_category("sub_sub_category").is_none());
+}
+
+#[test]
+fn test_move_category() {
+    let mut config = Common::get_test_config();
+    config.add_category("test_category_2").unwrap();
+    config.add_sub_category("sub_category", "test_category").unwrap();
+    config.add_sub_category("sub_category_2", "test_category").unwrap();
+
+    config.move_category("sub_category_2", Some("test_category_2"), None).unwrap();
+    config.move_category("sub_category", None, Some(0)).unwrap();
+    assert_eq!(config.get_category_tree(), vec![
+        CategoryTreeNode { name: String::from("sub_category"), children: vec![] },
+        CategoryTreeNode { name: String::from("test_category"), children: vec![] },
+        CategoryTreeNode { name: String::from("test_category_2"), children: vec![
+            CategoryTreeNode { name: String::from("sub_category_2"), children: vec![] }
+        ] }
+    ]);
+
+    let e = config.move_category("test_category_2", Some("sub_category_2"), None).expect_err("Expect error");
+    let ConfigErrorType::CategoryMoveIntoItself(category_name, parent_name) = e.err_type else {
+        panic!("Expect CategoryMoveIntoItself, got {:?}", e.err_type);
+    };
+    assert_eq!((category_name.as_str(), parent_name.as_str()), ("test_category_2", "sub_category_2"));
+
+    config.add_sub_category("sub_category_3", "test_category_2").unwrap();
+    config.update_sub_categories(vec!["sub_category_3".to_string(), "sub_category_2".to_string()], "test_category_2").unwrap();
+    assert_eq!(config.get_sub_category_list("test_category_2").unwrap(), vec!["sub_category_3", "sub_category_2"]);
+}
+
+#[test]
+fn test_favorites() {
+    let mut config = Common::get_test_config();
+    config.add_app("test_app_2", Common::get_test_app_metadata()).unwrap();
+    config.pin_app("test_app", None).unwrap();
+    config.pin_app("test_app_2", Some(0)).unwrap();
+    assert_eq!(config.get_favorite_app_name_list(), vec!["test_app_2", "test_app"]);
+    let e = config.pin_app("test_app", None).expect_err("Expect error");
+    let ConfigErrorType::AppExistInCategory(app_name, category_name) = e.err_type else {
+        panic!("Expect AppExistInCategory, got {:?}", e.err_type);
+    };
+    assert_eq!((app_name.as_str(), category_name.as_str()), ("test_app", FAVORITES_CATEGORY));
+    assert!(config.add_category(FAVORITES_CATEGORY).is_err());
+
+    config.update_favorites(vec!["test_app".to_string(), "test_app_2".to_string()]).unwrap();
+    assert!(config.update_favorites(vec!["test_app".to_string(), "test_app".to_string()]).is_err());
+    config.rename_app("test_app", "test_app_renamed").unwrap();
+    assert_eq!(config.get_favorite_app_name_list(), vec!["test_app_renamed", "test_app_2"]);
+    config.remove_app("test_app_2").unwrap();
+    assert_eq!(config.get_favorite_app_name_list(), vec!["test_app_renamed"]);
+
+    config.unpin_app("test_app_renamed").unwrap();
+    assert!(!config.is_pinned("test_app_renamed"));
+    assert!(config.unpin_app("test_app_renamed").is_err());
+
+    // a manual category named "Favorites" is kept as it is
+    config.add_category("Favorites").unwrap();
+    // categories saved with the reserved name are renamed on load
+    config.add_sub_category("reserved_category", "test_category").unwrap();
+    config.add_category(&format!("{} 2", FAVORITES_CATEGORY)).unwrap();
+    let content = config.serialize(ConfigFormat::Json).unwrap().replace("\"reserved_category\"", &format!("\"{}\"", FAVORITES_CATEGORY));
+    let loaded = Config::parse(&content, ConfigFormat::Json).expect("Failed to parse config");
+    assert!(loaded.get_category(FAVORITES_CATEGORY).is_none());
+    assert_eq!(loaded.get_sub_category_list("test_category").unwrap(), vec![&format!("{} 3", FAVORITES_CATEGORY)]);
+    assert!(loaded.get_category("Favorites").is_some());
+}
+
+#[test]
+fn test_theme() {
+    // two-color gradients of older configs are migrated to color stops
+    let config = Config::from_file("tests/config/legacy_theme_config.json").expect("Failed to load config file");
+    assert_eq!(config.theme, Theme::RadialGradient { stops: vec![
+        ColorStop { color: ThemeColor::RGB { r: 40, g: 84, b: 181 }, position: 0 },
+        ColorStop { color: ThemeColor::HSL { h: 186, s: 83, l: 45 }, position: 100 }
+    ] });
+    assert!(config.dark_theme.is_none());
+
+    let mut config = Common::get_test_config();
+    config.dark_theme = Some(Theme::LinearGradient {
+        stops: vec![
+            ColorStop { color: ThemeColor::HSLA { h: 220, s: 40, l: 10, a: 80 }, position: 0 },
+            ColorStop { color: ThemeColor::RGBA { r: 20, g: 20, b: 20, a: 100 }, position: 60 },
+            ColorStop { color: ThemeColor::RGB { r: 0, g: 0, b: 0 }, position: 100 }
+        ],
+        angle: 135
+    });
+    assert_eq!(config.get_theme(false), &config.theme);
+    assert_eq!(config.get_theme(true), config.dark_theme.as_ref().unwrap());
+    config.theme = Theme::Image { path: PathBuf::from("theme_background.png"), fit: ImageFit::Cover };
+    config.to_file("test_theme_config.json").expect("Failed to create config file");
+    assert_eq!(Config::from_file("test_theme_config.json").expect("Failed to load config file"), config);
+    remove_file("test_theme_config.json").expect("Failed to remove test config file");
+
+    let result = serde_json::from_str::<Theme>(r#"{ "type": "LinearGradient", "stops": [{ "color": { "type": "RGB", "r": 0, "g": 0, "b": 0 }, "position": 120 }] }"#);
+    assert!(result.is_err());
+    let result = serde_json::from_str::<ThemeColor>(r#"{ "type": "RGBA", "r": 0, "g": 0, "b": 0, "a": 128 }"#);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_launch_group() {
+    let mut config = Common::get_test_config();
+    config.add_app("test_app_2", Common::get_test_app_metadata()).unwrap();
+    let steps = vec![
+        LaunchStep { app: String::from("test_app"), ..Default::default() },
+        LaunchStep { app: String::from("test_app_2"), delay_ms: 500, wait_for_previous: true }
+    ];
+    config.add_launch_group("test_group", steps.clone()).unwrap();
+    assert_eq!(config.get_launch_group("test_group").unwrap().steps, steps);
+    let e = config.add_launch_group("test_group", Vec::new()).expect_err("Expect error");
+    let ConfigErrorType::LaunchGroupExist(group_name) = e.err_type else {
+        panic!("Expect LaunchGroupExist, got {:?}", e.err_type);
+    };
+    assert_eq!(group_name, "test_group");
+    let e = config.update_launch_group("test_group", vec![LaunchStep { app: String::from("non_existent_app"), ..Default::default() }]).expect_err("Expect error");
+    let ConfigErrorType::AppNotExist(app_name) = e.err_type else {
+        panic!("Expect AppNotExist, got {:?}", e.err_type);
+    };
+    assert_eq!(app_name, "non_existent_app");
+
+    // member apps are kept in sync
+    config.rename_app("test_app", "test_app_renamed").unwrap();
+    config.remove_app("test_app_2").unwrap();
+    assert_eq!(config.get_launch_group("test_group").unwrap().steps, vec![LaunchStep { app: String::from("test_app_renamed"), ..Default::default() }]);
+
+    config.rename_launch_group("test_group", "renamed_group").unwrap();
+    config.remove_launch_group("renamed_group").unwrap();
+    assert!(config.get_launch_group_list().is_empty());
+    assert!(config.remove_launch_group("renamed_group").is_err());
+}
+
+#[test]
+fn test_app_kind() {
+    let url = |app_path: &str| AppMetadata { app_path: PathBuf::from(app_path), kind: AppKind::Url, ..Default::default() };
+    assert!(url("https://intranet.example.com/dashboard").is_target_valid());
+    assert!(url("HTTP://localhost:8080").is_target_valid());
+    assert!(url("mailto:support@example.com").is_target_valid());
+    assert!(!url("https://").is_target_valid());
+    assert!(!url("https:///path").is_target_valid());
+    assert!(!url("javascript:alert(1)").is_target_valid());
+    assert!(!url("file:///C:/Windows/System32/cmd.exe").is_target_valid());
+    assert!(!url("https://example.com/a b").is_target_valid());
+
+    // documents and folders need no working directory
+    let document = AppMetadata { app_path: PathBuf::from("Cargo.toml"), kind: AppKind::Document, ..Default::default() };
+    assert!(document.is_target_valid());
+    assert!(!AppMetadata { kind: AppKind::Executable, ..document.clone() }.is_target_valid());
+    assert!(!AppMetadata { kind: AppKind::Folder, ..document.clone() }.is_target_valid());
+    assert!(AppMetadata { app_path: PathBuf::from("tests"), kind: AppKind::Folder, ..Default::default() }.is_target_valid());
+    assert!(AppKind::Document.is_file() && !AppKind::Url.is_file());
+}
@@ -0,0 +1,190 @@
+use std::path::PathBuf;
+use serde_json::json;
+use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::diff::*;
+use rapid_toolbox_lib::config::structure::*;
+
+struct Common;
+impl Common {
+    fn get_app_metadata(app_path: &str, desc: &str) -> AppMetadata {
+        AppMetadata { app_path: PathBuf::from(app_path), desc: desc.to_string(), ..Default::default() }
+    }
+
+    fn get_test_config() -> Config {
+        let mut config = Config::new();
+        config.toolbox_version = Some(ToolboxVersion(1, 2));
+        config.add_app("Notepad++", Common::get_app_metadata("apps/npp/notepad++.exe", "Text editor")).unwrap();
+        config.add_app("7-Zip", Common::get_app_metadata("apps/7zip/7zFM.exe", "Archiver")).unwrap();
+        config.add_app("Everything", Common::get_app_metadata("apps/everything/Everything.exe", "File search")).unwrap();
+        config.add_app("PuTTY", Common::get_app_metadata("apps/putty/putty.exe", "SSH client")).unwrap();
+        config.add_category("Tools").unwrap();
+        config.add_category("Network").unwrap();
+        config.add_sub_category("Archive", "Tools").unwrap();
+        config.update_apps_in_category(vec!["Notepad++".to_string(), "Everything".to_string()], "Tools").unwrap();
+        config.add_app_to_category("7-Zip", "Archive").unwrap();
+        config.add_app_to_category("PuTTY", "Network").unwrap();
+        config
+    }
+}
+
+#[test]
+fn test_diff_unchanged() {
+    let config = Common::get_test_config();
+    let diff = diff_configs(&config, &config);
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_markdown(), "# Changes from 1.2 to 1.2\n\nNo changes.\n");
+}
+
+#[test]
+fn test_diff_apps() {
+    let old = Common::get_test_config();
+    let mut new = old.clone();
+    // renamed in place, and renamed while moved to another folder
+    new.rename_app("Notepad++", "Notepad++ (portable)").unwrap();
+    new.remove_app("7-Zip").unwrap();
+    new.add_app("7-Zip File Manager", Common::get_app_metadata("tools/7zip/7zFM.exe", "Archiver")).unwrap();
+    new.remove_app("PuTTY").unwrap();
+    new.add_app("WinSCP", Common::get_app_metadata("apps/winscp/WinSCP.exe", "File transfer")).unwrap();
+    new.update_app("Everything", AppMetadata { version: "1.4".to_string(), ..Common::get_app_metadata("apps/everything/Everything.exe", "Search files by name") }).unwrap();
+
+    let diff = diff_configs(&old, &new);
+    assert_eq!(diff.added_apps, vec!["WinSCP"]);
+    assert_eq!(diff.removed_apps, vec!["PuTTY"]);
+    assert_eq!(diff.renamed_apps, vec![
+        AppRename { old_name: "7-Zip".to_string(), new_name: "7-Zip File Manager".to_string() },
+        AppRename { old_name: "Notepad++".to_string(), new_name: "Notepad++ (portable)".to_string() }
+    ]);
+    assert_eq!(diff.changed_apps, vec![
+        AppChange { name: "7-Zip File Manager".to_string(), fields: vec![
+            FieldChange { field: "app_path".to_string(), old_value: json!("apps/7zip/7zFM.exe"), new_value: json!("tools/7zip/7zFM.exe") }
+        ] },
+        AppChange { name: "Everything".to_string(), fields: vec![
+            FieldChange { field: "desc".to_string(), old_value: json!("File search"), new_value: json!("Search files by name") },
+            FieldChange { field: "version".to_string(), old_value: json!(""), new_value: json!("1.4") }
+        ] }
+    ]);
+    assert!(diff.settings.is_empty());
+}
+
+#[test]
+fn test_diff_ambiguous_rename() {
+    let mut old = Config::new();
+    old.add_app("Tool A", Common::get_app_metadata("a/tool.exe", "")).unwrap();
+    old.add_app("Tool B", Common::get_app_metadata("b/tool.exe", "")).unwrap();
+    let mut new = Config::new();
+    new.add_app("Tool", Common::get_app_metadata("c/tool.exe", "")).unwrap();
+    // two removed apps have the file name of the added one, neither is taken as renamed
+    let diff = diff_configs(&old, &new);
+    assert!(diff.renamed_apps.is_empty());
+    assert_eq!(diff.added_apps, vec!["Tool"]);
+    assert_eq!(diff.removed_apps, vec!["Tool A", "Tool B"]);
+}
+
+#[test]
+fn test_diff_categories() {
+    let old = Common::get_test_config();
+    let mut new = old.clone();
+    new.update_categories(vec!["Network".to_string(), "Tools".to_string()]).unwrap();
+    new.update_apps_in_category(vec!["Everything".to_string(), "Notepad++".to_string()], "Tools").unwrap();
+    new.move_category("Archive", None, None).unwrap();
+    new.remove_app_from_category("PuTTY", "Network").unwrap();
+    new.add_category("Media").unwrap();
+    new.add_app_to_category("Everything", "Media").unwrap();
+    new.rename_app("7-Zip", "7-Zip x64").unwrap();
+
+    let diff = diff_configs(&old, &new);
+    assert!(diff.categories_reordered);
+    assert_eq!(diff.categories, vec![
+        CategoryChange {
+            name: "Network".to_string(),
+            change_type: CategoryChangeType::Changed,
+            old_parent: None,
+            new_parent: None,
+            added_apps: vec![],
+            removed_apps: vec!["PuTTY".to_string()],
+            apps_reordered: false,
+            children_reordered: false
+        },
+        CategoryChange {
+            name: "Tools".to_string(),
+            change_type: CategoryChangeType::Changed,
+            old_parent: None,
+            new_parent: None,
+            added_apps: vec![],
+            removed_apps: vec![],
+            apps_reordered: true,
+            children_reordered: false
+        },
+        // renamed apps are not reported as removed and added
+        CategoryChange {
+            name: "Archive".to_string(),
+            change_type: CategoryChangeType::Changed,
+            old_parent: Some("Tools".to_string()),
+            new_parent: None,
+            added_apps: vec![],
+            removed_apps: vec![],
+            apps_reordered: false,
+            children_reordered: false
+        },
+        CategoryChange {
+            name: "Media".to_string(),
+            change_type: CategoryChangeType::Added,
+            old_parent: None,
+            new_parent: None,
+            added_apps: vec!["Everything".to_string()],
+            removed_apps: vec![],
+            apps_reordered: false,
+            children_reordered: false
+        }
+    ]);
+
+    let mut removed = old.clone();
+    removed.remove_category("Network").unwrap();
+    let diff = diff_configs(&old, &removed);
+    assert!(!diff.categories_reordered);
+    assert_eq!(diff.categories.len(), 1);
+    assert_eq!(diff.categories[0].change_type, CategoryChangeType::Removed);
+    assert_eq!(diff.categories[0].removed_apps, vec!["PuTTY"]);
+}
+
+#[test]
+fn test_diff_markdown() {
+    let old = Common::get_test_config();
+    let mut new = old.clone();
+    new.toolbox_version = Some(ToolboxVersion(1, 3));
+    // backticks in the value must not end its code span
+    new.header_text = String::from("Team `Toolbox`");
+    new.rename_app("PuTTY", "PuTTY SSH").unwrap();
+    new.add_app("WinSCP", Common::get_app_metadata("apps/winscp/WinSCP.exe", "File transfer")).unwrap();
+    new.add_app_to_category("WinSCP", "Network").unwrap();
+    new.update_app("Everything", AppMetadata { icon_url: format!("data:image/png;base64,{}", "A".repeat(100)), ..Common::get_app_metadata("apps/everything/Everything.exe", "File search") }).unwrap();
+
+    let diff = diff_configs(&old, &new);
+    assert_eq!(diff.settings.iter().map(|change| change.field.as_str()).collect::<Vec<&str>>(), vec!["header_text", "toolbox_version"]);
+    assert_eq!(diff.to_markdown(), [
+        "# Changes from 1.2 to 1.3",
+        "",
+        "## Settings",
+        "",
+        "- `header_text`: `Rapid Toolbox` -> `` Team `Toolbox` ``",
+        "- `toolbox_version`: `[1,2]` -> `[1,3]`",
+        "",
+        "## Added apps",
+        "",
+        "- **WinSCP**",
+        "",
+        "## Renamed apps",
+        "",
+        "- **PuTTY** -> **PuTTY SSH**",
+        "",
+        "## Changed apps",
+        "",
+        "- **Everything**",
+        "  - `icon_url`: `` -> `data:image/png;base64,AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA...`",
+        "",
+        "## Categories",
+        "",
+        "- **Network**: apps added: WinSCP",
+        ""
+    ].join("\n"));
+}
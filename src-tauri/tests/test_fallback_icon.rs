@@ -0,0 +1,56 @@
+use rapid_toolbox_lib::config::structure::*;
+use rapid_toolbox_lib::fallback_icon::*;
+use rapid_toolbox_lib::util::*;
+
+#[test]
+fn test_app_initials() {
+    assert_eq!(app_initials("Visual Studio Code"), "VS");
+    assert_eq!(app_initials("notepad"), "N");
+    assert_eq!(app_initials("PowerShell"), "PS");
+    assert_eq!(app_initials("7-Zip"), "7Z");
+    assert_eq!(app_initials("  build_release.bat"), "BR");
+    // chinese characters by the initials of their pinyin
+    assert_eq!(app_initials("记事本"), "JS");
+    assert_eq!(app_initials("QQ音乐"), "QY");
+    assert_eq!(app_initials("!!!"), "#");
+    assert_eq!(app_initials(""), "#");
+}
+
+#[test]
+fn test_badge_color() {
+    // the same name always gets the same color
+    assert_eq!(badge_color("Notepad", None), badge_color("Notepad", None));
+    assert_ne!(badge_color("Notepad", None), badge_color("Paint", None));
+
+    let stops = vec![
+        ColorStop { color: ThemeColor::RGB { r: 0x28, g: 0x54, b: 0xB5 }, position: 0 },
+        ColorStop { color: ThemeColor::RGBA { r: 0x14, g: 0xC0, b: 0xD3, a: 50 }, position: 100 }
+    ];
+    let theme = Theme::LinearGradient { stops, angle: DEFAULT_GRADIENT_ANGLE };
+    let theme_colors = [ThemeColor::RGB { r: 0x28, g: 0x54, b: 0xB5 }, ThemeColor::RGB { r: 0x8A, g: 0xE0, b: 0xE9 }];
+    for app_name in ["Notepad", "Paint", "Calculator", "Terminal"] {
+        assert!(theme_colors.contains(&badge_color(app_name, Some(&theme))));
+    }
+    let theme = Theme::Image { path: "background.png".into(), fit: ImageFit::default() };
+    assert_eq!(badge_color("Notepad", Some(&theme)), badge_color("Notepad", None));
+}
+
+#[test]
+fn test_render_badge() {
+    let badge = render_badge("VS", &ThemeColor::RGB { r: 0x28, g: 0x54, b: 0xB5 }, 64);
+    assert_eq!(badge.dimensions(), (64, 64));
+    // rounded corners, white text on a dark badge
+    assert_eq!(badge.get_pixel(0, 0).0[3], 0);
+    assert_eq!(badge.get_pixel(32, 2).0, [0x28, 0x54, 0xB5, 0xFF]);
+    assert!(badge.pixels().any(|pixel| pixel.0 == [0xFF, 0xFF, 0xFF, 0xFF]));
+    let badge = render_badge("N", &ThemeColor::RGB { r: 0xF0, g: 0xE0, b: 0x40 }, 64);
+    assert!(badge.pixels().any(|pixel| pixel.0 == [0, 0, 0, 0xFF]));
+    // three letters are wider than a small badge, which grows to fit them
+    let badge = render_badge("PHP", &ThemeColor::RGB { r: 0x77, g: 0x7B, b: 0xB4 }, 16);
+    assert_eq!(badge.dimensions(), (19, 19));
+    assert!(badge.pixels().any(|pixel| pixel.0 == [0, 0, 0, 0xFF]));
+
+    let settings = IconSettings { max_size: 48, ..Default::default() };
+    let icon_url = fallback_icon_url("Notepad", &Theme::Solid { color: ThemeColor::RGB { r: 0, g: 0, b: 0 } }, &settings).expect("Failed to generate icon");
+    assert!(icon_url.starts_with("data:image/png;base64,"));
+}
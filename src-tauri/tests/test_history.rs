@@ -0,0 +1,56 @@
+use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::history::*;
+
+#[test]
+fn test_undo_redo() {
+    let mut history = EditHistory::default();
+    let mut config = Config::new();
+    assert_eq!(history.state(), EditHistoryState { can_undo: false, can_redo: false });
+    assert!(!history.undo(&mut config));
+
+    history.record(config.clone());
+    config.add_category("test_category").unwrap();
+    history.record(config.clone());
+    config.rename_category("test_category", "renamed_category").unwrap();
+    assert_eq!(history.state(), EditHistoryState { can_undo: true, can_redo: false });
+
+    assert!(history.undo(&mut config));
+    assert_eq!(config.get_category_list(), vec!["test_category"]);
+    assert!(history.undo(&mut config));
+    assert!(config.get_category_list().is_empty());
+    assert_eq!(history.state(), EditHistoryState { can_undo: false, can_redo: true });
+
+    assert!(history.redo(&mut config));
+    assert!(history.redo(&mut config));
+    assert_eq!(config.get_category_list(), vec!["renamed_category"]);
+    assert!(!history.redo(&mut config));
+}
+
+#[test]
+fn test_new_edit_clears_redo() {
+    let mut history = EditHistory::default();
+    let mut config = Config::new();
+    history.record(config.clone());
+    config.add_category("test_category").unwrap();
+    assert!(history.undo(&mut config));
+    assert!(history.state().can_redo);
+
+    history.record(config.clone());
+    config.add_category("other_category").unwrap();
+    assert!(!history.state().can_redo);
+}
+
+#[test]
+fn test_history_depth() {
+    let mut history = EditHistory::new(2);
+    let mut config = Config::new();
+    for category in ["category_1", "category_2", "category_3"] {
+        history.record(config.clone());
+        config.add_category(category).unwrap();
+    }
+    assert!(history.undo(&mut config));
+    assert!(history.undo(&mut config));
+    assert!(!history.undo(&mut config));
+    // the oldest edit has been dropped
+    assert_eq!(config.get_category_list(), vec!["category_1"]);
+}
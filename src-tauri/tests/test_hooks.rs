@@ -0,0 +1,47 @@
+use rapid_toolbox_lib::config::structure::*;
+use rapid_toolbox_lib::hooks::*;
+
+#[cfg(windows)]
+const SLOW_COMMAND: &str = "ping -n 6 127.0.0.1";
+#[cfg(not(windows))]
+const SLOW_COMMAND: &str = "sleep 5";
+
+#[test]
+fn test_run_hook() {
+    let result = run_hook(&LaunchHook::new("echo hello"), ".").expect("Failed to run hook");
+    assert!(result.success());
+    assert_eq!(result.exit_code, Some(0));
+    assert_eq!(result.output, "hello");
+
+    let result = run_hook(&LaunchHook::new("exit 3"), ".").expect("Failed to run hook");
+    assert!(!result.success());
+    assert_eq!(result.summary(), format!("exited with code 3 in {} ms", result.duration_ms));
+
+    let hook = LaunchHook { timeout_ms: 200, ..LaunchHook::new(SLOW_COMMAND) };
+    let result = run_hook(&hook, ".").expect("Failed to run hook");
+    assert!(result.timed_out);
+    assert!(!result.success());
+    assert!(result.duration_ms < 5000);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_hook_timeout_kills_children() {
+    // the output is only read in full once the background sleep is killed and closes the pipe
+    let hook = LaunchHook { timeout_ms: 200, ..LaunchHook::new("echo started; sleep 5 & wait") };
+    let result = run_hook(&hook, ".").expect("Failed to run hook");
+    assert!(result.timed_out);
+    assert_eq!(result.output, "started");
+}
+
+#[test]
+fn test_hook_defaults() {
+    let defaults = LaunchHooks { pre_launch: Some(LaunchHook::new("mount")), post_exit: Some(LaunchHook::new("cleanup")) };
+    let hooks = LaunchHooks { pre_launch: Some(LaunchHook::new("start service")), post_exit: None };
+    assert_eq!(hooks.or(&defaults), LaunchHooks { pre_launch: Some(LaunchHook::new("start service")), post_exit: Some(LaunchHook::new("cleanup")) });
+    assert_eq!(LaunchHooks::default().or(&LaunchHooks::default()), LaunchHooks::default());
+
+    // the timeout falls back to the default when omitted
+    let hook: LaunchHook = serde_json::from_str(r#"{ "command": "mount", "abort_on_failure": true }"#).unwrap();
+    assert_eq!(hook.timeout_ms, DEFAULT_HOOK_TIMEOUT_MS);
+}
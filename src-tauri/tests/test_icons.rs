@@ -0,0 +1,78 @@
+use std::fs::read;
+use image::{GenericImageView, Rgba};
+use rapid_toolbox_lib::config::structure::{IconSettings, SvgMode};
+use rapid_toolbox_lib::icons::*;
+use rapid_toolbox_lib::util::*;
+
+fn entry_sizes(entries: &[IconEntry]) -> Vec<(u32, u16)> {
+    entries.iter().map(|entry| (entry.size(), entry.bit_depth)).collect()
+}
+
+#[test]
+fn test_parse_ico() {
+    let data = read("tests/encode_icon.ico").expect("Failed to read icon");
+    assert!(is_ico(&data));
+    let entries = parse_ico(&data);
+    assert_eq!(entry_sizes(&entries), vec![(16, 32), (32, 32), (48, 32)]);
+
+    // the smallest image not smaller than the display size, or the largest one
+    assert_eq!(select_icon(&entries, 24).unwrap().size(), 32);
+    assert_eq!(select_icon(&entries, 32).unwrap().size(), 32);
+    assert_eq!(select_icon(&entries, 256).unwrap().size(), 48);
+    let image = select_icon(&entries, 16).unwrap().decode().expect("Failed to decode bitmap");
+    assert_eq!(image.dimensions(), (16, 16));
+    assert_eq!(image.get_pixel(8, 8), Rgba([0xFF, 0, 0, 0xFF]));
+    let image = select_icon(&entries, 48).unwrap().decode().expect("Failed to decode png");
+    assert_eq!(image.get_pixel(8, 8), Rgba([0, 0, 0xFF, 0xFF]));
+
+    // cursors are icons with a hotspot
+    let data = read("tests/encode_icon.cur").expect("Failed to read cursor");
+    let entries = parse_ico(&data);
+    assert_eq!(entry_sizes(&entries), vec![(16, 32), (32, 32)]);
+    let image = select_icon(&entries, 32).unwrap().decode().expect("Failed to decode cursor");
+    assert_eq!(image.get_pixel(8, 8), Rgba([0, 0xFF, 0, 0xFF]));
+
+    // entries pointing out of the file are left out
+    assert_eq!(parse_ico(&data[..data.len() - 1]).len(), 1);
+    assert!(parse_ico(b"\0\0\x01\0").is_empty());
+}
+
+#[test]
+fn test_parse_icns() {
+    let data = read("tests/encode_icon.icns").expect("Failed to read icon");
+    assert!(is_icns(&data) && !is_ico(&data));
+    let entries = parse_icns(&data);
+    assert_eq!(entry_sizes(&entries), vec![(16, 32), (32, 32), (128, 32)]);
+
+    // legacy rgb image with its separate mask
+    let image = select_icon(&entries, 16).unwrap().decode().expect("Failed to decode rgb image");
+    assert_eq!(image.get_pixel(0, 0), Rgba([0xFF, 0, 0, 0x80]));
+    let image = select_icon(&entries, 32).unwrap().decode().expect("Failed to decode argb image");
+    assert_eq!(image.get_pixel(31, 31), Rgba([0, 0xFF, 0, 0xFF]));
+    let image = select_icon(&entries, 64).unwrap().decode().expect("Failed to decode png");
+    assert_eq!(image.dimensions(), (128, 128));
+}
+
+#[test]
+fn test_encode_icon_url_from_icon_file() {
+    let settings = IconSettings { max_size: 24, ..Default::default() };
+    for path in ["tests/encode_icon.ico", "tests/encode_icon.cur", "tests/encode_icon.icns"] {
+        let data_url = encode_icon_url_from_file(path, &settings).expect("Failed to load icon");
+        assert!(data_url.starts_with("data:image/png;base64,"), "Unexpected data URL from {}", path);
+    }
+    let mut data = read("tests/encode_icon.icns").expect("Failed to read icon");
+    data.truncate(8);
+    assert!(select_icon(&parse_icon_file(&data), 24).is_none());
+}
+
+#[test]
+fn test_encode_icon_url_from_svg() {
+    let data_url = encode_icon_url_from_file("tests/encode_icon.svg", &IconSettings::default()).expect("Failed to load icon");
+    assert!(data_url.starts_with("data:image/png;base64,"));
+    let settings = IconSettings { svg_mode: SvgMode::Sanitize, ..Default::default() };
+    let data_url = encode_icon_url_from_file("tests/encode_icon.svg", &settings).expect("Failed to load icon");
+    assert!(data_url.starts_with("data:image/svg+xml;base64,"));
+    // svg backgrounds are always sanitized
+    let data_url = encode_image_url_from_file("tests/encode_icon.svg").expect("Failed to load image");
+    assert!(data_url.starts_with("data:image/svg+xml;base64,"));
+}
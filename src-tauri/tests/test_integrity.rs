@@ -0,0 +1,125 @@
+use std::fs::{create_dir_all, remove_dir_all, remove_file, write as fs_write};
+use std::path::PathBuf;
+use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::structure::*;
+use rapid_toolbox_lib::integrity::*;
+
+struct Common;
+impl Common {
+    fn get_test_config(test_dir: &str) -> Config {
+        create_dir_all(test_dir).expect("Failed to create test directory");
+        fs_write(format!("{}/test_app.exe", test_dir), b"test app content").expect("Failed to create test app");
+        fs_write(format!("{}/test_app.dll", test_dir), b"test library content").expect("Failed to create test library");
+
+        let mut config = Config::new();
+        config.add_app("test_app", AppMetadata {
+            app_path: PathBuf::from(format!("{}/test_app.exe", test_dir)),
+            launch_args: String::new(),
+            working_dir: PathBuf::from(test_dir),
+            desc: String::new(),
+            icon_url: String::new(),
+            ..Default::default()
+        }).unwrap();
+        config
+    }
+}
+
+#[test]
+fn test_file_digest() {
+    let digest = FileDigest::from_file("tests/encode_image.jpg").expect("Failed to hash file");
+    assert_eq!(digest.size, 4906);
+    assert_eq!(digest.sha256.len(), 64);
+    FileDigest::from_file("non_existent_file.exe").expect_err("Expect error");
+}
+
+#[test]
+fn test_verify_manifest() {
+    let config = Common::get_test_config("test_integrity_verify");
+    let manifest = IntegrityManifest::from_config(&config, false).expect("Failed to generate manifest");
+    let app_metadata = config.get_app("test_app").unwrap();
+    assert_eq!(manifest.verify_app("test_app", app_metadata), IntegrityStatus::Valid);
+    assert_eq!(manifest.verify_app("other_app", app_metadata), IntegrityStatus::Unlisted);
+
+    // files next to the app are not covered unless directories are included
+    fs_write("test_integrity_verify/test_app.dll", b"tampered library").unwrap();
+    assert_eq!(manifest.verify_app("test_app", app_metadata), IntegrityStatus::Valid);
+
+    fs_write("test_integrity_verify/test_app.exe", b"tampered app content").unwrap();
+    assert_eq!(manifest.verify_app("test_app", app_metadata), IntegrityStatus::Modified);
+
+    remove_file("test_integrity_verify/test_app.exe").unwrap();
+    assert_eq!(manifest.verify(&config).get("test_app"), Some(&IntegrityStatus::Missing));
+    remove_dir_all("test_integrity_verify").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_verify_manifest_with_dirs() {
+    let config = Common::get_test_config("test_integrity_dirs");
+    let manifest = IntegrityManifest::from_config(&config, true).expect("Failed to generate manifest");
+    let app_metadata = config.get_app("test_app").unwrap();
+    assert!(manifest.entries["test_app"].dir.is_some());
+    assert_eq!(manifest.verify_app("test_app", app_metadata), IntegrityStatus::Valid);
+
+    fs_write("test_integrity_dirs/injected.dll", b"injected library").unwrap();
+    assert_eq!(manifest.verify_app("test_app", app_metadata), IntegrityStatus::DirectoryModified);
+    remove_dir_all("test_integrity_dirs").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_root_app_dir() {
+    // the folder of an app in the toolbox folder holds the files of the launcher, so it is not hashed
+    fs_write("test_integrity_root_app.exe", b"test app content").expect("Failed to create test app");
+    let mut config = Config::new();
+    config.add_app("root_app", AppMetadata { app_path: PathBuf::from("test_integrity_root_app.exe"), ..Default::default() }).unwrap();
+    let manifest = IntegrityManifest::from_config(&config, true).expect("Failed to generate manifest");
+    assert!(manifest.entries["root_app"].dir.is_none());
+    fs_write("test_integrity_root_log.txt", b"written by the launcher").unwrap();
+    assert_eq!(manifest.verify_app("root_app", config.get_app("root_app").unwrap()), IntegrityStatus::Valid);
+    remove_file("test_integrity_root_log.txt").unwrap();
+    remove_file("test_integrity_root_app.exe").expect("Failed to remove test app");
+}
+
+#[test]
+fn test_manifest_save_load() {
+    let config = Common::get_test_config("test_integrity_save_load");
+    let manifest = IntegrityManifest::from_config(&config, true).expect("Failed to generate manifest");
+    manifest.to_file("test_integrity_save_load/integrity.json").expect("Failed to save manifest");
+    let new_manifest = IntegrityManifest::from_file("test_integrity_save_load/integrity.json").expect("Failed to load manifest");
+    assert_eq!(new_manifest, manifest);
+    remove_dir_all("test_integrity_save_load").expect("Failed to remove test directory");
+
+    let e = IntegrityManifest::from_file("non_existent_integrity.json").expect_err("Expect error");
+    let IntegrityErrorType::FileNotExist = e.err_type else {
+        panic!("Expect FileNotExist, got {:?}", e.err_type);
+    };
+}
+
+#[test]
+fn test_manifest_missing_app() {
+    let mut config = Config::new();
+    config.add_app("missing_app", AppMetadata {
+        app_path: PathBuf::from("non_existent_app.exe"),
+        launch_args: String::new(),
+        working_dir: PathBuf::from("."),
+        desc: String::new(),
+        icon_url: String::new(),
+        ..Default::default()
+    }).unwrap();
+    let e = IntegrityManifest::from_config(&config, false).expect_err("Expect error");
+    let IntegrityErrorType::AppReadError(app_name, _) = e.err_type else {
+        panic!("Expect AppReadError, got {:?}", e.err_type);
+    };
+    assert_eq!(app_name, "missing_app");
+}
+
+#[test]
+fn test_non_file_entries() {
+    let mut config = Common::get_test_config("test_integrity_non_file");
+    config.add_app("dashboard", AppMetadata { app_path: PathBuf::from("https://intranet.example.com"), kind: AppKind::Url, ..Default::default() }).unwrap();
+    config.add_app("share", AppMetadata { app_path: PathBuf::from("test_integrity_non_file"), kind: AppKind::Folder, ..Default::default() }).unwrap();
+    // folders and urls have nothing to hash, so they are left out of the manifest
+    let manifest = IntegrityManifest::from_config(&config, false).expect("Failed to generate manifest");
+    assert_eq!(manifest.entries.keys().collect::<Vec<_>>(), vec!["test_app"]);
+    assert_eq!(manifest.verify(&config).into_keys().collect::<Vec<_>>(), vec!["test_app"]);
+    remove_dir_all("test_integrity_non_file").expect("Failed to remove test directory");
+}
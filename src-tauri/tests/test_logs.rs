@@ -0,0 +1,46 @@
+use std::fs::{read_to_string, remove_dir_all, write};
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+use rapid_toolbox_lib::logs::*;
+
+#[test]
+fn test_launch_log() {
+    assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01 00:00:00");
+    assert_eq!(format_timestamp(UNIX_EPOCH + Duration::from_secs(1709251199)), "2024-02-29 23:59:59");
+
+    append_log("test_launch_log/logs/launch.log", "Launched 'App'").expect("Failed to write log");
+    append_log("test_launch_log/logs/launch.log", "Launched 'App 2'").expect("Failed to write log");
+    let content = read_to_string("test_launch_log/logs/launch.log").expect("Failed to read log");
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with('[') && lines[0].ends_with("] Launched 'App'"));
+    assert!(lines[1].ends_with("] Launched 'App 2'"));
+    remove_dir_all("test_launch_log").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_app_logs() {
+    assert_eq!(app_log_dir("logs", "Tools: A/B?"), Path::new("logs").join("Tools_ A_B_"));
+    assert_eq!(app_log_dir("logs", ".."), Path::new("logs").join("_"));
+
+    for i in 0..MAX_APP_LOG_FILES + 2 {
+        let (_, path) = create_app_log("test_app_logs", "App").expect("Failed to create log");
+        write(path, format!("line 1\nline 2\nlaunch {}\n", i)).expect("Failed to write log");
+    }
+    let logs = list_app_logs("test_app_logs", "App");
+    assert_eq!(logs.len(), MAX_APP_LOG_FILES);
+    assert!(logs.windows(2).all(|pair| pair[0].file_name > pair[1].file_name));
+
+    // the newest log comes first, and a cut tail starts from a whole line
+    let newest = &logs[0].file_name;
+    assert_eq!(read_log_tail("test_app_logs", "App", newest, 1024).unwrap(), format!("line 1\nline 2\nlaunch {}\n", MAX_APP_LOG_FILES + 1));
+    assert_eq!(read_log_tail("test_app_logs", "App", newest, 12).unwrap(), format!("launch {}\n", MAX_APP_LOG_FILES + 1));
+    assert!(read_log_tail("test_app_logs", "App", "../../Cargo.toml", 1024).is_err());
+
+    create_app_log("test_app_logs", "Other App").expect("Failed to create log");
+    clear_app_logs("test_app_logs", Some("App")).expect("Failed to clear logs");
+    assert!(list_app_logs("test_app_logs", "App").is_empty());
+    assert_eq!(list_app_logs("test_app_logs", "Other App").len(), 1);
+    clear_app_logs("test_app_logs", None).expect("Failed to clear logs");
+    assert!(!Path::new("test_app_logs").exists());
+}
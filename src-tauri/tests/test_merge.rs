@@ -0,0 +1,257 @@
+use std::path::PathBuf;
+use serde_json::{Value, json};
+use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::merge::*;
+use rapid_toolbox_lib::config::structure::*;
+
+struct Common;
+impl Common {
+    fn get_app_metadata(app_path: &str, desc: &str) -> AppMetadata {
+        AppMetadata { app_path: PathBuf::from(app_path), desc: desc.to_string(), ..Default::default() }
+    }
+
+    fn get_base_config() -> Config {
+        let mut config = Config::new();
+        for name in ["A", "B", "C", "D", "G"] {
+            config.add_app(name, Common::get_app_metadata(&format!("apps/{}.exe", name), name)).unwrap();
+        }
+        config.add_category("Tools").unwrap();
+        config.add_category("Network").unwrap();
+        config.add_sub_category("Archive", "Tools").unwrap();
+        config.update_apps_in_category(vec!["A".to_string(), "B".to_string()], "Tools").unwrap();
+        config.add_app_to_category("C", "Archive").unwrap();
+        config.add_app_to_category("D", "Network").unwrap();
+        config
+    }
+
+    fn decision(target: MergeTarget, name: &str, action: MergeAction) -> MergeDecision {
+        MergeDecision { target, name: name.to_string(), action }
+    }
+
+    fn tree(config: &Config) -> Vec<(String, Vec<String>)> {
+        fn walk(nodes: &[CategoryTreeNode], out: &mut Vec<(String, Vec<String>)>) {
+            for node in nodes {
+                out.push((node.name.clone(), node.children.iter().map(|child| child.name.clone()).collect()));
+                walk(&node.children, out);
+            }
+        }
+        let mut out = Vec::new();
+        walk(&config.get_category_tree(), &mut out);
+        out
+    }
+}
+
+#[test]
+fn test_merge_apps() {
+    let base = Common::get_base_config();
+    let mut ours = base.clone();
+    ours.update_app("A", Common::get_app_metadata("apps/A.exe", "A by ours")).unwrap();
+    ours.update_app("D", Common::get_app_metadata("apps/D.exe", "D by ours")).unwrap();
+    ours.remove_app("C").unwrap();
+    ours.remove_app("G").unwrap();
+    ours.add_app("E", Common::get_app_metadata("apps/E.exe", "E")).unwrap();
+    let mut theirs = base.clone();
+    theirs.update_app("A", AppMetadata { version: "2.0".to_string(), ..Common::get_app_metadata("apps/A.exe", "A") }).unwrap();
+    theirs.update_app("B", Common::get_app_metadata("apps/B.exe", "B by theirs")).unwrap();
+    theirs.update_app("D", Common::get_app_metadata("apps/D.exe", "D by theirs")).unwrap();
+    theirs.update_app("G", Common::get_app_metadata("apps/G2.exe", "G")).unwrap();
+    theirs.add_app("F", Common::get_app_metadata("apps/F.exe", "F")).unwrap();
+
+    let (merged, report) = merge_configs(Some(&base), &ours, &theirs);
+    let mut names = merged.get_all_app_name_list();
+    names.sort();
+    assert_eq!(names, vec!["A", "B", "D", "E", "F", "G"]);
+    let app_a = merged.get_app("A").unwrap();
+    assert_eq!((app_a.desc.as_str(), app_a.version.as_str()), ("A by ours", "2.0"));
+    assert_eq!(merged.get_app("B").unwrap().desc, "B by theirs");
+    assert_eq!(merged.get_app("D").unwrap().desc, "D by ours");
+    assert_eq!(merged.get_app("G").unwrap().app_path, PathBuf::from("apps/G2.exe"));
+    // the removed app is gone from its category as well
+    assert!(merged.get_category("Archive").unwrap().apps.is_empty());
+
+    assert_eq!(report.decisions, vec![
+        Common::decision(MergeTarget::App, "A", MergeAction::Combined),
+        Common::decision(MergeTarget::App, "B", MergeAction::TookTheirs),
+        Common::decision(MergeTarget::App, "C", MergeAction::Removed),
+        Common::decision(MergeTarget::App, "D", MergeAction::KeptOurs),
+        Common::decision(MergeTarget::App, "E", MergeAction::KeptOurs),
+        Common::decision(MergeTarget::App, "F", MergeAction::TookTheirs),
+        Common::decision(MergeTarget::App, "G", MergeAction::TookTheirs),
+        Common::decision(MergeTarget::Category, "Archive", MergeAction::KeptOurs)
+    ]);
+    assert_eq!(report.conflicts, vec![
+        MergeConflict {
+            target: MergeTarget::App,
+            name: "D".to_string(),
+            conflict_type: ConflictType::BothModified,
+            fields: vec![FieldConflict { field: "desc".to_string(), base_value: json!("D"), ours_value: json!("D by ours"), theirs_value: json!("D by theirs") }]
+        },
+        MergeConflict { target: MergeTarget::App, name: "G".to_string(), conflict_type: ConflictType::ModifiedAndRemoved, fields: vec![] }
+    ]);
+}
+
+#[test]
+fn test_merge_without_base() {
+    let mut ours = Config::new();
+    ours.header_text = String::from("Our Toolbox");
+    ours.add_app("A", Common::get_app_metadata("apps/A.exe", "A")).unwrap();
+    ours.add_app("B", Common::get_app_metadata("apps/B.exe", "B by ours")).unwrap();
+    let mut theirs = Config::new();
+    theirs.add_app("B", Common::get_app_metadata("apps/B.exe", "B by theirs")).unwrap();
+    theirs.add_app("C", Common::get_app_metadata("apps/C.exe", "C")).unwrap();
+
+    // without a base nothing is removed, and every difference is a conflict
+    let (merged, report) = merge_configs(None, &ours, &theirs);
+    let mut names = merged.get_all_app_name_list();
+    names.sort();
+    assert_eq!(names, vec!["A", "B", "C"]);
+    assert_eq!(merged.header_text, "Our Toolbox");
+    assert_eq!(merged.get_app("B").unwrap().desc, "B by ours");
+    let conflicts: Vec<(&MergeTarget, &str)> = report.conflicts.iter().map(|conflict| (&conflict.target, conflict.name.as_str())).collect();
+    assert_eq!(conflicts, vec![(&MergeTarget::Setting, "header_text"), (&MergeTarget::App, "B")]);
+    assert_eq!(report.conflicts[0].fields[0].base_value, Value::Null);
+}
+
+#[test]
+fn test_merge_settings() {
+    let base = Config::new();
+    let mut ours = base.clone();
+    ours.header_text = String::from("Our Toolbox");
+    let mut theirs = base.clone();
+    theirs.toolbox_version = Some(ToolboxVersion(1, 1));
+    theirs.integrity_policy = IntegrityPolicy::Confirm;
+
+    let (merged, report) = merge_configs(Some(&base), &ours, &theirs);
+    assert_eq!(merged.header_text, "Our Toolbox");
+    assert_eq!(merged.toolbox_version, Some(ToolboxVersion(1, 1)));
+    assert_eq!(merged.integrity_policy, IntegrityPolicy::Confirm);
+    assert_eq!(report.decisions, vec![
+        Common::decision(MergeTarget::Setting, "header_text", MergeAction::KeptOurs),
+        Common::decision(MergeTarget::Setting, "integrity_policy", MergeAction::TookTheirs),
+        Common::decision(MergeTarget::Setting, "toolbox_version", MergeAction::TookTheirs)
+    ]);
+    assert!(report.conflicts.is_empty());
+}
+
+#[test]
+fn test_merge_categories() {
+    let base = Common::get_base_config();
+    let mut ours = base.clone();
+    ours.add_app("E", Common::get_app_metadata("apps/E.exe", "E")).unwrap();
+    ours.update_apps_in_category(vec!["B".to_string(), "A".to_string(), "E".to_string()], "Tools").unwrap();
+    let mut theirs = base.clone();
+    theirs.add_app("F", Common::get_app_metadata("apps/F.exe", "F")).unwrap();
+    theirs.add_app_to_category("F", "Tools").unwrap();
+    theirs.move_category("Archive", None, None).unwrap();
+    theirs.add_category("Media").unwrap();
+
+    let (merged, report) = merge_configs(Some(&base), &ours, &theirs);
+    assert_eq!(Common::tree(&merged), vec![
+        ("Tools".to_string(), vec![]),
+        ("Network".to_string(), vec![]),
+        ("Archive".to_string(), vec![]),
+        ("Media".to_string(), vec![])
+    ]);
+    // the order of ours, who reordered the apps, with the app added by theirs after the app it followed
+    assert_eq!(merged.get_category("Tools").unwrap().apps, vec!["B", "F", "A", "E"]);
+    assert_eq!(merged.get_category("Archive").unwrap().apps, vec!["C"]);
+    assert!(report.decisions.contains(&Common::decision(MergeTarget::Category, "Tools", MergeAction::Combined)));
+    assert!(report.decisions.contains(&Common::decision(MergeTarget::Category, "Archive", MergeAction::TookTheirs)));
+    assert!(report.decisions.contains(&Common::decision(MergeTarget::Category, "Media", MergeAction::TookTheirs)));
+    assert!(report.decisions.contains(&Common::decision(MergeTarget::CategoryOrder, "", MergeAction::TookTheirs)));
+    assert!(report.conflicts.is_empty());
+
+    // both reordered the top-level categories, the order of ours is kept
+    let mut base = base.clone();
+    base.add_category("Media").unwrap();
+    let mut ours = base.clone();
+    ours.update_categories(vec!["Network".to_string(), "Tools".to_string(), "Media".to_string()]).unwrap();
+    let mut theirs = base.clone();
+    theirs.add_category("Games").unwrap();
+    let (merged, report) = merge_configs(Some(&base), &ours, &theirs);
+    assert_eq!(merged.get_category_list(), vec!["Network", "Tools", "Media", "Games"]);
+    assert!(report.conflicts.is_empty());
+    theirs.update_categories(vec!["Tools".to_string(), "Media".to_string(), "Network".to_string(), "Games".to_string()]).unwrap();
+    let (merged, report) = merge_configs(Some(&base), &ours, &theirs);
+    assert_eq!(merged.get_category_list(), vec!["Network", "Games", "Tools", "Media"]);
+    assert_eq!(report.conflicts, vec![
+        MergeConflict { target: MergeTarget::CategoryOrder, name: String::new(), conflict_type: ConflictType::BothReordered, fields: vec![] }
+    ]);
+}
+
+#[test]
+fn test_merge_category_cycle() {
+    let mut base = Config::new();
+    base.add_category("A").unwrap();
+    base.add_category("B").unwrap();
+    let mut ours = base.clone();
+    ours.move_category("B", Some("A"), None).unwrap();
+    let mut theirs = base.clone();
+    theirs.move_category("A", Some("B"), None).unwrap();
+
+    // taken one by one the moves would put the categories inside each other
+    let (merged, _) = merge_configs(Some(&base), &ours, &theirs);
+    assert_eq!(Common::tree(&merged), vec![("A".to_string(), vec!["B".to_string()]), ("B".to_string(), vec![])]);
+}
+
+#[test]
+fn test_merge_favorites_and_groups() {
+    let mut base = Common::get_base_config();
+    base.add_launch_group("Work", vec![LaunchStep { app: "A".to_string(), delay_ms: 0, wait_for_previous: false }]).unwrap();
+    let mut ours = base.clone();
+    ours.pin_app("A", None).unwrap();
+    ours.update_launch_group("Work", vec![LaunchStep { app: "B".to_string(), delay_ms: 0, wait_for_previous: false }]).unwrap();
+    let mut theirs = base.clone();
+    theirs.pin_app("D", None).unwrap();
+    theirs.pin_app("C", None).unwrap();
+    theirs.remove_app("C").unwrap();
+    theirs.update_launch_group("Work", vec![LaunchStep { app: "D".to_string(), delay_ms: 500, wait_for_previous: false }]).unwrap();
+    theirs.add_launch_group("Play", vec![]).unwrap();
+
+    let (merged, report) = merge_configs(Some(&base), &ours, &theirs);
+    // the pins of theirs go after the app they follow there, or first, and the removed app is left out
+    assert_eq!(merged.get_favorite_app_name_list(), vec!["D", "A"]);
+    let groups: Vec<&str> = merged.get_launch_group_list().iter().map(|group| group.name.as_str()).collect();
+    assert_eq!(groups, vec!["Work", "Play"]);
+    assert_eq!(merged.get_launch_group("Work").unwrap().steps[0].app, "B");
+    assert!(report.decisions.contains(&Common::decision(MergeTarget::Favorites, "", MergeAction::Combined)));
+    assert!(report.decisions.contains(&Common::decision(MergeTarget::LaunchGroup, "Play", MergeAction::TookTheirs)));
+    assert!(report.conflicts.contains(&MergeConflict {
+        target: MergeTarget::LaunchGroup,
+        name: "Work".to_string(),
+        conflict_type: ConflictType::BothModified,
+        fields: vec![]
+    }));
+}
+
+#[test]
+fn test_merge_missing_references() {
+    let base = Common::get_base_config();
+    let mut ours = base.clone();
+    ours.remove_app("G").unwrap();
+    ours.add_category("Media").unwrap();
+    let mut theirs = base.clone();
+    let step = LaunchStep { app: "G".to_string(), delay_ms: 0, wait_for_previous: false };
+    theirs.add_launch_group("Night", vec![LaunchStep { app: "A".to_string(), ..step.clone() }, step.clone()]).unwrap();
+    theirs.add_smart_category("Media", SmartCategoryQuery { tags: vec!["media".to_string()], ..Default::default() }).unwrap();
+
+    let (merged, report) = merge_configs(Some(&base), &ours, &theirs);
+    // the group of theirs is taken without the app removed by ours
+    assert!(merged.get_app("G").is_none());
+    assert_eq!(merged.get_launch_group("Night").unwrap().steps.iter().map(|step| step.app.as_str()).collect::<Vec<&str>>(), vec!["A"]);
+    assert!(report.conflicts.contains(&MergeConflict {
+        target: MergeTarget::LaunchGroup,
+        name: "Night".to_string(),
+        conflict_type: ConflictType::MissingApp,
+        fields: vec![FieldConflict { field: "G".to_string(), base_value: Value::Null, ours_value: Value::Null, theirs_value: serde_json::to_value(&step).unwrap() }]
+    }));
+    // the manual category keeps the name
+    assert!(merged.get_category("Media").is_some());
+    assert!(merged.get_smart_category_list().is_empty());
+    assert!(report.conflicts.contains(&MergeConflict {
+        target: MergeTarget::SmartCategory,
+        name: "Media".to_string(),
+        conflict_type: ConflictType::NameTaken,
+        fields: vec![]
+    }));
+}
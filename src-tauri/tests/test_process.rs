@@ -0,0 +1,48 @@
+use rapid_toolbox_lib::config::structure::*;
+use rapid_toolbox_lib::process::*;
+
+#[test]
+fn test_process_tracker() {
+    let tracker = ProcessTracker::new();
+    assert!(tracker.begin_launch("App", false));
+    // a launch that is still starting counts as running for single instance apps
+    assert!(!tracker.begin_launch("App", false));
+    tracker.end_launch("App", Some(100));
+    assert!(tracker.is_running("App"));
+    assert!(!tracker.begin_launch("App", false));
+
+    assert!(tracker.begin_launch("App", true));
+    tracker.end_launch("App", Some(101));
+    assert_eq!(tracker.get_pids("App"), vec![100, 101]);
+
+    // a failed launch does not leave the app starting
+    assert!(tracker.begin_launch("Other App", false));
+    tracker.end_launch("Other App", None);
+    assert!(!tracker.is_running("Other App"));
+    assert!(tracker.begin_launch("Other App", false));
+    tracker.end_launch("Other App", Some(200));
+    assert_eq!(tracker.get_running_app_list(), vec!["App", "Other App"]);
+
+    tracker.rename_app("App", "Renamed App");
+    assert!(!tracker.is_running("App"));
+    assert_eq!(tracker.get_pids("Renamed App"), vec![100, 101]);
+
+    // the clones share the processes with the threads waiting for them
+    let watcher = tracker.clone();
+    watcher.exited(100);
+    watcher.exited(101);
+    assert!(!tracker.is_running("Renamed App"));
+    assert_eq!(tracker.get_running_app_list(), vec!["Other App"]);
+}
+
+#[test]
+fn test_launch_policy() {
+    let metadata: AppMetadata = serde_json::from_str(r#"{
+        "app_path": "app.exe", "launch_args": "", "working_dir": ".", "desc": "", "icon_url": ""
+    }"#).unwrap();
+    assert_eq!(metadata.launch_policy, LaunchPolicy::AllowMultiple);
+    let metadata: AppMetadata = serde_json::from_str(r#"{
+        "app_path": "app.exe", "launch_args": "", "working_dir": ".", "desc": "", "icon_url": "", "launch_policy": "SingleInstance"
+    }"#).unwrap();
+    assert_eq!(metadata.launch_policy, LaunchPolicy::SingleInstance);
+}
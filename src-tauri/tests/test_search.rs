@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::structure::*;
+use rapid_toolbox_lib::search::*;
+
+struct Common;
+impl Common {
+    fn get_app_metadata(app_path: &str, desc: &str, tags: &[&str]) -> AppMetadata {
+        AppMetadata {
+            app_path: PathBuf::from(app_path),
+            desc: desc.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn get_test_config() -> Config {
+        let mut config = Config::new();
+        config.add_app("Visual Studio Code", Common::get_app_metadata("apps/vscode/Code.exe", "Code editor", &["editor"])).unwrap();
+        config.add_app("Notepad++", Common::get_app_metadata("apps/npp/notepad++.exe", "Lightweight text editor", &["editor"])).unwrap();
+        config.add_app("网易云音乐", Common::get_app_metadata("apps/music/cloudmusic.exe", "Music player", &["media"])).unwrap();
+        config
+    }
+}
+
+#[test]
+fn test_fuzzy_match() {
+    let text_match = fuzzy_match("vsc", "Visual Studio Code").expect("Expect match");
+    assert_eq!(text_match.ranges, vec![MatchRange { start: 0, end: 1 }, MatchRange { start: 7, end: 8 }, MatchRange { start: 14, end: 15 }]);
+    assert!(fuzzy_match("code", "Code").unwrap().score > fuzzy_match("code", "Visual Studio Code").unwrap().score);
+    assert!(fuzzy_match("xyz", "Visual Studio Code").is_none());
+
+    // full pinyin and pinyin initials
+    assert_eq!(fuzzy_match("wangyi", "网易云音乐").unwrap().ranges, vec![MatchRange { start: 0, end: 2 }]);
+    assert_eq!(fuzzy_match("yyy", "网易云音乐").unwrap().ranges, vec![MatchRange { start: 1, end: 4 }]);
+    assert!(fuzzy_match("网易", "网易云音乐").unwrap().score > fuzzy_match("wy", "网易云音乐").unwrap().score);
+}
+
+#[test]
+fn test_search_apps() {
+    let config = Common::get_test_config();
+    let results = search_apps(&config, "code");
+    let app_names: Vec<&str> = results.iter().map(|result| result.app_name.as_str()).collect();
+    // "cloudmusic.exe" matches as a subsequence, but only by file name
+    assert_eq!(app_names, vec!["Visual Studio Code", "网易云音乐"]);
+    assert_eq!(results[0].matches.iter().map(|m| m.field.clone()).collect::<Vec<_>>(), vec![SearchField::Name, SearchField::Description, SearchField::FileName]);
+
+    // both match by description, the better match ranks first
+    let results = search_apps(&config, "edit");
+    let app_names: Vec<&str> = results.iter().map(|result| result.app_name.as_str()).collect();
+    assert_eq!(app_names, vec!["Visual Studio Code", "Notepad++"]);
+    let results = search_apps(&config, "note");
+    assert_eq!(results[0].app_name, "Notepad++");
+    assert_eq!(results[0].matches[0].field, SearchField::Name);
+
+    assert_eq!(search_apps(&config, "yunyin")[0].app_name, "网易云音乐");
+    assert_eq!(search_apps(&config, "media")[0].matches[0].field, SearchField::Tag { index: 0 });
+    assert!(search_apps(&config, "  ").is_empty());
+}
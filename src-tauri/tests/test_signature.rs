@@ -0,0 +1,122 @@
+use std::fs::{create_dir_all, remove_dir_all, write as fs_write};
+use std::path::PathBuf;
+use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::structure::*;
+use rapid_toolbox_lib::signature::*;
+
+struct Common;
+impl Common {
+    fn get_test_config(test_dir: &str) -> Config {
+        create_dir_all(test_dir).expect("Failed to create test directory");
+        fs_write(format!("{}/test_app.exe", test_dir), b"test app content").expect("Failed to create test app");
+
+        let mut config = Config::new();
+        config.add_app("test_app", AppMetadata {
+            app_path: PathBuf::from(format!("{}/test_app.exe", test_dir)),
+            launch_args: String::new(),
+            working_dir: PathBuf::from(test_dir),
+            desc: String::new(),
+            icon_url: String::new(),
+            ..Default::default()
+        }).unwrap();
+        config.to_file(format!("{}/config.json", test_dir)).expect("Failed to create config file");
+        config
+    }
+}
+
+#[test]
+fn test_sign_verify() {
+    let config = Common::get_test_config("test_signature_verify");
+    let signing_key = generate_signing_key();
+    let trusted_keys = [signing_key.verifying_key()];
+    assert_eq!(verify_config_file("test_signature_verify/config.json", &trusted_keys, &config), SignatureStatus::Unsigned);
+
+    let signature = ConfigSignature::sign("test_signature_verify/config.json", &signing_key, None).expect("Failed to sign config");
+    signature.to_file(signature_path_of("test_signature_verify/config.json")).expect("Failed to save signature");
+    assert_eq!(verify_config_file("test_signature_verify/config.json", &trusted_keys, &config), SignatureStatus::Valid);
+
+    // signed by a key that is not trusted
+    let other_trusted_keys = [generate_signing_key().verifying_key()];
+    assert_eq!(verify_config_file("test_signature_verify/config.json", &other_trusted_keys, &config), SignatureStatus::Tampered);
+
+    let mut modified_config = config.clone();
+    modified_config.header_text = String::from("Modified Toolbox");
+    modified_config.to_file("test_signature_verify/config.json").unwrap();
+    assert_eq!(verify_config_file("test_signature_verify/config.json", &trusted_keys, &modified_config), SignatureStatus::Tampered);
+    remove_dir_all("test_signature_verify").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_sign_verify_app_hashes() {
+    let config = Common::get_test_config("test_signature_app_hashes");
+    let signing_key = generate_signing_key();
+    let trusted_keys = [signing_key.verifying_key()];
+    let signature = ConfigSignature::sign("test_signature_app_hashes/config.json", &signing_key, Some(&config)).expect("Failed to sign config");
+    assert!(signature.app_hashes.as_ref().is_some_and(|app_hashes| app_hashes.contains_key("test_app")));
+    assert_eq!(signature.verify("test_signature_app_hashes/config.json", &trusted_keys, &config), SignatureStatus::Valid);
+
+    fs_write("test_signature_app_hashes/test_app.exe", b"tampered app content").unwrap();
+    assert_eq!(signature.verify("test_signature_app_hashes/config.json", &trusted_keys, &config), SignatureStatus::Tampered);
+    remove_dir_all("test_signature_app_hashes").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_sign_verify_non_file_apps() {
+    let mut config = Common::get_test_config("test_signature_non_file");
+    config.add_app("dashboard", AppMetadata { app_path: PathBuf::from("https://intranet.example.com"), kind: AppKind::Url, ..Default::default() }).unwrap();
+    config.add_app("share", AppMetadata { app_path: PathBuf::from("test_signature_non_file"), kind: AppKind::Folder, ..Default::default() }).unwrap();
+    config.to_file("test_signature_non_file/config.json").expect("Failed to save config file");
+    let signing_key = generate_signing_key();
+    let trusted_keys = [signing_key.verifying_key()];
+    // folders and urls are signed along with the config but not hashed
+    let signature = ConfigSignature::sign("test_signature_non_file/config.json", &signing_key, Some(&config)).expect("Failed to sign config");
+    let app_hashes = signature.app_hashes.as_ref().unwrap();
+    assert_eq!(app_hashes.keys().collect::<Vec<&String>>(), vec!["test_app"]);
+    assert_eq!(signature.verify("test_signature_non_file/config.json", &trusted_keys, &config), SignatureStatus::Valid);
+    remove_dir_all("test_signature_non_file").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_config_verifier() {
+    let config = Common::get_test_config("test_signature_verifier");
+    let signing_key = generate_signing_key();
+    create_dir_all("test_signature_verifier/launcher").unwrap();
+    fs_write(format!("test_signature_verifier/launcher/{}", TRUSTED_KEYS_FILE_NAME), encode_public_key(&signing_key)).unwrap();
+    let mut verifier = ConfigVerifier::new(Some(PathBuf::from("test_signature_verifier/launcher")));
+    // keys next to the config are not trusted
+    let mut untrusted_verifier = ConfigVerifier::new(Some(PathBuf::from("test_signature_verifier/non_existent")));
+    fs_write(format!("test_signature_verifier/{}", TRUSTED_KEYS_FILE_NAME), encode_public_key(&signing_key)).unwrap();
+    assert_eq!(verifier.status("test_signature_verifier/config.json", &config), SignatureStatus::Unsigned);
+
+    let signature = ConfigSignature::sign("test_signature_verifier/config.json", &signing_key, None).expect("Failed to sign config");
+    signature.to_file(signature_path_of("test_signature_verifier/config.json")).expect("Failed to save signature");
+    assert_eq!(verifier.status("test_signature_verifier/config.json", &config), SignatureStatus::Valid);
+    assert_eq!(untrusted_verifier.status("test_signature_verifier/config.json", &config), SignatureStatus::Tampered);
+
+    // saved after signing
+    let mut modified_config = config.clone();
+    modified_config.header_text = String::from("Modified Toolbox");
+    modified_config.to_file("test_signature_verifier/config.json").unwrap();
+    assert_eq!(verifier.status("test_signature_verifier/config.json", &modified_config), SignatureStatus::Tampered);
+    remove_dir_all("test_signature_verifier").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_key_files() {
+    create_dir_all("test_signature_keys").expect("Failed to create test directory");
+    let signing_key = generate_signing_key();
+    signing_key_to_file(&signing_key, "test_signature_keys/toolbox.key").expect("Failed to save signing key");
+    let loaded_key = signing_key_from_file("test_signature_keys/toolbox.key").expect("Failed to load signing key");
+    assert_eq!(loaded_key.to_bytes(), signing_key.to_bytes());
+
+    fs_write("test_signature_keys/trusted_keys.txt", format!("# toolbox publisher\n{}\n\nnot a key\n", encode_public_key(&signing_key))).unwrap();
+    assert_eq!(load_trusted_keys("test_signature_keys/trusted_keys.txt"), vec![signing_key.verifying_key()]);
+    assert!(load_trusted_keys("test_signature_keys/non_existent_keys.txt").is_empty());
+
+    fs_write("test_signature_keys/invalid.key", "invalid key").unwrap();
+    let e = signing_key_from_file("test_signature_keys/invalid.key").expect_err("Expect error");
+    let SignatureErrorType::InvalidKey = e.err_type else {
+        panic!("Expect InvalidKey, got {:?}", e.err_type);
+    };
+    remove_dir_all("test_signature_keys").expect("Failed to remove test directory");
+}
@@ -0,0 +1,80 @@
+use std::fs::{File, read};
+use std::io::Cursor;
+use rapid_toolbox_lib::app_icon::{elf_end_offset, is_appimage};
+use rapid_toolbox_lib::squashfs::*;
+
+const APPIMAGE_PATH: &str = "tests/app_icon/tool.AppImage";
+
+// the same bytes as in the fixture, to tell a block stored as it is from a compressed one
+fn pseudo_random_bytes(len: usize) -> Vec<u8> {
+    let mut x: u32 = 1;
+    (0..len).map(|_| {
+        x = x.wrapping_mul(1103515245).wrapping_add(12345) & 0x7FFF_FFFF;
+        (x >> 16) as u8
+    }).collect()
+}
+
+#[test]
+fn test_read_squashfs() {
+    let header = read(APPIMAGE_PATH).expect("Failed to read AppImage");
+    assert!(is_appimage(&header));
+    let start = elf_end_offset(&header).expect("Failed to read ELF header");
+    assert_eq!(start, 128);
+    let mut image = SquashFs::new(File::open(APPIMAGE_PATH).unwrap(), start).expect("Failed to open image");
+
+    assert_eq!(image.list_dir("").unwrap(), vec![".DirIcon", "data.bin", "tool.desktop", "tool.png", "usr"]);
+    assert_eq!(image.list_dir("usr/bin").unwrap(), vec!["tool"]);
+    // files in fragments, the symlink in the root and paths going up
+    assert!(image.read_file("tool.desktop").unwrap().starts_with(b"[Desktop Entry]"));
+    assert_eq!(image.read_file("usr/bin/tool").unwrap(), b"#!/bin/sh\n");
+    assert_eq!(image.read_file("./usr/bin/../../.DirIcon").unwrap(), image.read_file("tool.png").unwrap());
+    // a block stored as it is and a compressed one
+    let mut expected = pseudo_random_bytes(4096);
+    expected.resize(5000, 0);
+    assert_eq!(image.read_file("data.bin").unwrap(), expected);
+
+    let e = image.read_file("usr/tool").unwrap_err();
+    let SquashFsErrorType::NotFound = e.err_type else {
+        panic!("Expect NotFound, got {:?}", e.err_type);
+    };
+    let e = image.read_file("usr").unwrap_err();
+    let SquashFsErrorType::NotAFile = e.err_type else {
+        panic!("Expect NotAFile, got {:?}", e.err_type);
+    };
+    let e = image.list_dir("tool.png").unwrap_err();
+    let SquashFsErrorType::NotADirectory = e.err_type else {
+        panic!("Expect NotADirectory, got {:?}", e.err_type);
+    };
+}
+
+#[test]
+fn test_invalid_squashfs() {
+    let data = read(APPIMAGE_PATH).expect("Failed to read AppImage");
+    let e = SquashFs::new(Cursor::new(data.clone()), 0).err().unwrap();
+    let SquashFsErrorType::InvalidImage = e.err_type else {
+        panic!("Expect InvalidImage, got {:?}", e.err_type);
+    };
+    // lzo
+    let mut lzo_data = data.clone();
+    lzo_data[128 + 20] = 3;
+    let e = SquashFs::new(Cursor::new(lzo_data), 128).err().unwrap();
+    let SquashFsErrorType::UnsupportedCompression(3) = e.err_type else {
+        panic!("Expect UnsupportedCompression, got {:?}", e.err_type);
+    };
+    let e = SquashFs::new(Cursor::new(&data[..200]), 128).err().unwrap();
+    let SquashFsErrorType::ReadError(_) = e.err_type else {
+        panic!("Expect ReadError, got {:?}", e.err_type);
+    };
+}
+
+#[test]
+fn test_corrupted_squashfs() {
+    let mut data = read(APPIMAGE_PATH).expect("Failed to read AppImage");
+    // an inode table past the end of the address space
+    data[128 + 64..128 + 72].copy_from_slice(&u64::MAX.to_le_bytes());
+    let mut image = SquashFs::new(Cursor::new(data), 128).expect("Failed to open image");
+    let e = image.list_dir("").unwrap_err();
+    let SquashFsErrorType::Corrupted = e.err_type else {
+        panic!("Expect Corrupted, got {:?}", e.err_type);
+    };
+}
@@ -0,0 +1,31 @@
+use std::fs::read;
+use rapid_toolbox_lib::svg::*;
+
+#[test]
+fn test_sanitize_svg() {
+    let data = read("tests/encode_icon.svg").expect("Failed to read svg");
+    let svg = sanitize_svg(&data).expect("Failed to sanitize svg");
+    for removed in ["alert", "script", "example.com", "@import", "inkscape", "secret", "passwd", "foreignObject", "html", "<!--", "javascript", "<set", "onclick"] {
+        assert!(!svg.contains(removed), "'{}' is left in {}", removed, svg);
+    }
+    for kept in ["fill=\"url(#fill)\"", "xlink:href=\"#fill\"", "<stop offset=\"0\" stop-color=\"#2854B5\"/>", "xml:space=\"preserve\">a &lt; b</text>", "<animate attributeName=\"opacity\""] {
+        assert!(svg.contains(kept), "'{}' is missing in {}", kept, svg);
+    }
+    // the result is still a valid svg
+    assert_eq!(sanitize_svg(svg.as_bytes()).expect("Failed to sanitize svg again"), svg);
+
+    assert!(sanitize_svg(b"<html><svg xmlns=\"http://www.w3.org/2000/svg\"/></html>").is_err());
+    assert!(sanitize_svg(b"<svg xmlns=\"http://www.w3.org/2000/svg\">").is_err());
+    assert!(sanitize_svg(b"<!DOCTYPE svg [<!ENTITY x \"y\">]><svg xmlns=\"http://www.w3.org/2000/svg\">&x;</svg>").is_err());
+}
+
+#[test]
+fn test_rasterize_svg() {
+    let data = read("tests/encode_icon.svg").expect("Failed to read svg");
+    let image = rasterize_svg(&data, 64).expect("Failed to rasterize svg");
+    // scaled to fit, keeping the aspect ratio
+    assert_eq!(image.dimensions(), (64, 32));
+    assert_eq!(image.get_pixel(16, 16).0, [0x28, 0x54, 0xB5, 0xFF]);
+    assert_eq!(image.get_pixel(48, 2).0, [0x14, 0xC0, 0xD3, 0xFF]);
+    assert!(rasterize_svg(b"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"0\" height=\"0\"/>", 64).is_err());
+}
@@ -0,0 +1,49 @@
+use std::fs::{create_dir_all, remove_dir_all};
+use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::sync::ConfigSync;
+use rapid_toolbox_lib::config::error::ConfigErrorType;
+
+#[test]
+fn test_detect_external_modification() {
+    create_dir_all("test_sync_detect").expect("Failed to create test directory");
+    let mut sync = ConfigSync::new();
+    let config = Config::new();
+    sync.force_save(&config, "test_sync_detect/config.json").expect("Failed to save config");
+    assert!(!sync.is_modified_externally("test_sync_detect/config.json"));
+
+    let mut external_config = config.clone();
+    external_config.header_text = String::from("Edited Toolbox");
+    external_config.to_file("test_sync_detect/config.json").unwrap();
+    assert!(sync.is_modified_externally("test_sync_detect/config.json"));
+
+    let reloaded_config = sync.load("test_sync_detect/config.json").expect("Failed to load config");
+    assert_eq!(reloaded_config, external_config);
+    assert!(!sync.is_modified_externally("test_sync_detect/config.json"));
+    remove_dir_all("test_sync_detect").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_save_conflict() {
+    create_dir_all("test_sync_conflict").expect("Failed to create test directory");
+    let mut sync = ConfigSync::new();
+    let mut config = Config::new();
+    sync.force_save(&config, "test_sync_conflict/config.json").expect("Failed to save config");
+
+    let mut external_config = config.clone();
+    external_config.header_text = String::from("Edited Toolbox");
+    external_config.to_file("test_sync_conflict/config.json").unwrap();
+
+    config.add_category("test_category").unwrap();
+    let e = sync.save(&config, "test_sync_conflict/config.json").expect_err("Expect error");
+    let ConfigErrorType::ModifiedExternally = e.err_type else {
+        panic!("Expect ModifiedExternally, got {:?}", e.err_type);
+    };
+    assert!(sync.has_conflict());
+    // the external edit is kept until the conflict is resolved
+    assert_eq!(Config::from_file("test_sync_conflict/config.json").unwrap(), external_config);
+
+    sync.force_save(&config, "test_sync_conflict/config.json").expect("Failed to save config");
+    assert!(!sync.has_conflict());
+    assert_eq!(Config::from_file("test_sync_conflict/config.json").unwrap(), config);
+    remove_dir_all("test_sync_conflict").expect("Failed to remove test directory");
+}
@@ -0,0 +1,73 @@
+use std::fs::{create_dir_all, remove_dir_all};
+use rapid_toolbox_lib::config::structure::*;
+use rapid_toolbox_lib::theme::*;
+
+#[test]
+fn test_color_conversion() {
+    let color = ThemeColor::RGB { r: 0x28, g: 0x54, b: 0xB5 };
+    assert_eq!(color.to_hsl(), (221, 64, 43));
+    assert_eq!(ThemeColor::HSL { h: 221, s: 64, l: 43 }.to_rgb(), (0x27, 0x54, 0xB4));
+    assert_eq!(ThemeColor::HSL { h: 0, s: 100, l: 50 }.to_rgb(), (255, 0, 0));
+    assert_eq!(ThemeColor::RGB { r: 128, g: 128, b: 128 }.to_hsl(), (0, 0, 50));
+
+    let translucent = ThemeColor::RGBA { r: 0, g: 0, b: 0, a: 50 };
+    assert_eq!(translucent.alpha(), 50);
+    assert_eq!(translucent.blend_over((255, 255, 255)), (128, 128, 128));
+    assert_eq!(ThemeColor::HSLA { h: 0, s: 0, l: 0, a: 100 }.blend_over((255, 255, 255)), (0, 0, 0));
+}
+
+#[test]
+fn test_contrast() {
+    let black = ThemeColor::RGB { r: 0, g: 0, b: 0 };
+    assert!((black.contrast_ratio(&HEADER_TEXT_COLOR) - 21.0).abs() < 1e-9);
+    assert!((HEADER_TEXT_COLOR.contrast_ratio(&HEADER_TEXT_COLOR) - 1.0).abs() < 1e-9);
+
+    let contrast = check_contrast(&Theme::Solid { color: ThemeColor::RGB { r: 0xF0, g: 0xF0, b: 0xF0 } }, &HEADER_TEXT_COLOR).unwrap();
+    assert!(!contrast.readable);
+    assert!(contrast.has_unreadable_stop);
+
+    // a light stop in a dark gradient is flagged without failing the whole theme
+    let stops = vec![
+        ColorStop { color: ThemeColor::RGB { r: 0, g: 0, b: 0 }, position: 0 },
+        ColorStop { color: ThemeColor::RGB { r: 0, g: 0, b: 0 }, position: 50 },
+        ColorStop { color: ThemeColor::RGB { r: 0xF0, g: 0xF0, b: 0xF0 }, position: 100 }
+    ];
+    let contrast = check_contrast(&Theme::LinearGradient { stops, angle: 90 }, &HEADER_TEXT_COLOR).unwrap();
+    assert!(contrast.readable);
+    assert!(contrast.has_unreadable_stop);
+    assert!(contrast.min_ratio < MIN_CONTRAST_RATIO);
+    for preset in builtin_presets() {
+        let contrast = check_contrast(&preset.theme, &HEADER_TEXT_COLOR).unwrap();
+        assert!(contrast.readable, "Built-in preset '{}' is hard to read", preset.name);
+    }
+    assert!(check_contrast(&Theme::Image { path: "background.png".into(), fit: ImageFit::Cover }, &HEADER_TEXT_COLOR).is_none());
+}
+
+#[test]
+fn test_custom_presets() {
+    create_dir_all("test_theme_presets").expect("Failed to create test directory");
+    let theme = Theme::Solid { color: ThemeColor::HSL { h: 200, s: 50, l: 30 } };
+    save_custom_preset("test_theme_presets/presets", &ThemePreset::new("My Theme", theme.clone())).expect("Failed to save preset");
+    assert_eq!(load_custom_presets("test_theme_presets/presets"), vec![ThemePreset::new("My Theme", theme.clone())]);
+
+    let e = save_custom_preset("test_theme_presets/presets", &ThemePreset::new("Azure", theme.clone())).expect_err("Expect error");
+    let ThemeErrorType::BuiltinPreset(name) = e.err_type else {
+        panic!("Expect BuiltinPreset, got {:?}", e.err_type);
+    };
+    assert_eq!(name, "Azure");
+    let e = save_custom_preset("test_theme_presets/presets", &ThemePreset::new("../escape", theme.clone())).expect_err("Expect error");
+    let ThemeErrorType::InvalidName(_) = e.err_type else {
+        panic!("Expect InvalidName, got {:?}", e.err_type);
+    };
+
+    // export and import a preset shared by others
+    ThemePreset::new("Shared Theme", theme.clone()).to_file("test_theme_presets/shared.json").expect("Failed to export preset");
+    let imported = import_preset("test_theme_presets/presets", "test_theme_presets/shared.json").expect("Failed to import preset");
+    assert_eq!(imported.name, "Shared Theme");
+    assert_eq!(load_custom_presets("test_theme_presets/presets").len(), 2);
+
+    remove_custom_preset("test_theme_presets/presets", "My Theme").expect("Failed to remove preset");
+    assert!(remove_custom_preset("test_theme_presets/presets", "My Theme").is_err());
+    assert_eq!(load_custom_presets("test_theme_presets/presets"), vec![imported]);
+    remove_dir_all("test_theme_presets").expect("Failed to remove test directory");
+}
@@ -1,3 +1,7 @@
+use std::fs::{copy, create_dir_all, remove_dir_all};
+use image::{GenericImageView, ImageFormat, Rgba, RgbaImage, load_from_memory};
+use base64::prelude::*;
+use rapid_toolbox_lib::config::structure::{AppKind, DEFAULT_ICON_MAX_SIZE, IconFormat, IconSettings, MAX_ICON_MAX_SIZE, MIN_ICON_MAX_SIZE};
 use rapid_toolbox_lib::util::*;
 
 #[test]
@@ -10,9 +14,76 @@ fn test_encode_image_url_from_file() {
     encode_image_url_from_file("non_existent_image.jpg").expect_err("Expect error");
 }
 
+fn decode_data_url(data_url: &str, mime_type: &str) -> Vec<u8> {
+    let data = data_url.strip_prefix(&format!("data:{};base64,", mime_type)).expect("Unexpected mime type");
+    BASE64_STANDARD.decode(data).expect("Invalid base64 data")
+}
+
+#[test]
+fn test_sniff_image_type() {
+    create_dir_all("test_sniff_image").expect("Failed to create test directory");
+    // the content decides the type, not the extension
+    copy("tests/encode_image.jpg", "test_sniff_image/mislabeled.png").expect("Failed to copy image");
+    let data_url = encode_image_url_from_file("test_sniff_image/mislabeled.png").expect("Failed to encode data URL from file");
+    assert!(data_url.starts_with("data:image/jpeg;base64,"));
+    assert_eq!(sniff_image_type(b"\xEF\xBB\xBF  <?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>"), Some("image/svg+xml"));
+    assert_eq!(sniff_image_type(b"<html><body></body></html>"), None);
+    assert_eq!(sniff_image_type(b"<!-- drawn by hand --><!DOCTYPE svg>\n<svg/>"), Some("image/svg+xml"));
+    // markup that only embeds an svg is not one
+    assert_eq!(sniff_image_type(b"<html><body><svg></svg></body></html>"), None);
+    assert_eq!(sniff_image_type(b"<?xml version=\"1.0\"?><svgfoo></svgfoo>"), None);
+
+    let err = encode_image_url_from_file("Cargo.toml").expect_err("Expect error");
+    assert!(matches!(err.err_type, IconErrorType::UnsupportedFormat));
+    let err = encode_icon_url_from_file("Cargo.toml", &IconSettings::default()).expect_err("Expect error");
+    assert!(matches!(err.err_type, IconErrorType::UnsupportedFormat));
+    remove_dir_all("test_sniff_image").expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_normalize_icon() {
+    create_dir_all("test_normalize_icon").expect("Failed to create test directory");
+    let mut image = RgbaImage::from_pixel(1024, 512, Rgba([0x28, 0x54, 0xB5, 0xFF]));
+    image.put_pixel(0, 0, Rgba([0, 0, 0, 0]));
+    image.save_with_format("test_normalize_icon/large.png", ImageFormat::Png).expect("Failed to save image");
+
+    // scaled down to fit, keeping the aspect ratio and the transparency
+    let data_url = encode_icon_url_from_file("test_normalize_icon/large.png", &IconSettings::default()).expect("Failed to load icon");
+    let icon = load_from_memory(&decode_data_url(&data_url, "image/png")).expect("Failed to decode icon");
+    assert_eq!(icon.dimensions(), (256, 128));
+    assert!(icon.color().has_alpha());
+
+    let settings = IconSettings { max_size: 64, format: IconFormat::WebP, ..Default::default() };
+    let data_url = encode_icon_url_from_file("test_normalize_icon/large.png", &settings).expect("Failed to load icon");
+    let icon = load_from_memory(&decode_data_url(&data_url, "image/webp")).expect("Failed to decode icon");
+    assert_eq!(icon.dimensions(), (64, 32));
+
+    // small icons keep their size, but are converted
+    let data_url = encode_icon_url_from_file("tests/encode_image.jpg", &IconSettings::default()).expect("Failed to load icon");
+    let icon = load_from_memory(&decode_data_url(&data_url, "image/png")).expect("Failed to decode icon");
+    assert_eq!(icon.dimensions(), (64, 64));
+    assert!(!icon.color().has_alpha());
+    remove_dir_all("test_normalize_icon").expect("Failed to remove test directory");
+
+    // sizes out of range in hand-edited configs are clamped on load
+    let settings: IconSettings = serde_json::from_str(r#"{ "max_size": 100000 }"#).unwrap();
+    assert_eq!(settings.max_size, MAX_ICON_MAX_SIZE);
+    let settings: IconSettings = serde_json::from_str(r#"{ "max_size": 0 }"#).unwrap();
+    assert_eq!(settings.max_size, MIN_ICON_MAX_SIZE);
+    let settings: IconSettings = serde_json::from_str("{}").unwrap();
+    assert_eq!(settings.max_size, DEFAULT_ICON_MAX_SIZE);
+}
+
+#[test]
+fn test_default_icon_url() {
+    let folder_icon = default_icon_url(&AppKind::Folder);
+    assert!(folder_icon.starts_with("data:image/svg+xml;base64,"));
+    assert_ne!(folder_icon, default_icon_url(&AppKind::Url));
+}
+
 #[cfg(windows)]
 #[test]
 fn test_encode_image_url_from_app_icon() {
-    println!("{}", encode_image_url_from_app_icon("Cargo.toml").expect("Failed to extract app icon"));
-    assert_eq!(encode_image_url_from_app_icon("non_existent_file.txt"), None);
+    println!("{}", encode_image_url_from_app_icon("Cargo.toml", &IconSettings::default()).expect("Failed to extract app icon"));
+    assert_eq!(encode_image_url_from_app_icon("non_existent_file.txt", &IconSettings::default()), None);
 }
\ No newline at end of file
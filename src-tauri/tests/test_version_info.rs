@@ -0,0 +1,57 @@
+use std::fs::{read, remove_file, write};
+use rapid_toolbox_lib::version_info::*;
+
+#[test]
+fn test_read_version_info() {
+    let version_info = read_version_info("tests/version_info/versioned.exe").expect("Failed to read version info");
+    assert_eq!(version_info.file_version.as_deref(), Some("1.4.2.7"));
+    assert_eq!(version_info.product_version.as_deref(), Some("1.4.2.0"));
+    let table_keys: Vec<&str> = version_info.string_tables.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(table_keys, vec!["040904B0", "080404B0"]);
+
+    assert_eq!(version_info.string("ProductName", None), Some("Rapid Tool"));
+    assert_eq!(version_info.string("ProductName", lang_id_of("zh-CN")), Some("快捷工具"));
+    assert_eq!(version_info.string("FileDescription", lang_id_of("en")), Some("Rapid tool for testing"));
+    // empty values are taken from the other tables
+    assert_eq!(version_info.string("CompanyName", lang_id_of("zh-CN")), Some("WinTP Labs"));
+    assert_eq!(version_info.string("Comments", None), None);
+    assert_eq!(version_info.string("LegalCopyright", None), None);
+}
+
+#[test]
+fn test_read_version_info_error() {
+    let e = read_version_info("tests/version_info/unversioned.exe").unwrap_err();
+    let VersionInfoErrorType::NoVersionInfo = e.err_type else {
+        panic!("Expect NoVersionInfo, got {:?}", e.err_type);
+    };
+    let e = read_version_info("tests/encode_image.jpg").unwrap_err();
+    let VersionInfoErrorType::NotAPeFile = e.err_type else {
+        panic!("Expect NotAPeFile, got {:?}", e.err_type);
+    };
+    let e = read_version_info("tests/version_info/missing.exe").unwrap_err();
+    let VersionInfoErrorType::ReadError(_) = e.err_type else {
+        panic!("Expect ReadError, got {:?}", e.err_type);
+    };
+
+    // sections whose file offsets run past the end of the address space
+    let mut data = read("tests/version_info/versioned.exe").expect("Failed to read executable");
+    let pe_offset = u32::from_le_bytes(data[0x3C..0x40].try_into().unwrap()) as usize;
+    let section_count = u16::from_le_bytes(data[pe_offset + 6..pe_offset + 8].try_into().unwrap()) as usize;
+    let optional_header_size = u16::from_le_bytes(data[pe_offset + 20..pe_offset + 22].try_into().unwrap()) as usize;
+    for i in 0..section_count {
+        let section_pos = pe_offset + 24 + optional_header_size + i * 40;
+        data[section_pos + 8..section_pos + 12].copy_from_slice(&u32::MAX.to_le_bytes());
+        data[section_pos + 12..section_pos + 16].copy_from_slice(&0u32.to_le_bytes());
+        data[section_pos + 20..section_pos + 24].copy_from_slice(&u32::MAX.to_le_bytes());
+    }
+    write("test_version_info_corrupted.exe", &data).expect("Failed to write executable");
+    let e = read_version_info("test_version_info_corrupted.exe").unwrap_err();
+    let VersionInfoErrorType::Corrupted = e.err_type else {
+        panic!("Expect Corrupted, got {:?}", e.err_type);
+    };
+    remove_file("test_version_info_corrupted.exe").expect("Failed to remove test executable");
+
+    let data = read("tests/version_info/versioned.exe").expect("Failed to read executable");
+    assert!(parse_version_resource(&data).is_none());
+    assert!(parse_version_resource(b"\x06\0\0\0\0\0").is_none());
+}
@@ -0,0 +1,144 @@
+use std::fs::{copy, create_dir_all, remove_dir_all, remove_file, write as fs_write};
+use std::path::PathBuf;
+use rapid_toolbox_lib::config::Config;
+use rapid_toolbox_lib::config::structure::*;
+use rapid_toolbox_lib::versions::*;
+
+#[test]
+fn test_parse_version_output() {
+    assert_eq!(parse_version_output("tool v1.2.3-beta (build 45)").as_deref(), Some("1.2.3-beta"));
+    assert_eq!(parse_version_output("git version 2.39.2.windows.1").as_deref(), Some("2.39.2.windows.1"));
+    assert_eq!(parse_version_output("Python 3.12.1\n").as_deref(), Some("3.12.1"));
+    assert_eq!(parse_version_output("7-Zip 23.01 (x64) : Copyright (c) 1999-2023").as_deref(), Some("23.01"));
+    assert_eq!(parse_version_output("version: V10.0.").as_deref(), Some("10.0"));
+    assert_eq!(parse_version_output("Usage: tool [options] file..."), None);
+    assert_eq!(parse_version_output(""), None);
+}
+
+#[test]
+fn test_detect_version() {
+    let metadata = AppMetadata { app_path: PathBuf::from("tests/version_info/versioned.exe"), ..Default::default() };
+    assert_eq!(detect_version(&metadata, false), Some(("1.4.2".to_string(), VersionSource::VersionInfo)));
+    let metadata = AppMetadata { app_path: PathBuf::from("tests/version_info/unversioned.exe"), ..Default::default() };
+    assert_eq!(detect_version(&metadata, false), None);
+    let metadata = AppMetadata { app_path: PathBuf::from("tests/version_info/versioned.exe"), kind: AppKind::Document, ..Default::default() };
+    assert_eq!(detect_version(&metadata, true), None);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_probe_version() {
+    use std::fs::{Permissions, set_permissions};
+    use std::os::unix::fs::PermissionsExt;
+    let test_dir = "test_versions_probe";
+    create_dir_all(test_dir).expect("Failed to create test directory");
+    fs_write(format!("{}/tool.sh", test_dir), "#!/bin/sh\necho \"tool version 0.9.1\"\n").unwrap();
+    set_permissions(format!("{}/tool.sh", test_dir), Permissions::from_mode(0o755)).unwrap();
+    let metadata = AppMetadata {
+        app_path: PathBuf::from(format!("{}/tool.sh", test_dir)),
+        working_dir: PathBuf::from(test_dir),
+        ..Default::default()
+    };
+    assert_eq!(probe_version(&metadata).as_deref(), Some("0.9.1"));
+    assert_eq!(detect_version(&metadata, true), Some(("0.9.1".to_string(), VersionSource::Probe)));
+    assert_eq!(detect_version(&metadata, false), None);
+
+    // apps failing the integrity policy are not run
+    let mut config = Config::new();
+    config.add_app("tool", metadata).unwrap();
+    config.integrity_policy = IntegrityPolicy::Enforce;
+    let (changes, scans) = scan_app_files(&config, true);
+    assert_eq!(changes[0].new_version.as_deref(), Some(""));
+    assert_eq!(scans[0].version_source, VersionSource::Manual);
+    config.integrity_policy = IntegrityPolicy::Off;
+    let (changes, _) = scan_app_files(&config, true);
+    assert_eq!(changes[0].new_version.as_deref(), Some("0.9.1"));
+    remove_dir_all(test_dir).expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_take_snapshot() {
+    let test_dir = "test_versions_snapshot";
+    create_dir_all(test_dir).expect("Failed to create test directory");
+    let path = PathBuf::from(format!("{}/tool.exe", test_dir));
+    fs_write(&path, b"tool content").unwrap();
+    let snapshot = take_snapshot(&path, None, "1.0".to_string()).expect("Failed to take snapshot");
+    assert_eq!(snapshot.size, 12);
+    assert_eq!(snapshot.sha256.len(), 64);
+    assert_eq!(snapshot.version, "1.0");
+    assert!(snapshot.modified.is_some());
+
+    // the hash is reused as long as the size and modification time are the same
+    let previous = AppSnapshot { sha256: "cached".to_string(), ..snapshot.clone() };
+    assert_eq!(take_snapshot(&path, Some(&previous), "1.0".to_string()).unwrap().sha256, "cached");
+    let previous = AppSnapshot { size: 1, ..previous };
+    assert_eq!(take_snapshot(&path, Some(&previous), "1.0".to_string()).unwrap().sha256, snapshot.sha256);
+
+    take_snapshot(&PathBuf::from(format!("{}/missing.exe", test_dir)), None, String::new()).expect_err("Expect error");
+    remove_dir_all(test_dir).expect("Failed to remove test directory");
+}
+
+#[test]
+fn test_scan_versions() {
+    let test_dir = "test_versions_scan";
+    create_dir_all(test_dir).expect("Failed to create test directory");
+    copy("tests/version_info/versioned.exe", format!("{}/versioned.exe", test_dir)).unwrap();
+    fs_write(format!("{}/script.bat", test_dir), b"@echo off").unwrap();
+    let mut config = Config::new();
+    config.add_app("versioned", AppMetadata {
+        app_path: PathBuf::from(format!("{}/versioned.exe", test_dir)),
+        working_dir: PathBuf::from(test_dir),
+        ..Default::default()
+    }).unwrap();
+    config.add_app("script", AppMetadata {
+        app_path: PathBuf::from(format!("{}/script.bat", test_dir)),
+        working_dir: PathBuf::from(test_dir),
+        version: "2.0".to_string(),
+        ..Default::default()
+    }).unwrap();
+    config.add_app("website", AppMetadata { app_path: PathBuf::from("https://example.com"), kind: AppKind::Url, ..Default::default() }).unwrap();
+
+    let changes = scan_versions(&mut config, false);
+    assert_eq!(changes, vec![
+        AppVersionChange { app_name: "script".to_string(), change_type: VersionChangeType::New, old_version: None, new_version: Some("2.0".to_string()) },
+        AppVersionChange { app_name: "versioned".to_string(), change_type: VersionChangeType::New, old_version: None, new_version: Some("1.4.2".to_string()) }
+    ]);
+    let versioned = config.get_app("versioned").unwrap();
+    assert_eq!(versioned.version, "1.4.2");
+    assert_eq!(versioned.version_source, VersionSource::VersionInfo);
+    // versions typed in are kept
+    assert_eq!(config.get_app("script").unwrap().version_source, VersionSource::Manual);
+    assert!(config.get_app("website").unwrap().snapshot.is_none());
+
+    assert_eq!(scan_versions(&mut config, false), vec![]);
+
+    fs_write(format!("{}/script.bat", test_dir), b"@echo off\necho updated").unwrap();
+    assert_eq!(scan_versions(&mut config, false), vec![
+        AppVersionChange { app_name: "script".to_string(), change_type: VersionChangeType::Updated, old_version: Some("2.0".to_string()), new_version: Some("2.0".to_string()) }
+    ]);
+
+    remove_file(format!("{}/versioned.exe", test_dir)).unwrap();
+    assert_eq!(scan_versions(&mut config, false), vec![
+        AppVersionChange { app_name: "versioned".to_string(), change_type: VersionChangeType::Missing, old_version: Some("1.4.2".to_string()), new_version: None }
+    ]);
+    // the last snapshot is kept for the missing app
+    assert_eq!(config.get_app("versioned").unwrap().snapshot.as_ref().map(|snapshot| snapshot.version.as_str()), Some("1.4.2"));
+
+    // scans of apps pointed to another file meanwhile are dropped
+    let previous = config.get_app("script").unwrap().snapshot.clone();
+    fs_write(format!("{}/script.bat", test_dir), b"@echo off\necho updated again").unwrap();
+    let (_, scans) = scan_app_files(&config, false);
+    config.update_app("script", AppMetadata { app_path: PathBuf::from("other.bat"), ..config.get_app("script").unwrap().clone() }).unwrap();
+    apply_version_scans(&mut config, scans);
+    assert_eq!(config.get_app("script").unwrap().snapshot, previous);
+
+    // and so are the scans of apps whose version was typed in meanwhile
+    let script_path = PathBuf::from(format!("{}/script.bat", test_dir));
+    config.update_app("script", AppMetadata { app_path: script_path, ..config.get_app("script").unwrap().clone() }).unwrap();
+    let (_, scans) = scan_app_files(&config, false);
+    config.update_app("script", AppMetadata { version: String::from("3.0"), ..config.get_app("script").unwrap().clone() }).unwrap();
+    apply_version_scans(&mut config, scans);
+    assert_eq!(config.get_app("script").unwrap().version, "3.0");
+    assert_eq!(config.get_app("script").unwrap().snapshot, previous);
+    remove_dir_all(test_dir).expect("Failed to remove test directory");
+}
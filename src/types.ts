@@ -7,6 +7,16 @@ export interface AppMetadata {
     workingDir: string;
     desc: string;
     iconUrl: string;
+    tags?: string[];
+    hooks?: LaunchHooks;
+    captureOutput?: boolean;
+    launchPolicy?: LaunchPolicy;
+    kind?: AppKind;
+    generatedIcon?: boolean;
+    version?: string;
+    versionSource?: VersionSource;
+    publisher?: string;
+    snapshot?: AppSnapshot;
 }
 
 export interface DnDItem {
@@ -26,7 +36,27 @@ export interface ThemeColorHSL {
     s: number;
     l: number;
 }
-export type ThemeColor = ThemeColorRGB | ThemeColorHSL;
+// alpha is a percentage
+export interface ThemeColorRGBA {
+    type: "RGBA";
+    r: number;
+    g: number;
+    b: number;
+    a: number;
+}
+export interface ThemeColorHSLA {
+    type: "HSLA";
+    h: number;
+    s: number;
+    l: number;
+    a: number;
+}
+export type ThemeColor = ThemeColorRGB | ThemeColorHSL | ThemeColorRGBA | ThemeColorHSLA;
+
+export interface ColorStop {
+    color: ThemeColor;
+    position: number;
+}
 
 export interface ThemeSolid {
     type: "Solid";
@@ -34,15 +64,32 @@ export interface ThemeSolid {
 }
 export interface ThemeLinearGradient {
     type: "LinearGradient";
-    from: ThemeColor;
-    to: ThemeColor;
+    stops: ColorStop[];
+    angle: number;
 }
 export interface ThemeRadialGradient {
     type: "RadialGradient";
-    from: ThemeColor;
-    to: ThemeColor;
+    stops: ColorStop[];
+}
+export interface ThemeImage {
+    type: "Image";
+    path: string;
+    fit: "Cover" | "Contain" | "Tile";
+}
+export type Theme = ThemeSolid | ThemeLinearGradient | ThemeRadialGradient | ThemeImage;
+
+export interface ThemePreset {
+    name: string;
+    theme: Theme;
+    builtin?: boolean;
+}
+
+export interface ThemeContrast {
+    minRatio: number;
+    averageRatio: number;
+    readable: boolean;
+    hasUnreadableStop: boolean;
 }
-export type Theme = ThemeSolid | ThemeLinearGradient | ThemeRadialGradient;
 
 export interface ConfigBasicInfo {
     lang: string;
@@ -50,4 +97,180 @@ export interface ConfigBasicInfo {
     author: string | null;
     toolboxVersion: [number, number] | null;
     theme: Theme;
-}
\ No newline at end of file
+    darkTheme?: Theme | null;
+}
+
+export interface FieldChange {
+    field: string;
+    oldValue: unknown;
+    newValue: unknown;
+}
+
+export type CategoryChangeType = "Added" | "Removed" | "Changed";
+
+export interface CategoryChange {
+    name: string;
+    changeType: CategoryChangeType;
+    oldParent: string | null;
+    newParent: string | null;
+    addedApps: string[];
+    removedApps: string[];
+    appsReordered: boolean;
+    childrenReordered: boolean;
+}
+
+export type MergeTarget = "Setting" | "App" | "Category" | "CategoryOrder" | "SmartCategory" | "LaunchGroup" | "Favorites";
+
+export interface MergeDecision {
+    target: MergeTarget;
+    name: string;
+    action: "KeptOurs" | "TookTheirs" | "Combined" | "Removed";
+}
+
+export interface MergeConflict {
+    target: MergeTarget;
+    name: string;
+    conflictType: "BothModified" | "ModifiedAndRemoved" | "BothReordered" | "BothMoved" | "MissingApp" | "NameTaken";
+    fields: { field: string; baseValue: unknown; oursValue: unknown; theirsValue: unknown }[];
+}
+
+export interface MergeReport {
+    decisions: MergeDecision[];
+    conflicts: MergeConflict[];
+}
+
+export interface ConfigDiff {
+    oldVersion: [number, number] | null;
+    newVersion: [number, number] | null;
+    settings: FieldChange[];
+    addedApps: string[];
+    removedApps: string[];
+    renamedApps: { oldName: string; newName: string }[];
+    changedApps: { name: string; fields: FieldChange[] }[];
+    categoriesReordered: boolean;
+    categories: CategoryChange[];
+}
+export type IntegrityPolicy = "Off" | "Confirm" | "Enforce";
+
+export interface IntegrityStatus {
+    type: "Valid" | "Unlisted" | "Missing" | "PathChanged" | "Modified" | "DirectoryModified";
+}
+
+export interface SignatureStatus {
+    type: "Unsigned" | "Valid" | "Tampered";
+}
+
+export interface EditHistoryState {
+    canUndo: boolean;
+    canRedo: boolean;
+}
+
+export interface SmartCategoryQuery {
+    tags: string[];
+    path_pattern: string | null;
+    name_contains: string | null;
+    file_types: string[];
+}
+
+export interface SmartCategory {
+    name: string;
+    query: SmartCategoryQuery;
+}
+
+export interface CategoryTreeNode {
+    name: string;
+    children: CategoryTreeNode[];
+}
+
+export type SearchField =
+    | { type: "Name" }
+    | { type: "Description" }
+    | { type: "Tag"; index: number }
+    | { type: "FileName" };
+
+export interface SearchMatch {
+    field: SearchField;
+    score: number;
+    // char offsets, end exclusive
+    ranges: { start: number; end: number }[];
+}
+
+export interface AppSearchResult {
+    app: AppMetadata;
+    matches: SearchMatch[];
+}
+
+// internal name of the virtual category holding the pinned apps, see get_category_list
+export const FAVORITES_CATEGORY = "__favorites__";
+
+export interface LaunchStep {
+    app: string;
+    delay_ms?: number;
+    wait_for_previous?: boolean;
+}
+
+export interface LaunchGroup {
+    name: string;
+    steps: LaunchStep[];
+}
+
+export type LaunchStatus =
+    | { type: "Launched" | "AppNotExist" | "FileNotExist" }
+    | { type: "IntegrityCheckFailed"; status: IntegrityStatus }
+    | { type: "SpawnFailed" | "PreLaunchHookFailed"; reason: string }
+    | { type: "AlreadyRunning"; ask: boolean; focused: boolean };
+
+export interface LaunchStepResult {
+    appName: string;
+    status: LaunchStatus;
+}
+
+export const DEFAULT_HOOK_TIMEOUT_MS = 30000;
+
+export interface LaunchHook {
+    command: string;
+    timeout_ms?: number;
+    abort_on_failure?: boolean;
+}
+
+export interface LaunchHooks {
+    pre_launch?: LaunchHook | null;
+    post_exit?: LaunchHook | null;
+}
+
+export interface AppLogInfo {
+    fileName: string;
+    size: number;
+    modified: string;
+}
+
+export type LaunchPolicy = "AllowMultiple" | "SingleInstance" | "Ask";
+
+export type AppKind = "Executable" | "Document" | "Folder" | "Url";
+
+export type VersionSource = "Manual" | "VersionInfo" | "Probe";
+
+export interface AppSnapshot {
+    size: number;
+    sha256: string;
+    modified?: number;
+    version: string;
+}
+
+export type VersionChangeType = "New" | "Updated" | "Missing";
+
+export interface AppVersionChange {
+    appName: string;
+    changeType: VersionChangeType;
+    oldVersion?: string;
+    newVersion?: string;
+}
+
+export const DEFAULT_ICON_MAX_SIZE = 256;
+
+export interface IconSettings {
+    max_size: number;
+    format: "Png" | "WebP";
+    svg_mode: "Rasterize" | "Sanitize";
+    fallback_color: "Name" | "Theme";
+}
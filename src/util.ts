@@ -1,6 +1,7 @@
 import { useConfirm } from "primevue/useconfirm";
 import { useI18n } from "vue-i18n";
-import { Theme, ThemeColor } from "./types";
+import { invoke } from "@tauri-apps/api/core";
+import { ColorStop, FAVORITES_CATEGORY, LaunchStatus, Theme, ThemeColor } from "./types";
 
 type MessageDialogIcon = "info" | "warning" | "error" | "success";
 
@@ -44,23 +45,90 @@ export function useMessageDialog() {
     return messageDialog;
 }
 
+// the name to show for the category, localized for the virtual favorites category
+export function useCategoryLabel() {
+    const { t } = useI18n();
+    return (category: string) => category === FAVORITES_CATEGORY ? t('CategoryList.favorites') : category;
+}
+
+// launch the app following its launch policy, resolving to false if the app failed to launch
+export function useLaunchApp() {
+    const confirm = useConfirm();
+    const messageDialog = useMessageDialog();
+    const { t } = useI18n();
+    const launchApp = async (appName: string, allowAnother = false): Promise<boolean> => {
+        const status = await invoke<LaunchStatus>("launch_app", { appName, allowAnother });
+        if (status.type !== "AlreadyRunning") {
+            return status.type === "Launched";
+        }
+        if (status.ask) {
+            return new Promise(resolve => confirm.require({
+                message: t('LaunchApp.msgAskLaunchAnother', [appName]),
+                header: t('LaunchApp.titleAlreadyRunning'),
+                icon: "pi pi-question-circle",
+                rejectLabel: t('DialogCommon.btnCancel'),
+                rejectProps: {
+                    severity: "secondary",
+                    outlined: true,
+                    size: "small"
+                },
+                acceptLabel: t('LaunchApp.btnLaunchAnother'),
+                acceptProps: {
+                    size: "small"
+                },
+                async accept() {
+                    resolve(await launchApp(appName, true));
+                },
+                reject() {
+                    resolve(true);
+                },
+                onHide() {
+                    resolve(true);
+                }
+            }));
+        }
+        // the running instance is brought to the front if it has a window, otherwise tell the user
+        if (!status.focused) {
+            messageDialog(t('LaunchApp.titleAlreadyRunning'), t('LaunchApp.msgAlreadyRunning', [appName]));
+        }
+        return true;
+    };
+    return launchApp;
+}
+
 export function getThemeColorCssValue(color: ThemeColor) {
     switch (color.type) {
         case "RGB":
             return `rgb(${color.r}, ${color.g}, ${color.b})`;
         case "HSL":
             return `hsl(${color.h}, ${color.s}%, ${color.l}%)`;
+        case "RGBA":
+            return `rgb(${color.r} ${color.g} ${color.b} / ${color.a}%)`;
+        case "HSLA":
+            return `hsl(${color.h} ${color.s}% ${color.l}% / ${color.a}%)`;
     }
 }
 
-export function getThemeStyle(theme: Theme) {
+function getColorStopsCssValue(stops: ColorStop[]) {
+    return stops.map(stop => `${getThemeColorCssValue(stop.color)} ${stop.position}%`).join(", ");
+}
+
+// imageUrl is the data url of the background image for Image themes, see load_theme_image
+export function getThemeStyle(theme: Theme, imageUrl?: string) {
     switch (theme.type) {
         case "Solid":
             return { backgroundColor: getThemeColorCssValue(theme.color) };
         case "LinearGradient":
-            return { background: `linear-gradient(to top right, ${getThemeColorCssValue(theme.from)}, ${getThemeColorCssValue(theme.to)})` };
+            return { background: `linear-gradient(${theme.angle}deg, ${getColorStopsCssValue(theme.stops)})` };
         case "RadialGradient":
-            return { background: `radial-gradient(circle, ${getThemeColorCssValue(theme.from)} 0%, ${getThemeColorCssValue(theme.to)} 100%)` };
+            return { background: `radial-gradient(circle, ${getColorStopsCssValue(theme.stops)})` };
+        case "Image":
+            return {
+                backgroundImage: imageUrl ? `url("${imageUrl}")` : "none",
+                backgroundSize: theme.fit === "Tile" ? "auto" : theme.fit.toLowerCase(),
+                backgroundRepeat: theme.fit === "Tile" ? "repeat" : "no-repeat",
+                backgroundPosition: "center"
+            };
     }
 }
 
@@ -69,9 +137,11 @@ export function cloneTheme(theme: Theme): Theme {
         case "Solid":
             return { type: "Solid", color: { ...theme.color } };
         case "LinearGradient":
-            return { type: "LinearGradient", from: { ...theme.from }, to: { ...theme.to } };
+            return { type: "LinearGradient", stops: theme.stops.map(stop => ({ color: { ...stop.color }, position: stop.position })), angle: theme.angle };
         case "RadialGradient":
-            return { type: "RadialGradient", from: { ...theme.from }, to: { ...theme.to } };
+            return { type: "RadialGradient", stops: theme.stops.map(stop => ({ color: { ...stop.color }, position: stop.position })) };
+        case "Image":
+            return { ...theme };
     }
 }
 